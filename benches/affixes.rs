@@ -0,0 +1,47 @@
+mod mock_hashers;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use passwordmaker_rs::HashAlgorithm;
+use mock_hashers::Pwm;
+
+const CHARACTERS : &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+
+fn criterion_bench_long_suffix(c: &mut Criterion) {
+    let suffix = "A long suffix, that is way longer than the password. 👍🏽".repeat(200);
+    c.bench_function("new per site with long suffix", |b| b.iter(|| {
+        let pwm = Pwm::new(
+            HashAlgorithm::Md5, 
+            passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+            CHARACTERS,
+            "",
+            "",
+            12,
+            "",
+            black_box(&suffix)
+        ).unwrap();
+        pwm.generate(black_box("example.com"), black_box("password"))
+    }));
+}
+
+fn criterion_bench_long_prefix(c: &mut Criterion) {
+    let prefix = "A long prefix, that is way longer than the password. 👍🏽".repeat(200);
+    c.bench_function("new per site with long prefix", |b| b.iter(|| {
+        let pwm = Pwm::new(
+            HashAlgorithm::Md5, 
+            passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+            CHARACTERS,
+            "",
+            "",
+            12,
+            black_box(&prefix),
+            ""
+        ).unwrap();
+        pwm.generate(black_box("example.com"), black_box("password"))
+    }));
+}
+
+criterion_group!(benches,
+    criterion_bench_long_suffix,
+    criterion_bench_long_prefix
+);
+criterion_main!(benches);
@@ -25,6 +25,44 @@ fn criterion_bench_16bytes_typical(c: &mut Criterion) {
     }));
 }
 
+fn criterion_bench_16bytes_typical_short(c: &mut Criterion) {
+    let pwm = Pwm::new(
+        HashAlgorithm::Md5, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./",
+        "",
+        "",
+        8,
+        "",
+        ""
+    ).unwrap();
+    c.bench_function("16 bytes typical short", |b| b.iter(|| {
+        pwm.generate(
+            black_box("This is a long string. With many, many characters. For no particular reason.".to_owned()),
+            black_box("And another relatively long string for no reason other than it being long.".to_owned())
+        )
+    }));
+}
+
+fn criterion_bench_16bytes_typical_long(c: &mut Criterion) {
+    let pwm = Pwm::new(
+        HashAlgorithm::Md5, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./",
+        "",
+        "",
+        16,
+        "",
+        ""
+    ).unwrap();
+    c.bench_function("16 bytes typical long", |b| b.iter(|| {
+        pwm.generate(
+            black_box("This is a long string. With many, many characters. For no particular reason.".to_owned()),
+            black_box("And another relatively long string for no reason other than it being long.".to_owned())
+        )
+    }));
+}
+
 fn criterion_bench_16bytes_full_divide(c: &mut Criterion) {
     let pwm = Pwm::new(
         HashAlgorithm::Md5, 
@@ -67,6 +105,8 @@ criterion_group!(name = benches;
     // This can be any expression that returns a `Criterion` object.
     config = Criterion::default().significance_level(0.02).sample_size(500).measurement_time(Duration::from_secs(10));
     targets = criterion_bench_16bytes_typical,
+    criterion_bench_16bytes_typical_short,
+    criterion_bench_16bytes_typical_long,
     criterion_bench_16bytes_full_divide,
     criterion_bench_16bytes_worst_case,
 );
@@ -44,10 +44,50 @@ fn criterion_bench_16bytes_hmac_short_key(c: &mut Criterion) {
     }));
 }
 
+fn criterion_bench_16bytes_hmac_long_key_multi_part(c: &mut Criterion) {
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacMd5,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./",
+        "",
+        "",
+        80,
+        "",
+        ""
+    ).unwrap();
+    c.bench_function("16 bytes HMAC long key multi part", |b| b.iter(|| {
+        pwm.generate(
+            black_box("This is a long string. With many, many characters. For no particular reason.".to_owned()),
+            black_box("And another relatively long string for no reason other than it being long.".to_owned())
+        )
+    }));
+}
+
+fn criterion_bench_16bytes_hmac_short_key_multi_part(c: &mut Criterion) {
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacMd5,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./",
+        "",
+        "",
+        80,
+        "",
+        ""
+    ).unwrap();
+    c.bench_function("16 bytes HMAC short key multi part", |b| b.iter(|| {
+        pwm.generate(
+            black_box("This is a long string. With many, many characters. For no particular reason.".to_owned()),
+            black_box("Short Key".to_owned())
+        )
+    }));
+}
+
 criterion_group!(name = benches;
     // This can be any expression that returns a `Criterion` object.
     config = Criterion::default().significance_level(0.02).sample_size(500).measurement_time(Duration::from_secs(10));
     targets = criterion_bench_16bytes_hmac_long_key,
     criterion_bench_16bytes_hmac_short_key,
+    criterion_bench_16bytes_hmac_long_key_multi_part,
+    criterion_bench_16bytes_hmac_short_key_multi_part,
 );
 criterion_main!(benches);
@@ -0,0 +1,34 @@
+mod mock_hashers;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use passwordmaker_rs::{HashAlgorithm, PreparedCharset};
+use mock_hashers::Pwm;
+
+//There are no precomputed max powers for character sets this large. Compare with and without the cached_max_powers feature
+//(cargo bench --bench large_charset --no-default-features --features precomputed_common_max_powers) to see the effect of the cache.
+fn characters() -> String {
+    //Latin Extended-A and -B. Each of those code points is a grapheme cluster of its own.
+    let characters : String = (0x100..0x1c8).filter_map(std::char::from_u32).collect();
+    assert_eq!(characters.chars().count(), 200);
+    characters
+}
+
+fn criterion_bench_200_symbols(c: &mut Criterion) {
+    let characters = PreparedCharset::new(characters()).unwrap();
+    c.bench_function("Sha256 with 200 symbols, 512 characters", |b| b.iter(|| {
+        let pwm = Pwm::new_with_prepared_charset(
+            HashAlgorithm::Sha256,
+            passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+            black_box(&characters),
+            "",
+            "",
+            512,
+            "",
+            ""
+        );
+        pwm.generate(black_box("example.com"), black_box("password"))
+    }));
+}
+
+criterion_group!(benches, criterion_bench_200_symbols);
+criterion_main!(benches);
@@ -23,6 +23,26 @@ fn criterion_bench_16bytes_post_leet(c: &mut Criterion) {
     }));
 }
 
+fn criterion_bench_16bytes_post_leet_two_characters(c: &mut Criterion) {
+    //Worst case: Every digit is a single character, and each of them expands to several characters with leet.
+    let pwm = Pwm::new(
+        HashAlgorithm::Md5, 
+        passwordmaker_rs::UseLeetWhenGenerating::After { level: LeetLevel::Nine },
+        "mw",
+        "",
+        "",
+        150,
+        "",
+        ""
+    ).unwrap();
+    c.bench_function("16 bytes with post_leet, 2 characters", |b| b.iter(|| {
+        pwm.generate(
+            black_box("This is a long string. With many, many characters. For no particular reason.".to_owned()),
+            black_box("And another relatively long string for no reason other than it being long.".to_owned())
+        )
+    }));
+}
+
 fn criterion_bench_16bytes_pre_leet(c: &mut Criterion) {
     let pwm = Pwm::new(
         HashAlgorithm::Md5, 
@@ -44,6 +64,7 @@ fn criterion_bench_16bytes_pre_leet(c: &mut Criterion) {
 
 criterion_group!(benches,
     criterion_bench_16bytes_post_leet,
+    criterion_bench_16bytes_post_leet_two_characters,
     criterion_bench_16bytes_pre_leet
 );
 criterion_main!(benches);
@@ -1,7 +1,7 @@
 //We want to bench the surrounding string manipulation, not the hashers.
 //For this reason, we fake them with a black_box.
 
-use passwordmaker_rs::{PasswordMaker, Hasher, HasherList, };
+use passwordmaker_rs::{PasswordMaker, Extended, ExtendedHasherList, Hasher, HasherList, };
 use criterion::{black_box};
 
 
@@ -10,6 +10,8 @@ pub(crate) struct MockMd5;
 pub(crate) struct MockSha1;
 pub(crate) struct MockSha256;
 pub(crate) struct MockRipeMD160;
+pub(crate) struct MockRipeMD128;
+pub(crate) struct MockTiger;
 impl Hasher for MockMd4{
     type Output = [u8;16];
     fn hash(_data : &[u8]) -> Self::Output {
@@ -41,11 +43,19 @@ impl Hasher for MockRipeMD160{
     }
 }
 
-impl passwordmaker_rs::Md4 for MockMd4{}
-impl passwordmaker_rs::Md5 for MockMd5{}
-impl passwordmaker_rs::Sha1 for MockSha1{}
-impl passwordmaker_rs::Sha256 for MockSha256{}
-impl passwordmaker_rs::Ripemd160 for MockRipeMD160{}
+impl Hasher for MockRipeMD128{
+    type Output = [u8;16];
+    fn hash(_data : &[u8]) -> Self::Output {
+        black_box([219u8,4u8,123u8,54u8,91u8,85u8,34u8,159u8,243u8,210u8,35u8,41u8,31u8,34u8,75u8,94u8])
+    }
+}
+impl Hasher for MockTiger{
+    type Output = [u8;24];
+    fn hash(_data : &[u8]) -> Self::Output {
+        black_box([219u8,4u8,123u8,54u8,91u8,85u8,34u8,159u8,243u8,210u8,35u8,41u8,31u8,34u8,75u8,94u8,46,49,13,24,156u8,4u8,123u8,54u8])
+    }
+}
+
 
 pub(crate) struct MockHashes{}
 impl HasherList for MockHashes {
@@ -55,5 +65,9 @@ impl HasherList for MockHashes {
     type SHA256 = MockSha256;
     type RIPEMD160 = MockRipeMD160;
 }
+impl ExtendedHasherList for MockHashes {
+    type RIPEMD128 = MockRipeMD128;
+    type TIGER = MockTiger;
+}
 
-pub(crate) type Pwm<'a> = PasswordMaker<'a, MockHashes>;
\ No newline at end of file
+pub(crate) type Pwm<'a> = PasswordMaker<'a, Extended<MockHashes>>;
\ No newline at end of file
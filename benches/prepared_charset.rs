@@ -0,0 +1,46 @@
+mod mock_hashers;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use passwordmaker_rs::{HashAlgorithm, PreparedCharset};
+use mock_hashers::Pwm;
+
+const CHARACTERS : &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+
+fn criterion_bench_per_site_new(c: &mut Criterion) {
+    c.bench_function("new per site", |b| b.iter(|| {
+        let pwm = Pwm::new(
+            HashAlgorithm::Md5, 
+            passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+            black_box(CHARACTERS),
+            "",
+            "",
+            12,
+            "",
+            ""
+        ).unwrap();
+        pwm.generate(black_box("example.com"), black_box("password"))
+    }));
+}
+
+fn criterion_bench_per_site_prepared_charset(c: &mut Criterion) {
+    let characters = PreparedCharset::new(CHARACTERS).unwrap();
+    c.bench_function("new_with_prepared_charset per site", |b| b.iter(|| {
+        let pwm = Pwm::new_with_prepared_charset(
+            HashAlgorithm::Md5, 
+            passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+            black_box(&characters),
+            "",
+            "",
+            12,
+            "",
+            ""
+        );
+        pwm.generate(black_box("example.com"), black_box("password"))
+    }));
+}
+
+criterion_group!(benches,
+    criterion_bench_per_site_new,
+    criterion_bench_per_site_prepared_charset
+);
+criterion_main!(benches);
@@ -21,7 +21,7 @@
 //! # Features
 //! The library comes with a set of precomputed powers to (slightly) speed up computation in common use cases. By default, constants
 //! for the lengths of the pre-defined character sets of PasswordMaker Pro are included (10, 16, 32, 52, 62, 94), amounting to a total
-//! of 360 bytes on a 32bit machine, and 408 bytes on a 64bit machine (and some instructions to read them). For all other character
+//! of 528 bytes on a 32bit machine, and 600 bytes on a 64bit machine (and some instructions to read them). For all other character
 //! set lengths the values are computed at runtime when needed. Those values are in the (default-enabled)
 //! `precomputed_common_max_powers` feature.
 //! 
@@ -29,69 +29,336 @@
 //! computation for all values, at the cost of a slight performance impact.
 //! 
 //! On the other hand, if binary size is not of concern, you might want to enable the `precomputed_max_powers` feature.
-//! This feature enables precomputed powers for all bases in the range 2..130. It therefore needs 7680 bytes on a 32bit machine, and
-//! 8704 bytes on a 64bit machine (plus some extra instructions).
+//! This feature enables precomputed powers for all bases in the range 2..130. It therefore needs 11264 bytes on a 32bit machine, and
+//! 12800 bytes on a 64bit machine (plus some extra instructions).
+//! 
+//! Character sets longer than 129 grapheme clusters are never covered by the precomputed powers. The (default-enabled)
+//! `cached_max_powers` feature therefore caches the powers computed at runtime, per thread and per character set length, so they are
+//! only computed once for SHA-1, SHA-256 and Tiger (MD4, MD5 and RIPEMD-128 are cheap enough to not need a cache). Each thread
+//! remembers the 8 most recently used character set lengths per hash size, and drops older ones, so the cache takes about a
+//! kilobyte per thread, no matter how many different character sets it sees.
+//! 
+//! The optional `unicode-normalization` feature adds `PasswordMaker::with_unicode_normalization`, which brings `data` and `key`
+//! into a Unicode normalization form before hashing, using the [unicode-normalization](https://docs.rs/unicode-normalization) crate.
+//! 
+//! The optional `rayon` feature adds `PasswordMaker::generate_parallel`, which generates passwords for many inputs on the
+//! [rayon](https://docs.rs/rayon) thread pool. Beware that rayon needs a much more recent Rust version than this crate itself.
+//! 
+//! The optional `tracing` feature emits [tracing](https://docs.rs/tracing) spans for input validation (at `debug` level), URL parsing
+//! (`debug`) and the generation of each password part (`trace`). The spans only carry settings, like the hash algorithm, the
+//! password length, the number of output characters and the part index. The master password, the data, and the generated
+//! password are never recorded. Without the feature, there is no overhead.
+//! 
+//! # Not supported
+//! PasswordMaker Pro can store a hash of the master password, to warn users about typos. This crate does not offer that
+//! verification value: No reference of the algorithm PasswordMaker Pro uses for it was available, and a guessed algorithm would
+//! silently disagree with the values PasswordMaker Pro has stored. Applications that want such a warning need to store a hash
+//! of their own, for instance with a password hashing function.
+//! 
+//! The passwords this crate generates match those of the JavaScript edition of PasswordMaker Pro. There are no compatibility
+//! profiles for other editions, like the Android port or the Chrome extension: No passwords generated by those were available
+//! to test against. Where such an edition differs, the individual settings can be adjusted instead, for instance with
+//! [`PasswordMaker::with_part_counter_placement`], [`PasswordMaker::with_part_counter_separator`] and [`UrlParsing`].
+//! 
+//! # Forward compatibility
+//! The enums [`HashAlgorithm`], [`LeetLevel`], [`UseLeetWhenGenerating`], [`ProtocolUsageMode`], [`GenerationError`] and
+//! [`SettingsError`] are marked `#[non_exhaustive]`, so that new variants (for instance additional hash algorithms) can be added
+//! without a breaking change.
+//! When matching on them outside of this crate, a wildcard `_ =>` arm is required.
+//! 
+//! Migrating from version 0.2: Existing exhaustive matches on these enums fail to compile. Add a `_ =>` arm that handles
+//! unknown variants, for instance by reporting them as unsupported to the user.
+//! 
+//! The per-algorithm traits [`Md4`], [`Md5`], [`Sha1`], [`Sha256`] and [`Ripemd160`] are no longer
+//! needed, as [`HasherList`] only requires [`Hasher`]. Existing implementations of them still compile, but are deprecated, and
+//! can be removed.
 //! 
 //! # Warning
 //! This library has NOT been tested on 16bit machines. It might work, but probably does not.
 
 
 mod passwordmaker;
+mod settings;
 mod url_parsing;
-use passwordmaker::{PasswordPartParameters, PasswordAssemblyParameters};
+use passwordmaker::{PasswordPartParameters, PasswordAssemblyParameters, split_into_graphemes, is_base_precomputed as is_base_precomputed_impl, validate_output_characters, validate_output_symbols};
 use passwordmaker::leet::LeetReplacementTable;
+use passwordmaker::grapheme::GraphemeList;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fmt::Display;
 use std::marker::PhantomData;
 
+/// Splits `characters` into grapheme clusters, the same way [`PasswordMaker`] does with its output characters.
+/// 
+/// Each grapheme cluster is one character of the generated password. This is meant to show users how their character set will be
+/// interpreted, as what looks like a single character can consist of several Unicode code points, and vice versa.
+/// 
+/// ```
+/// # use passwordmaker_rs::graphemes;
+/// //A family emoji is made of several emoji, joined with zero width joiners. Still it's a single grapheme cluster.
+/// assert_eq!(graphemes("a👨‍👩‍👧b").collect::<Vec<_>>(), ["a", "👨‍👩‍👧", "b"]);
+/// //A combining accent attaches to the character in front of it.
+/// assert_eq!(graphemes("ae\u{301}").collect::<Vec<_>>(), ["a", "e\u{301}"]);
+/// ```
+pub fn graphemes(characters : &str) -> impl Iterator<Item=&str> {
+    split_into_graphemes(characters)
+}
+
+/// Returns `charset` without the grapheme clusters that occur in `exclude`, for instance to remove characters that are
+/// problematic in shell scripts or HTML from a large character set. The remaining grapheme clusters keep their order.
+/// 
+/// Both strings are split into grapheme clusters the same way as by [`graphemes`], so excluding `"e"` does not remove
+/// `"e\u{301}"`, and excluding an emoji removes it as a whole. Pass the result as `characters` to [`PasswordMaker::new`].
+/// 
+/// ```
+/// # use passwordmaker_rs::exclude_from_charset;
+/// assert_eq!(exclude_from_charset("abc<>&\"'def", "<>&\"'"), "abcdef");
+/// assert_eq!(exclude_from_charset("a👍🏽e\u{301}e", "e👍🏽"), "ae\u{301}");
+/// ```
+#[must_use]
+pub fn exclude_from_charset(charset : &str, exclude : &str) -> String {
+    let excluded : Vec<&str> = split_into_graphemes(exclude).collect();
+    split_into_graphemes(charset).filter(|grapheme| !excluded.contains(grapheme)).collect()
+}
+
+/// Maps digit indices, as returned by [`PasswordMaker::generate_indices`], onto the grapheme clusters of `charset`.
+/// 
+/// This is the same mapping that [`PasswordMaker::generate`] does internally: Each index selects one grapheme cluster of `charset`,
+/// split the same way as by [`graphemes`], and the selected grapheme clusters are joined in the order of `indices`. Prefix,
+/// suffix and leet are not applied. This is meant to verify that exported indices still map to the expected password for a
+/// given charset.
+/// 
+/// ```
+/// # use passwordmaker_rs::{map_indices, MapError};
+/// assert_eq!(map_indices(&[2, 0, 1], "ab👍🏽"), Ok(String::from("👍🏽ab")));
+/// assert_eq!(map_indices(&[0, 3], "ab👍🏽"), Err(MapError::IndexOutOfRange { position : 1, index : 3, charset_length : 3 }));
+/// ```
+/// 
+/// # Errors
+/// Fails with [`MapError::IndexOutOfRange`] for the first index that is not smaller than the number of grapheme clusters in `charset`.
+pub fn map_indices(indices : &[usize], charset : &str) -> Result<String, MapError> {
+    let graphemes : Vec<&str> = split_into_graphemes(charset).collect();
+    indices.iter().enumerate().map(|(position, &index)| {
+        graphemes.get(index).copied().ok_or(MapError::IndexOutOfRange { position, index, charset_length : graphemes.len() })
+    }).collect()
+}
+
+/// Returns true if converting a hash of `hash_bytes` bytes to `base` digits uses precomputed constants.
+/// 
+/// `base` is the number of grapheme clusters in the output character set, and `hash_bytes` the output length of the hash
+/// algorithm: 16 for MD4, MD5 and RIPEMD-128, 20 for SHA-1 and RIPEMD-160, 24 for Tiger, and 32 for SHA-256. This is meant for performance tuning: If a character set's length is not
+/// precomputed, the highest power of it that fits into the hash needs to be computed at runtime (and is cached per thread if the
+/// `cached_max_powers` feature is enabled). Which bases are precomputed depends on the enabled features, see the crate documentation.
+/// 
+/// 16 byte hashes (MD4, MD5, RIPEMD-128) always return false. For them the power is computed at runtime, which is cheap.
+/// 
+/// ```
+/// # use passwordmaker_rs::is_base_precomputed;
+/// assert_eq!(is_base_precomputed(94, 32), cfg!(feature = "precomputed_common_max_powers"));
+/// assert!(!is_base_precomputed(200, 32));
+/// assert!(!is_base_precomputed(94, 16));
+/// ```
+#[must_use]
+pub fn is_base_precomputed(base : usize, hash_bytes : usize) -> bool {
+    is_base_precomputed_impl(base, hash_bytes)
+}
+
 /// Trait you need to implement for the various hash functions you need to provide.
 /// Currently only a single function, that computes the hash of a string slice, is needed. This may change in a later version.
 /// 
 /// Beware: There is currently no way to put constraints on associated constants in Rust, so Block Size is not exposed.
 /// It's anyhow the same (currently hardcoded) value for all supported algorithms.
+/// 
+/// The length of the output depends on the algorithm, see [`HasherList`]. If a hash function returns a different number of
+/// bytes, password generation panics.
 pub trait Hasher {
-    /// The output type of the respective hash function. Typically some form of byte array.
-    type Output;
+    /// The output type of the respective hash function. Any byte container works, for instance `[u8;16]`, or the `GenericArray`
+    /// returned by the [Rust Crypto Hashes](https://github.com/RustCrypto/hashes), which can be returned without conversion.
+    type Output : AsRef<[u8]>;
     /// Function that takes a byte array as input, and generates the cryptographic hash of it as output.
     fn hash(input : &[u8]) -> Self::Output;
+    /// Computes the hash of the concatenation of `chunks`. Used for HMAC, where the input is made of a pad and the message.
+    /// 
+    /// The default implementation copies the chunks into a single buffer and calls [`hash`][Hasher::hash]. Hash functions
+    /// that can process their input incrementally should override this, and feed the chunks one after the other.
+    fn hash_chunks<'a, I : Iterator<Item=&'a [u8]>>(chunks : I) -> Self::Output {
+        let input : Vec<u8> = chunks.flat_map(|chunk| chunk.iter().copied()).collect();
+        Self::hash(&input)
+    }
 }
 
-/// Trait your Md4 hash function needs to implement.
-pub trait Md4 : Hasher<Output = [u8;16]> {}
-/// Trait your Md5 hash function needs to implement.
-pub trait Md5 : Hasher<Output = [u8;16]> {}
-/// Trait your Sha1 hash function needs to implement.
-pub trait Sha1 : Hasher<Output = [u8;20]> {}
-/// Trait your Sha256 hash function needs to implement.
-pub trait Sha256 : Hasher<Output = [u8;32]> {}
-/// Trait your Ripemd160 hash function needs to implement.
-pub trait Ripemd160 : Hasher<Output = [u8;20]> {}
+/// Trait your Md4 hash function needed to implement. Not needed any more, see [`HasherList`].
+#[deprecated(since = "0.3.0", note = "HasherList only requires Hasher. Implementations of this trait can be removed.")]
+pub trait Md4 : Hasher {}
+/// Trait your Md5 hash function needed to implement. Not needed any more, see [`HasherList`].
+#[deprecated(since = "0.3.0", note = "HasherList only requires Hasher. Implementations of this trait can be removed.")]
+pub trait Md5 : Hasher {}
+/// Trait your Sha1 hash function needed to implement. Not needed any more, see [`HasherList`].
+#[deprecated(since = "0.3.0", note = "HasherList only requires Hasher. Implementations of this trait can be removed.")]
+pub trait Sha1 : Hasher {}
+/// Trait your Sha256 hash function needed to implement. Not needed any more, see [`HasherList`].
+#[deprecated(since = "0.3.0", note = "HasherList only requires Hasher. Implementations of this trait can be removed.")]
+pub trait Sha256 : Hasher {}
+/// Trait your Ripemd160 hash function needed to implement. Not needed any more, see [`HasherList`].
+#[deprecated(since = "0.3.0", note = "HasherList only requires Hasher. Implementations of this trait can be removed.")]
+pub trait Ripemd160 : Hasher {}
 
 /// List of hash functions to use. Trait may change in later versions to include constructors for actual hasher objects.
+/// 
+/// These are the hash functions PasswordMaker Pro offers. For RIPEMD-128 and Tiger see [`ExtendedHasherList`].
 pub trait HasherList {
-    /// The type that offers MD4 hashing. See the [`Md4`] trait.
-    type MD4 : Md4;
-    /// The type that offers MD5 hashing. See the [`Md5`] trait.
-    type MD5 : Md5;
-    /// The type that offers SHA1 hashing. See the [`Sha1`] trait.
-    type SHA1 : Sha1;
-    /// The type that offers SHA256 hashing. See the [`Sha256`] trait.
-    type SHA256 : Sha256;
-    /// The type that offers Ripemd160 hashing. See the [`Ripemd160`] trait.
-    type RIPEMD160 : Ripemd160;
+    /// The type that offers MD4 hashing. Its output must be 16 bytes long.
+    type MD4 : Hasher;
+    /// The type that offers MD5 hashing. Its output must be 16 bytes long.
+    type MD5 : Hasher;
+    /// The type that offers SHA1 hashing. Its output must be 20 bytes long.
+    type SHA1 : Hasher;
+    /// The type that offers SHA256 hashing. Its output must be 32 bytes long.
+    type SHA256 : Hasher;
+    /// The type that offers Ripemd160 hashing. Its output must be 20 bytes long.
+    type RIPEMD160 : Hasher;
+}
+
+/// Hash functions that PasswordMaker Pro does not offer, but some other PasswordMaker compatible tools do.
+/// 
+/// A [`HasherList`] is enough for all algorithms of PasswordMaker Pro. To also generate passwords with RIPEMD-128 and Tiger,
+/// implement this trait as well, and use a `PasswordMaker<Extended<YourList>>`. With a plain `HasherList`, password generation
+/// with these algorithms fails with [`GenerationError::UnavailableAlgorithm`].
+pub trait ExtendedHasherList : HasherList {
+    /// The type that offers Ripemd128 hashing. Its output must be 16 bytes long.
+    type RIPEMD128 : Hasher;
+    /// The type that offers Tiger hashing. Its output must be 24 bytes long.
+    type TIGER : Hasher;
+}
+
+/// Makes the hash functions of an [`ExtendedHasherList`] available to [`PasswordMaker`]. See [`ExtendedHasherList`] for details.
+/// Never instantiated, only used as type parameter.
+pub struct Extended<L>(PhantomData<fn() -> L>);
+
+/// The hash functions a [`PasswordMaker`] uses. Implemented for every [`HasherList`], and for [`Extended`] ones.
+/// 
+/// This trait cannot be implemented outside of this crate. Implement [`HasherList`], and maybe [`ExtendedHasherList`], instead.
+pub trait HashFunctions : passwordmaker::HashFunctionsImpl {}
+impl<T : HasherList> HashFunctions for T {}
+impl<L : ExtendedHasherList> HashFunctions for Extended<L> {}
+
+/// Fallible counterpart of [`Hasher`], for hash functions that can fail. For instance because they run in a secure enclave,
+/// or on another machine. See [`TryHasherList`] for how to use them.
+pub trait TryHasher {
+    /// The output type of the respective hash function. Typically some form of byte array.
+    type Output;
+    /// The error returned if hashing failed.
+    type Error;
+    /// Function that takes a byte array as input, and either generates the cryptographic hash of it, or fails.
+    /// 
+    /// # Errors
+    /// Whatever the implementation considers a failure.
+    fn try_hash(input : &[u8]) -> Result<Self::Output, Self::Error>;
+}
+
+/// List of fallible hash functions, the counterpart of [`HasherList`] for [`TryHasher`]s.
+/// 
+/// Wrapping a `TryHasherList` in [`Fallible`] lets the fallible hash functions be used with a `PasswordMaker<Fallible<YourList>>`.
+/// Passwords are then generated with [`try_generate`][PasswordMaker::try_generate], which returns the error of the first hash
+/// function call that failed. The other generation functions need a [`HasherList`], and are not available for `Fallible`.
+/// 
+/// All hash functions in the list share the same error type. There is no fallible counterpart of [`ExtendedHasherList`], so
+/// RIPEMD-128 and Tiger are not available with fallible hash functions.
+pub trait TryHasherList {
+    /// The error type of all hash functions in this list.
+    type Error : 'static;
+    /// The type that offers MD4 hashing.
+    type MD4 : TryHasher<Output = [u8;16], Error = Self::Error>;
+    /// The type that offers MD5 hashing.
+    type MD5 : TryHasher<Output = [u8;16], Error = Self::Error>;
+    /// The type that offers SHA1 hashing.
+    type SHA1 : TryHasher<Output = [u8;20], Error = Self::Error>;
+    /// The type that offers SHA256 hashing.
+    type SHA256 : TryHasher<Output = [u8;32], Error = Self::Error>;
+    /// The type that offers Ripemd160 hashing.
+    type RIPEMD160 : TryHasher<Output = [u8;20], Error = Self::Error>;
 }
 
+/// Lets a [`PasswordMaker`] use the hash functions of a [`TryHasherList`]. See [`TryHasherList`] for details. Never instantiated,
+/// only used as type parameter.
+/// 
+/// Only [`try_generate`][PasswordMaker::try_generate] can report the errors of the hash functions, so the other generation
+/// functions can't be used:
+/// ```compile_fail
+/// # use passwordmaker_rs::*;
+/// # struct H16; struct H20; struct H32;
+/// # impl TryHasher for H16 { type Output = [u8;16]; type Error = (); fn try_hash(_ : &[u8]) -> Result<[u8;16], ()> { Err(()) } }
+/// # impl TryHasher for H20 { type Output = [u8;20]; type Error = (); fn try_hash(_ : &[u8]) -> Result<[u8;20], ()> { Err(()) } }
+/// # impl TryHasher for H32 { type Output = [u8;32]; type Error = (); fn try_hash(_ : &[u8]) -> Result<[u8;32], ()> { Err(()) } }
+/// # struct Hashers;
+/// # impl TryHasherList for Hashers {
+/// #     type Error = (); type MD4 = H16; type MD5 = H16; type SHA1 = H20; type SHA256 = H32; type RIPEMD160 = H20;
+/// # }
+/// let pwm = PasswordMaker::<Fallible<Hashers>>::new(
+///     HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "0123456789abcdef", "", "", 8, "", ""
+/// ).unwrap();
+/// pwm.generate("www.example.com", "password");
+/// ```
+pub struct Fallible<L>(PhantomData<fn() -> L>);
+
+
 /// A cached instance of validated `PasswordMaker` settings. See [`new`][PasswordMaker::new] for details.
-pub struct PasswordMaker<'a, T : HasherList>{
-    username : &'a str,
-    modifier : &'a str,
+#[allow(clippy::struct_excessive_bools)] //Independent options. Grouping them into enums would not make anything clearer.
+pub struct PasswordMaker<'a, T>{
+    username : Cow<'a, str>,
+    modifier : Cow<'a, str>,
     password_part_parameters : PasswordPartParameters<'a>, //contains pre_leet, as this is different for different algorithms
     post_leet : Option<LeetReplacementTable>, //same for all algorithms. applied before before password assembly.
     assembly_settings : PasswordAssemblyParameters<'a>,
-    _hashers : PhantomData<T>,
+    #[cfg(feature = "unicode-normalization")]
+    normalize_unicode : Option<NormalizationForm>,
+    post_leet_fallback : bool,
+    part_counter_placement : PartCounterPlacement,
+    part_counter_separator : Cow<'a, str>,
+    max_output_bytes : Option<usize>,
+    revision : Cow<'a, str>,
+    context_tag : Cow<'a, str>,
+    pre_leet_username : bool,
+    pre_leet_modifier : bool,
+    min_key_length : usize,
+    strict_charset : bool,
+    strict_whitespace : bool,
+    max_affix_length : Option<usize>,
+    require_fast_base : bool,
+    _hashers : PhantomData<fn() -> T>, //fn() -> T, because we never hold a T. This keeps PasswordMaker Send and Sync for any T.
+}
+
+//Derive would require T : Clone, even though T is only used in PhantomData.
+impl<T> Clone for PasswordMaker<'_, T>{
+    fn clone(&self) -> Self {
+        PasswordMaker {
+            username : self.username.clone(),
+            modifier : self.modifier.clone(),
+            password_part_parameters : self.password_part_parameters.clone(),
+            post_leet : self.post_leet.clone(),
+            assembly_settings : self.assembly_settings.clone(),
+            #[cfg(feature = "unicode-normalization")]
+            normalize_unicode : self.normalize_unicode,
+            post_leet_fallback : self.post_leet_fallback,
+            part_counter_placement : self.part_counter_placement,
+            part_counter_separator : self.part_counter_separator.clone(),
+            max_output_bytes : self.max_output_bytes,
+            revision : self.revision.clone(),
+            context_tag : self.context_tag.clone(),
+            pre_leet_username : self.pre_leet_username,
+            pre_leet_modifier : self.pre_leet_modifier,
+            min_key_length : self.min_key_length,
+            strict_charset : self.strict_charset,
+            strict_whitespace : self.strict_whitespace,
+            max_affix_length : self.max_affix_length,
+            require_fast_base : self.require_fast_base,
+            _hashers : PhantomData,
+        }
+    }
 }
 
-impl<'a, T : HasherList> PasswordMaker<'a, T>{
+impl<'a, T> PasswordMaker<'a, T>{
     /// Validates user input and returns a `PasswordMaker` object if the input is valid.
     /// 
     /// `hash_algorithm` is a PasswordMaker Pro algorithm selection.
@@ -103,6 +370,11 @@ impl<'a, T : HasherList> PasswordMaker<'a, T>{
     /// `prefix` is the prefix to which the password gets appended. Counts towards `password_length`.
     /// `suffix` is the suffix appended to the password. Counts towards `password_length`.
     /// 
+    /// The data that gets hashed is the `data` passed to [`generate`][PasswordMaker::generate], followed by `username`, followed
+    /// by `modifier`, without any separator. Empty fields add nothing, so with empty `username` and `modifier` exactly `data` is
+    /// hashed. See [`GenerationContext`] to inspect the result, and [`with_strict_whitespace`][PasswordMaker::with_strict_whitespace]
+    /// to catch accidental whitespace.
+    /// 
     /// # Errors
     /// Fails if characters does not contain at least 2 grapheme clusters. Mapping to output happens by number system conversion,
     /// and a number system base 1 or base 0 does not make any sense.
@@ -117,189 +389,2132 @@ impl<'a, T : HasherList> PasswordMaker<'a, T>{
         prefix : &'a str,
         suffix : &'a str,
     ) -> Result<Self, SettingsError> {
-        if Self::is_suitable_as_output_characters(characters) {
-            let post_leet = match &use_leet {
-                UseLeetWhenGenerating::NotAtAll
-                 | UseLeetWhenGenerating::Before { .. }
-                 => None,
-                UseLeetWhenGenerating::After { level }
-                 | UseLeetWhenGenerating::BeforeAndAfter { level }
-                 => Some(LeetReplacementTable::get(*level)),
-            };
-            Ok(PasswordMaker {
-                username,
-                modifier,
-                password_part_parameters: PasswordPartParameters::from_public_parameters(hash_algorithm, use_leet, characters),
-                post_leet,
-                assembly_settings: PasswordAssemblyParameters::from_public_parameters(prefix, suffix, password_length),
-                _hashers: PhantomData,
-            })
-        } else {
-            Err(SettingsError::InsufficientCharset)
+        Self::from_cow_parameters(
+            hash_algorithm,
+            use_leet,
+            Cow::Borrowed(characters),
+            Cow::Borrowed(username),
+            Cow::Borrowed(modifier),
+            password_length,
+            Cow::Borrowed(prefix),
+            Cow::Borrowed(suffix),
+        )
+    }
+
+    /// Same as [`new`][PasswordMaker::new], but takes a [`PreparedCharset`] instead of a string slice for `characters`.
+    /// 
+    /// The charset has already been validated and split into grapheme clusters, so this function cannot fail, and
+    /// does not need to do that work again. This is useful if many `PasswordMaker`s with the same characters are created,
+    /// for instance one per website.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn new_with_prepared_charset(
+        hash_algorithm : HashAlgorithm,
+        use_leet : UseLeetWhenGenerating,
+        characters : &'a PreparedCharset,
+        username : &'a str,
+        modifier: &'a str,
+        password_length : usize,
+        prefix : &'a str,
+        suffix : &'a str,
+    ) -> Self {
+        Self::from_grapheme_list(
+            hash_algorithm,
+            use_leet,
+            characters.graphemes.borrow(),
+            Cow::Borrowed(username),
+            Cow::Borrowed(modifier),
+            password_length,
+            Cow::Borrowed(prefix),
+            Cow::Borrowed(suffix),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn from_cow_parameters(
+        hash_algorithm : HashAlgorithm,
+        use_leet : UseLeetWhenGenerating,
+        characters : Cow<'a, str>,
+        username : Cow<'a, str>,
+        modifier: Cow<'a, str>,
+        password_length : usize,
+        prefix : Cow<'a, str>,
+        suffix : Cow<'a, str>,
+    ) -> Result<Self, SettingsError> {
+        validate_output_characters(&characters)?;
+        Ok(Self::from_grapheme_list(hash_algorithm, use_leet, GraphemeList::new(characters), username, modifier, password_length, prefix, suffix))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn from_grapheme_list(
+        hash_algorithm : HashAlgorithm,
+        use_leet : UseLeetWhenGenerating,
+        characters : GraphemeList<'a>,
+        username : Cow<'a, str>,
+        modifier: Cow<'a, str>,
+        password_length : usize,
+        prefix : Cow<'a, str>,
+        suffix : Cow<'a, str>,
+    ) -> Self {
+        let post_leet = match &use_leet {
+            UseLeetWhenGenerating::NotAtAll
+             | UseLeetWhenGenerating::Before { .. }
+             => None,
+            UseLeetWhenGenerating::After { level }
+             | UseLeetWhenGenerating::BeforeAndAfter { level }
+             => Some(LeetReplacementTable::get(*level)),
+        };
+        PasswordMaker {
+            username,
+            modifier,
+            password_part_parameters: PasswordPartParameters::from_public_parameters(hash_algorithm, use_leet, characters),
+            post_leet,
+            assembly_settings: PasswordAssemblyParameters::from_public_parameters(prefix, suffix, password_length),
+            #[cfg(feature = "unicode-normalization")]
+            normalize_unicode: None,
+            post_leet_fallback: false,
+            part_counter_placement: PartCounterPlacement::default(),
+            part_counter_separator: Cow::Borrowed("\n"),
+            max_output_bytes: None,
+            revision: Cow::Borrowed(""),
+            context_tag: Cow::Borrowed(""),
+            pre_leet_username: true,
+            pre_leet_modifier: true,
+            min_key_length: 1,
+            strict_charset: false,
+            strict_whitespace: false,
+            max_affix_length: None,
+            require_fast_base: false,
+            _hashers: PhantomData,
         }
     }
 
+}
+
+impl<T : HashFunctions> PasswordMaker<'_, T>{
     /// Generates a password for the given `data` and `key`.
     /// `data` is the "text-to-use", typically the output of [`UrlParsing`].
     /// `key` is the key, also known as "master password".
+    /// Both can be passed as anything that converts into a `String`, so owned strings are not copied, and string slices
+    /// do not need a `to_owned()` at the call site. The same holds for all other `generate` functions.
+    /// 
+    /// All `generate` functions borrow `self`. The settings are validated once, in [`new`][PasswordMaker::new], and the
+    /// `PasswordMaker` can then be used for any number of passwords, for instance for many websites, or together with
+    /// [`generate_nth`][PasswordMaker::generate_nth]. Only `data` and `key` are taken by value, as they are consumed by generation.
+    /// 
+    /// ```
+    /// # use passwordmaker_rs::*;
+    /// # struct H16; struct H20; struct H32;
+    /// # impl Hasher for H16 { type Output = [u8;16]; fn hash(_ : &[u8]) -> [u8;16] { [7;16] } }
+    /// # impl Hasher for H20 { type Output = [u8;20]; fn hash(_ : &[u8]) -> [u8;20] { [7;20] } }
+    /// # impl Hasher for H32 { type Output = [u8;32]; fn hash(_ : &[u8]) -> [u8;32] { [7;32] } }
+    /// # struct Hashers;
+    /// # impl HasherList for Hashers {
+    /// #     type MD4 = H16; type MD5 = H16; type SHA1 = H20; type SHA256 = H32;
+    /// #     type RIPEMD160 = H20;
+    /// # }
+    /// // The settings are borrowed, so a `&String` works just as well as a `&str`.
+    /// let username = String::from("user");
+    /// let pwm = PasswordMaker::<Hashers>::new(
+    ///     HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "0123456789abcdef", &username, "", 8, "", ""
+    /// ).unwrap();
+    /// let data = UrlParsing::new(ProtocolUsageMode::Ignored, false, true, true, false).parse("https://www.example.com");
+    /// let from_strings = pwm.generate(data, String::from("password")).unwrap();
+    /// let from_slices = pwm.generate("www.example.com", "password").unwrap();
+    /// assert_eq!(from_strings, from_slices);
+    /// ```
     /// 
     ///  # Errors
-    ///  Fails if either of the parameters has zero-length.
-    pub fn generate(&self, data: String, key: String) -> Result<String, GenerationError> {
-        if data.is_empty() {
-            Err(GenerationError::MissingTextToUse)
-        } else if key.is_empty(){
-            Err(GenerationError::MissingMasterPassword)
-        } else {
-            Ok(self.generate_password_verified_input(data, key))
-        }
+    ///  Fails in the cases listed at [`GenerationError`].
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn generate(&self, data: impl Into<String>, key: impl Into<String>) -> Result<String, GenerationError> {
+        self.generate_nth(data, key, 0)
     }
-}
 
-/// The leet level to use. The higher the value, the more obfuscated the results.
-#[cfg_attr(test, derive(strum_macros::EnumIter))]
-#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
-#[derive(Debug,Clone, Copy)]
-pub enum LeetLevel {
-    /// First Leet level:\
-    /// `["4", "b", "c", "d", "3", "f", "g", "h", "i", "j", "k", "1", "m", "n", "0", "p", "9", "r", "s", "7", "u", "v", "w", "x", "y", "z"]`
-    One,
-    /// Second Leet level:\
-    /// `["4", "b", "c", "d", "3", "f", "g", "h", "1", "j", "k", "1", "m", "n", "0", "p", "9", "r", "5", "7", "u", "v", "w", "x", "y", "2"]`
-    Two,
-    /// Third Leet level:\
-    /// `["4", "8", "c", "d", "3", "f", "6", "h", "'", "j", "k", "1", "m", "n", "0", "p", "9", "r", "5", "7", "u", "v", "w", "x", "'/", "2"]`
-    Three,
-    /// Fourth Leet level:\
-    /// `["@", "8", "c", "d", "3", "f", "6", "h", "'", "j", "k", "1", "m", "n", "0", "p", "9", "r", "5", "7", "u", "v", "w", "x", "'/", "2"]`
-    Four,
-    /// Fifth Leet level:\
-    /// `["@", "|3", "c", "d", "3", "f", "6", "#", "!", "7", "|<", "1", "m", "n", "0", "|>", "9", "|2", "$", "7", "u", "\\/", "w", "x", "'/", "2"]`
-    Five,
-    /// Sixth Leet level:\
-    /// `["@", "|3", "c", "|)", "&", "|=", "6", "#", "!", ",|", "|<", "1", "m", "n", "0", "|>", "9", "|2", "$", "7", "u", "\\/", "w", "x", "'/", "2"]`
-    Six,
-    /// Seventh Leet level:\
-    /// `["@", "|3", "[", "|)", "&", "|=", "6", "#", "!", ",|", "|<", "1", "^^", "^/", "0", "|*", "9", "|2", "5", "7", "(_)", "\\/", "\\/\\/", "><", "'/", "2"]`
-    Seven,
-    /// Eigth Leet level:\
-    /// `["@", "8", "(", "|)", "&", "|=", "6", "|-|", "!", "_|", "|(", "1", "|\\/|", "|\\|", "()", "|>", "(,)", "|2", "$", "|", "|_|", "\\/", "\\^/", ")(", "'/", "\"/_"]`
-    Eight,
-    /// Ninth Leet level:\
-    /// `["@", "8", "(", "|)", "&", "|=", "6", "|-|", "!", "_|", "|{", "|_", "/\\/\\", "|\\|", "()", "|>", "(,)", "|2", "$", "|", "|_|", "\\/", "\\^/", ")(", "'/", "\"/_"]`
-    Nine,
-}
+    /// Parses `raw_url` with `url_settings`, and generates a password from the result and `key`.
+    /// 
+    /// This is the same as calling [`UrlParsing::parse`] and passing the result as `data` to [`generate`][PasswordMaker::generate].
+    /// 
+    /// ```
+    /// # use passwordmaker_rs::*;
+    /// # struct H16; struct H20; struct H32;
+    /// # impl Hasher for H16 { type Output = [u8;16]; fn hash(_ : &[u8]) -> [u8;16] { [7;16] } }
+    /// # impl Hasher for H20 { type Output = [u8;20]; fn hash(_ : &[u8]) -> [u8;20] { [7;20] } }
+    /// # impl Hasher for H32 { type Output = [u8;32]; fn hash(_ : &[u8]) -> [u8;32] { [7;32] } }
+    /// # struct Hashers;
+    /// # impl HasherList for Hashers {
+    /// #     type MD4 = H16; type MD5 = H16; type SHA1 = H20; type SHA256 = H32;
+    /// #     type RIPEMD160 = H20;
+    /// # }
+    /// let pwm = PasswordMaker::<Hashers>::new(
+    ///     HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "0123456789abcdef", "", "", 8, "", ""
+    /// ).unwrap();
+    /// let url_settings = UrlParsing::default();
+    /// let password = pwm.generate_from_url(&url_settings, "https://www.example.com/login", "password").unwrap();
+    /// assert_eq!(password, pwm.generate("example.com", "password").unwrap());
+    /// ```
+    /// 
+    ///  # Errors
+    ///  Fails in the cases listed at [`GenerationError`]. If nothing is left of `raw_url` after parsing, that is
+    ///  [`GenerationError::MissingTextToUse`].
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn generate_from_url(&self, url_settings : &UrlParsing, raw_url : &str, key: impl Into<String>) -> Result<String, GenerationError> {
+        self.generate(url_settings.parse(raw_url), key)
+    }
 
-/// The hash algorithm to use, as shown in the GUI of the JavaScript edition of PasswordMaker Pro.
-/// 
-/// # Description 
-/// 
-/// Most algorithms work by computing the hash of the input values and doing a number system base conversion to indices into
-/// the supplied character array.
-/// Notable exceptions are the HMAC algorithms, which not only compute the HMAC for the input, but also, before that, encode the
-/// input as UTF-16 and discard all upper bytes.
-/// The `Md5Version06` variant is for compatibility with ancient versions of PasswordMaker Pro. Not only does it also do the conversion
-/// to UTF-16 and the discarding of the upper bytes, in addition it disregards the user-supplied character set completely, and instead
-/// just outputs the hash encoded as hexadecimal numbers.
-/// The `HmacMd5Version06` is similarly ignoring the supplied characters and using hexadecimal numbers as output.
-#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
-#[derive(Debug,Clone, Copy)]
-pub enum HashAlgorithm {
-    /// Regular Md4 PasswordMaker Pro setting.
-    Md4,
-    /// HAMC Md4 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
-    HmacMd4,
-    /// Regular Md5 PasswordMaker Pro setting.
-    Md5,
-    /// Md5 as computed by PasswordMaker Pro version 0.6. Encodes input as UTF-16 and discards upper byte and outputs MD5 as hex number.
-    Md5Version06,
-    /// HMAC Md5 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
-    HmacMd5,
-    /// HMAC Md5 as computed by PasswordMaker Pro version 0.6. Encodes input as UTF-16 and discards upper byte and outputs MD5 as hex number.
-    HmacMd5Version06,
-    /// Regular Sha1 PasswordMaker Pro setting.
-    Sha1,
-    /// HAMC Sha1 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
-    HmacSha1,
-    /// Regular Sha256 PasswordMaker Pro setting.
-    Sha256,
-    /// HAMC Sha256 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
-    HmacSha256,
-    /// Regular Ripemd160 PasswordMaker Pro setting.
-    Ripemd160,
-    /// HAMC Ripemd160 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
-    HmacRipemd160,
-}
+    /// Generates a password for each of the `urls`, using the same `key` for all of them. For instance to migrate a list of
+    /// bookmarks.
+    /// 
+    /// The results are in the same order as `urls`, and each of them is identical to what
+    /// [`generate_from_url`][PasswordMaker::generate_from_url] returns for the respective URL. The settings, including the
+    /// prepared output characters, are shared by all URLs. Create the `PasswordMaker` with [`Settings::password_maker`] to
+    /// use stored settings.
+    /// ```
+    /// # use passwordmaker_rs::*;
+    /// # struct H16; struct H20; struct H32;
+    /// # impl Hasher for H16 { type Output = [u8;16]; fn hash(_ : &[u8]) -> [u8;16] { [7;16] } }
+    /// # impl Hasher for H20 { type Output = [u8;20]; fn hash(_ : &[u8]) -> [u8;20] { [7;20] } }
+    /// # impl Hasher for H32 { type Output = [u8;32]; fn hash(_ : &[u8]) -> [u8;32] { [7;32] } }
+    /// # struct Hashers;
+    /// # impl HasherList for Hashers {
+    /// #     type MD4 = H16; type MD5 = H16; type SHA1 = H20; type SHA256 = H32;
+    /// #     type RIPEMD160 = H20;
+    /// # }
+    /// let pwm = PasswordMaker::<Hashers>::new(
+    ///     HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "0123456789abcdef", "", "", 8, "", ""
+    /// ).unwrap();
+    /// let passwords = pwm.generate_for_urls(&UrlParsing::default(), "password", &["https://www.example.com/login", ""]);
+    /// assert_eq!(passwords[0].as_ref().unwrap(), &pwm.generate("example.com", "password").unwrap());
+    /// assert!(passwords[1].is_err());
+    /// ```
+    /// 
+    ///  # Errors
+    ///  Each entry fails for the same reasons as [`generate_from_url`][PasswordMaker::generate_from_url]. A failing URL does
+    ///  not affect the others.
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    #[must_use]
+    pub fn generate_for_urls(&self, url_settings : &UrlParsing, key : &str, urls : &[&str]) -> Vec<Result<String, GenerationError>> {
+        urls.iter().map(|url| self.generate_from_url(url_settings, url, key)).collect()
+    }
 
-/// When the Leet replacement as illustrated in [`LeetLevel`] is applied.
-/// 
-/// # Description
-/// If Leet is enabled, the input will be converted to lower case.
-/// It is always applied to each password part when the required password length
-/// is longer than the length obtained by computing a single hash. This is important if the input data or output charset contains certain
-/// characters where the lower case representation depends on context (e.g. 'Σ').
-#[cfg_attr(feature = "strum", derive(strum_macros::EnumDiscriminants, strum_macros::VariantNames), strum_discriminants(derive(strum_macros::EnumString)))]
-#[derive(Debug,Clone, Copy)]
-pub enum UseLeetWhenGenerating {
-    /// Do not apply Leet on input or output.
-    NotAtAll,
-    /// Apply Leet on the input before computing a password part.
-    Before {
-        /// The Leet level to apply to the input.
-        level : LeetLevel,
-    },
-    /// Apply Leet on the generated password-part. Beware that this will force the password to lower-case characters.
-    After {
-        /// The Leet level to apply to the generated password parts.
-        level : LeetLevel,
-    },
-    /// Apply Leet both, to the input for the hasher, and the generated password parts. Beware that this will force the password to lower-case characters.
-    BeforeAndAfter {
-        /// The Leet level to apply to both, input and generated password parts.
-        level : LeetLevel,
-    },
-}
+    /// Generates the `n`-th password for the given `data` and `key`.
+    /// 
+    /// PasswordMaker Pro generates passwords that are longer than a single hash by concatenating several password parts.
+    /// Each part is computed with a modified key: The first part uses `key` as-is, part `i` uses `key + "\n" + i`.
+    /// This function starts this part counter at `n` instead of 0, so the first part uses the key of part `n`,
+    /// the second part the key of part `n+1`, and so on. This allows to get a different password for the same settings
+    /// and inputs, for instance if a website requires a password change.
+    /// 
+    /// `n = 0` yields the same result as [`generate`][PasswordMaker::generate].
+    /// Beware that for passwords that need multiple parts, the parts of different `n` overlap. For instance, the second
+    /// part of the password with `n = 0` is identical to the first part of the password with `n = 1`.
+    /// 
+    ///  # Errors
+    ///  Fails in the cases listed at [`GenerationError`].
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn generate_nth(&self, data: impl Into<String>, key: impl Into<String>, n : usize) -> Result<String, GenerationError> {
+        let (data, key) = self.prepare_input(data.into(), key.into())?;
+        self.generate_password_verified_input(data, key, n)
+    }
 
-/// Settings for the parsing of the user's input URL.
-/// This is used to generate the `data` parameter for [`PasswordMaker`].
-#[allow(clippy::struct_excessive_bools)]
-#[derive(Debug, Clone)]
-pub struct UrlParsing {
-    use_protocol : ProtocolUsageMode,
-    use_userinfo : bool,
-    use_subdomains : bool,
-    use_domain : bool,
-    use_port_path : bool,
-}
+    /// Same as [`generate`][PasswordMaker::generate], but yields the grapheme clusters of the password one by one, instead of
+    /// returning a `String`.
+    /// 
+    /// The password parts are only computed when the iterator reaches them, and the password is never stored as a whole.
+    /// This allows to write the password to a sink without allocating it, or to reveal it character by character.
+    /// Collecting the iterator yields the same password as [`generate`][PasswordMaker::generate], including prefix and suffix.
+    /// 
+    /// The grapheme clusters borrow from the `PasswordMaker` where possible. If leet is applied after generation,
+    /// the password needs to be leeted as a whole, so it is computed up front, and each grapheme cluster is an owned copy.
+    /// 
+    /// ```
+    /// # use passwordmaker_rs::*;
+    /// # struct H16; struct H20; struct H32;
+    /// # impl Hasher for H16 { type Output = [u8;16]; fn hash(_ : &[u8]) -> [u8;16] { [7;16] } }
+    /// # impl Hasher for H20 { type Output = [u8;20]; fn hash(_ : &[u8]) -> [u8;20] { [7;20] } }
+    /// # impl Hasher for H32 { type Output = [u8;32]; fn hash(_ : &[u8]) -> [u8;32] { [7;32] } }
+    /// # struct Hashers;
+    /// # impl HasherList for Hashers {
+    /// #     type MD4 = H16; type MD5 = H16; type SHA1 = H20; type SHA256 = H32;
+    /// #     type RIPEMD160 = H20;
+    /// # }
+    /// use std::io::Write;
+    /// let pwm = PasswordMaker::<Hashers>::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 30, "pre", "").unwrap();
+    /// let mut sink = Vec::new();
+    /// for grapheme in pwm.grapheme_iter("example.com", "password").unwrap() {
+    ///     sink.write_all(grapheme.as_bytes()).unwrap();
+    /// }
+    /// assert_eq!(sink, pwm.generate("example.com", "password").unwrap().into_bytes());
+    /// ```
+    /// 
+    ///  # Errors
+    ///  Fails in the cases listed at [`GenerationError`], except for [`GenerationError::OutputTooLarge`]. The limit set with
+    ///  [`with_max_output_bytes`][PasswordMaker::with_max_output_bytes] does not apply, as the password is not stored.
+    /// 
+    /// # Panics
+    /// Panics, either right away or while iterating, if a hash function returns a different number of bytes than the algorithm
+    /// needs, see [`Hasher`].
+    pub fn grapheme_iter(&self, data: impl Into<String>, key: impl Into<String>) -> Result<impl Iterator<Item=Cow<'_, str>> + '_, GenerationError> {
+        let (data, key) = self.prepare_input(data.into(), key.into())?;
+        Ok(self.generate_graphemes_verified_input(data, key))
+    }
 
-#[allow(clippy::fn_params_excessive_bools)]
-impl UrlParsing {
-    /// Creates a new `UrlParsing` instance with the given settings.
-    #[must_use]
-    pub fn new(
-        use_protocol : ProtocolUsageMode,
-        use_userinfo : bool,
-        use_subdomains : bool,
-        use_domain : bool,
-        use_port_path : bool,
-    ) -> Self{
-        UrlParsing{ use_protocol, use_userinfo, use_subdomains, use_domain, use_port_path, }
+    /// Same as [`generate`][PasswordMaker::generate], but writes the password to `target` instead of returning a `String`.
+    /// 
+    /// The grapheme clusters are written one by one, as [`grapheme_iter`][PasswordMaker::grapheme_iter] yields them. This allows to
+    /// append the password to an existing buffer, or to write it to a [`Formatter`][std::fmt::Formatter] without an intermediate `String`.
+    /// 
+    ///  # Errors
+    ///  Fails in the same cases as [`grapheme_iter`][PasswordMaker::grapheme_iter], before anything is written. Like there, the limit
+    ///  set with [`with_max_output_bytes`][PasswordMaker::with_max_output_bytes] does not apply.
+    ///  Fails with [`GenerationError::WriteFailed`] if `target` returns an error. The part of the password written up to then
+    ///  stays in `target`.
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn write_to<W : std::fmt::Write>(&self, data: impl Into<String>, key: impl Into<String>, target : &mut W) -> Result<(), GenerationError> {
+        for grapheme in self.grapheme_iter(data, key)? {
+            target.write_str(&grapheme).map_err(|_| GenerationError::WriteFailed)?;
+        }
+        Ok(())
     }
 
-    /// Parses an input string, applying the settings in `self`, and generates a string suitable for
-    /// the `data` parameter of [`PasswordMaker`]
-    #[must_use]
-    pub fn parse(&self, input : &str) -> String{
-        self.make_used_text_from_url(input)
+    /// Same as [`generate`][PasswordMaker::generate], but also returns a [`GenerationContext`], that describes the generation
+    /// without revealing any secrets. Meant for audit logging.
+    /// 
+    /// ```
+    /// # use passwordmaker_rs::*;
+    /// # struct H16; struct H20; struct H32;
+    /// # impl Hasher for H16 { type Output = [u8;16]; fn hash(_ : &[u8]) -> [u8;16] { [7;16] } }
+    /// # impl Hasher for H20 { type Output = [u8;20]; fn hash(_ : &[u8]) -> [u8;20] { [7;20] } }
+    /// # impl Hasher for H32 { type Output = [u8;32]; fn hash(_ : &[u8]) -> [u8;32] { [7;32] } }
+    /// # struct Hashers;
+    /// # impl HasherList for Hashers {
+    /// #     type MD4 = H16; type MD5 = H16; type SHA1 = H20; type SHA256 = H32;
+    /// #     type RIPEMD160 = H20;
+    /// # }
+    /// let pwm = PasswordMaker::<Hashers>::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "me", "", 8, "", "").unwrap();
+    /// let (password, context) = pwm.generate_with_context("example.com", "password").unwrap();
+    /// assert_eq!(password, pwm.generate("example.com", "password").unwrap());
+    /// assert_eq!(context.data(), "example.comme");
+    /// assert_eq!(context.hash_algorithm(), HashAlgorithm::Md5);
+    /// assert_eq!(context.password_length(), 8);
+    /// ```
+    /// 
+    ///  # Errors
+    ///  Same as [`generate`][PasswordMaker::generate].
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn generate_with_context(&self, data: impl Into<String>, key: impl Into<String>) -> Result<(String, GenerationContext), GenerationError> {
+        let (data, key) = self.prepare_input(data.into(), key.into())?;
+        let text_to_use_length = data.len();
+        let context_data = data.clone() + &self.username + &self.modifier;
+        let password = self.generate_password_verified_input(data, key, 0)?;
+        let context = GenerationContext {
+            data : context_data,
+            text_to_use_length,
+            username_length : self.username.len(),
+            hash_algorithm : self.password_part_parameters.hash_algorithm(),
+            password_length : split_into_graphemes(&password).count(),
+        };
+        Ok((password, context))
     }
-}
 
-/// How to handle the URL protocol, or the absence of it, during [`UrlParsing`].
-/// 
-/// # Description
+    /// Same as [`generate`][PasswordMaker::generate], but returns the password as bytes. Meant for callers that use the password
+    /// as a raw key, for instance as input for a key derivation function, rather than displaying it.
+    /// 
+    /// The result is always the same as `generate(data, key).map(String::into_bytes)`, meaning that for charsets with multi-byte
+    /// characters the bytes are the UTF-8 encoding of the password. If all `characters` are single-byte (ASCII), and neither
+    /// prefix, suffix, nor leet after generation are used, the bytes are taken directly from the
+    /// [indices][PasswordMaker::generate_indices], without building a `String` first.
+    /// 
+    ///  # Errors
+    ///  Fails in the cases listed at [`GenerationError`].
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn generate_bytes(&self, data: impl Into<String>, key: impl Into<String>) -> Result<Vec<u8>, GenerationError> {
+        let (data, key) = self.prepare_input(data.into(), key.into())?;
+        self.generate_bytes_verified_input(data, key)
+    }
+
+    /// Computes the digits that [`generate`][PasswordMaker::generate] maps onto the output characters, instead of the password itself.
+    /// 
+    /// The digits are indices into the list of grapheme clusters of `characters` (or of "0123456789abcdef" for the V0.6 algorithms),
+    /// most significant digit of each password part first. Exactly as many digits are returned as the password needs,
+    /// which is `password_length` minus the lengths of `prefix` and `suffix`.
+    /// Mapping the indices onto the characters and adding prefix and suffix yields the output of [`generate`][PasswordMaker::generate],
+    /// unless leet is applied after generation. Leet works on strings, so it is not reflected in the indices.
+    /// 
+    ///  # Errors
+    ///  Fails in the cases listed at [`GenerationError`], except for [`GenerationError::OutputTooLarge`].
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn generate_indices(&self, data: impl Into<String>, key: impl Into<String>) -> Result<Vec<usize>, GenerationError> {
+        let (data, key) = self.prepare_input(data.into(), key.into())?;
+        Ok(self.generate_indices_verified_input(data, key))
+    }
+
+    /// Same as [`generate`][PasswordMaker::generate], but additionally records the intermediate values of each password part.
+    /// 
+    /// This is meant for debugging compatibility issues with PasswordMaker Pro. It is slower than [`generate`][PasswordMaker::generate],
+    /// because every hash is converted twice and all intermediate values get copied. See [`GenerationTrace`] for details.
+    /// 
+    ///  # Errors
+    ///  Fails in the cases listed at [`GenerationError`].
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn generate_with_trace(&self, data: impl Into<String>, key: impl Into<String>) -> Result<(String, GenerationTrace), GenerationError> {
+        let (data, key) = self.prepare_input(data.into(), key.into())?;
+        self.generate_password_verified_input_traced(data, key)
+    }
+
+    /// Computes only the password part with the given index, and returns its intermediate values, most importantly the exact bytes
+    /// passed to the hash function.
+    /// 
+    /// This is meant for diffing against another implementation. The part is computed by the same code as in
+    /// [`generate`][PasswordMaker::generate], so [`hasher_input`][PasswordPartTrace::hasher_input] and
+    /// [`hmac_key`][PasswordPartTrace::hmac_key] include leet, the UTF-16 conversion where applicable, and the part counter. The
+    /// result is the same as entry `part_index` of the trace returned by [`generate_with_trace`][PasswordMaker::generate_with_trace],
+    /// but `part_index` may be larger than the number of parts the password needs.
+    /// 
+    ///  # Errors
+    ///  Fails in the cases listed at [`GenerationError`], except for [`GenerationError::OutputTooLarge`].
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn debug_hash_input(&self, data: impl Into<String>, key: impl Into<String>, part_index : usize) -> Result<PasswordPartTrace, GenerationError> {
+        let (data, key) = self.prepare_input(data.into(), key.into())?;
+        Ok(self.trace_password_part_verified_input(data, key, part_index))
+    }
+
+    /// Generates one password for each of the given `specs`, all from the same `data` and `key`.
+    /// 
+    /// This is meant for use cases like "password + PIN", where several outputs with different character sets or lengths
+    /// should be derived from the same inputs. The hashes of the password parts do not depend on the output characters, so
+    /// each of them is only computed once and then reused for all specs.
+    /// 
+    /// # Determinism
+    /// The password for each spec is exactly the password [`generate`][PasswordMaker::generate] would return for a `PasswordMaker`
+    /// that has the same hash algorithm, leet settings, `username` and `modifier` as this one, but the `characters`, `password_length`,
+    /// `prefix` and `suffix` of the spec. This also means that it matches PasswordMaker Pro for such settings.
+    /// The settings of this `PasswordMaker` that are replaced by the spec are ignored.
+    /// The result does not depend on the order or number of specs. All outputs start at the first password part, so they are
+    /// NOT independent of each other: Two specs with the same characters yield passwords where one is a prefix of the other
+    /// (apart from prefix and suffix, and only if no leet is applied after generation).
+    /// For the V0.6 algorithms the output characters are always hexadecimal digits, and the spec's characters are ignored.
+    /// The same holds for all algorithms if an [`OutputEncoding`] other than `Characters` is selected.
+    /// 
+    ///  # Errors
+    ///  Fails in the cases listed at [`GenerationError`].
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn generate_batch(&self, data: impl Into<String>, key: impl Into<String>, specs : &[OutputSpec]) -> Result<Vec<String>, GenerationError> {
+        let (data, key) = self.prepare_input_for(data.into(), key.into(), Some(specs))?;
+        self.generate_batch_verified_input(data, key, specs)
+    }
+
+    /// Generates a password for each `(data, key)` pair in `inputs`, in parallel on the rayon thread pool.
+    /// 
+    /// The results are in the same order as `inputs`, and each of them is identical to what
+    /// [`generate`][PasswordMaker::generate] returns for the respective pair.
+    /// Only available with the `rayon` feature.
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn generate_parallel(&self, inputs : &[(String, String)]) -> Vec<Result<String, GenerationError>> {
+        use rayon::prelude::*;
+        inputs.par_iter().map(|(data, key)| self.generate(data.as_str(), key.as_str())).collect()
+    }
+
+}
+
+impl<'a, T> PasswordMaker<'a, T>{
+    /// Returns a fingerprint of the settings this `PasswordMaker` was created with.
+    /// 
+    /// The fingerprint covers everything that influences the generated password, except for the `data` and `key` parameters
+    /// of [`generate`][PasswordMaker::generate]: The hash algorithm, the leet settings, the output characters (as grapheme clusters),
+    /// `username`, `modifier`, `password_length`, `prefix`, `suffix` and the Unicode normalization form. It is meant to be used as part of a cache key, for instance
+    /// if an application memoizes generated passwords. The value is stable across program runs and platforms.
+    /// 
+    /// Beware that this is NOT a cryptographic hash. Different settings may, with low probability, have the same fingerprint.
+    #[must_use]
+    pub fn settings_fingerprint(&self) -> u64 {
+        self.compute_settings_fingerprint()
+    }
+
+    /// Sets whether `data` and `key` should be brought into the given Unicode normalization form before hashing.
+    /// 
+    /// The same text can be encoded in different ways in Unicode. For instance "é" can either be a single precomposed character,
+    /// or an "e" followed by a combining accent, depending on how it was typed. Without normalization those yield different passwords.
+    /// PasswordMaker Pro does not normalize its input, so this is disabled (`None`) by default. `username` and `modifier` are not normalized.
+    /// 
+    /// Only available with the `unicode-normalization` feature.
+    #[cfg(feature = "unicode-normalization")]
+    #[must_use]
+    pub fn with_unicode_normalization(self, normalize_unicode : Option<NormalizationForm>) -> Self {
+        PasswordMaker{ normalize_unicode, ..self }
+    }
+
+    /// Sets whether leet that is applied after generation should be skipped if it makes the password much longer.
+    /// 
+    /// Higher leet levels replace many letters by several characters (for instance "m" by "/\\/\\" at level 9). The password is cut
+    /// to `password_length` after leet was applied, so if leet lengthens the output a lot, only few of the generated characters remain.
+    /// If this option is enabled, and leet more than doubles the number of grapheme clusters of the password parts it is applied to,
+    /// the password is generated as if leet was not applied after generation. Leet applied before generation is not affected.
+    /// 
+    /// The decision only depends on the settings and the inputs, so the result is still deterministic. It does however
+    /// differ from PasswordMaker Pro whenever the fallback kicks in, so this is disabled by default.
+    #[must_use]
+    pub fn with_post_leet_fallback(self, post_leet_fallback : bool) -> Self {
+        PasswordMaker{ post_leet_fallback, ..self }
+    }
+
+    /// Sets to which input the password part counter is appended, see [`PartCounterPlacement`].
+    /// 
+    /// This only makes a difference if `password_length` is larger than what a single hash yields, because the
+    /// first password part never gets a counter. The default is [`Key`][PartCounterPlacement::Key], like in PasswordMaker Pro.
+    #[must_use]
+    pub fn with_part_counter_placement(self, part_counter_placement : PartCounterPlacement) -> Self {
+        PasswordMaker{ part_counter_placement, ..self }
+    }
+
+    /// Sets the text that is put between the input and the password part counter.
+    /// 
+    /// PasswordMaker Pro appends `"\n"` followed by the decimal part number, for instance `key + "\n1"` for the second part.
+    /// Some other PasswordMaker ports use a different separator, or none at all. Setting it to theirs allows to reproduce
+    /// their passwords. Like the [placement][PasswordMaker::with_part_counter_placement], this only makes a difference if
+    /// `password_length` is larger than what a single hash yields. The default is `"\n"`.
+    #[must_use]
+    pub fn with_part_counter_separator(self, part_counter_separator : impl Into<Cow<'a, str>>) -> Self {
+        PasswordMaker{ part_counter_separator : part_counter_separator.into(), ..self }
+    }
+
+    /// Sets how the hashes are turned into the password, see [`OutputEncoding`].
+    /// 
+    /// With [`OutputEncoding::Hex`] the `characters` are ignored, and the password is made of the lowercase hexadecimal
+    /// representation of the hashes, like the V0.6 algorithms always do. This works with all hash algorithms, but apart from
+    /// the V0.6 algorithms PasswordMaker Pro has no such option, so the passwords differ from what it generates.
+    /// [`OutputEncoding::Base64`] and [`OutputEncoding::Base58`] work the same way, but use the respective standard encoding of each hash.
+    /// PasswordMaker Pro has neither of them. The password parts are joined and cut to `password_length` as usual.
+    /// The default is [`OutputEncoding::Characters`].
+    #[must_use]
+    pub fn with_output_encoding(self, output_encoding : OutputEncoding) -> Self {
+        PasswordMaker{ password_part_parameters : self.password_part_parameters.with_output_encoding(output_encoding), ..self }
+    }
+
+    /// Sets whether leading zero digits of each hash are kept, see [`OutputPadding`].
+    /// 
+    /// PasswordMaker Pro drops the leading zeros of each hash, except for the V0.6 algorithms. A hash with a small value
+    /// therefore yields fewer characters than [`max_single_hash_length`][PasswordMaker::max_single_hash_length], and the password
+    /// needs more password parts. With [`OutputPadding::LeadingZeros`] every hash yields exactly `max_single_hash_length`
+    /// characters, leading zeros mapped onto the first of the `characters`. The number of password parts then only depends on
+    /// `password_length`, and is what [`estimated_parts`][PasswordMaker::estimated_parts] returns. The password is cut to
+    /// `password_length` as usual, so if it is shorter than a single hash, the padding can take up all of it.
+    /// 
+    /// This only affects modern algorithms with [`OutputEncoding::Characters`]. The default is [`OutputPadding::None`].
+    #[must_use]
+    pub fn with_output_padding(self, output_padding : OutputPadding) -> Self {
+        PasswordMaker{ password_part_parameters : self.password_part_parameters.with_output_padding(output_padding), ..self }
+    }
+
+    /// Sets how the input of the non-HMAC modern algorithms is turned into bytes, see [`InputEncoding`].
+    /// 
+    /// PasswordMaker Pro hashes the input of the HMAC and V0.6 algorithms as UTF-16 with the upper byte of each code unit
+    /// discarded, but the input of the other algorithms as UTF-8. Some ports use the UTF-16 quirk for all algorithms. To get the
+    /// same passwords as those, set [`InputEncoding::Utf16DiscardHighByte`]. For ASCII input both encodings yield the same
+    /// bytes, so this only changes passwords if `data`, `key`, `username` or `modifier` contain other characters.
+    /// 
+    /// This only affects the modern non-HMAC algorithms, like [`HashAlgorithm::Md5`]. The default is [`InputEncoding::Utf8`].
+    #[must_use]
+    pub fn with_input_encoding(self, input_encoding : InputEncoding) -> Self {
+        PasswordMaker{ password_part_parameters : self.password_part_parameters.with_input_encoding(input_encoding), ..self }
+    }
+
+    /// Sets the unit `password_length` and the lengths of prefix and suffix are counted in, see [`LengthUnit`].
+    /// 
+    /// The password is truncated to `password_length` in this unit, after prefix and password have been truncated to leave room for
+    /// the suffix. With [`LengthUnit::CodePoints`] and [`LengthUnit::Utf16CodeUnits`] this can cut a grapheme cluster, for instance
+    /// separate an emoji from its skin tone modifier. This matches implementations that truncate Javascript strings, or that count
+    /// code points. A code point is never cut though: If only one of the two UTF-16 code units of a code point would fit, the code
+    /// point is dropped, and the output is one code unit shorter than `password_length`.
+    /// 
+    /// This only makes a difference if prefix, suffix or the output characters contain characters made of several code points, or,
+    /// for UTF-16, characters outside the Basic Multilingual Plane, like emoji. [`estimated_parts`][PasswordMaker::estimated_parts]
+    /// and [`search_space`][PasswordMaker::search_space] assume each output character is one unit long. With
+    /// [`grapheme_iter`][PasswordMaker::grapheme_iter] a cut grapheme cluster is yielded in pieces.
+    /// 
+    /// The default is [`LengthUnit::GraphemeClusters`].
+    #[must_use]
+    pub fn with_length_unit(self, length_unit : LengthUnit) -> Self {
+        PasswordMaker{ assembly_settings : self.assembly_settings.with_length_unit(length_unit), ..self }
+    }
+
+    /// Sets a revision label, for instance `"v2"`, that is appended to the key.
+    /// 
+    /// If a password has to be changed, for instance after a breach, a new revision label yields a completely different password
+    /// for otherwise unchanged settings and inputs. A memorable label makes it easy to regenerate the current password later.
+    /// The label is appended to the key as `"\n@"` followed by the label, for all password parts. The password part counter, if
+    /// needed, is appended after it. An empty label (the default) appends nothing, so the passwords match PasswordMaker Pro.
+    #[must_use]
+    pub fn with_revision(self, revision : impl Into<Cow<'a, str>>) -> Self {
+        PasswordMaker{ revision : revision.into(), ..self }
+    }
+
+    /// Sets a context string (a domain separation tag), for instance `"email"` or `"banking"`, that is appended to the data.
+    /// 
+    /// This is meant for deriving passwords for distinct purposes from the same master password and the same URL: Different
+    /// context strings yield independent passwords. The context is appended to the data as `"\n#"` followed by the context,
+    /// after `username` and `modifier`, for all password parts. If the part counter is placed in the modifier (see
+    /// [`PartCounterPlacement::Modifier`]), it is appended after the context. Leet that is applied before generation also applies
+    /// to the context. This is an extension of this crate, PasswordMaker Pro has no such option. An empty context (the default)
+    /// appends nothing, so the passwords match PasswordMaker Pro.
+    #[must_use]
+    pub fn with_context_tag(self, context_tag : impl Into<Cow<'a, str>>) -> Self {
+        PasswordMaker{ context_tag : context_tag.into(), ..self }
+    }
+
+    /// Sets whether leet that is applied before generation also applies to `username`.
+    /// 
+    /// PasswordMaker Pro appends `username` and `modifier` to `data`, and applies leet to the result. If the username is, for
+    /// instance, an e-mail address, leeting it might not be wanted. If this is disabled, the username is used as it is.
+    /// 
+    /// If leet is excluded from `username` or `modifier`, the inputs are leeted separately, and then concatenated. As leet converts
+    /// its input to lower case, and lower-casing depends on context (an upper case sigma at the end of a word becomes "ς" instead
+    /// of "σ"), this can also change the leeted parts, compared to leeting the whole input at once.
+    /// Enabled by default, which gives the same results as PasswordMaker Pro. Has no effect if leet is not applied before generation.
+    #[must_use]
+    pub fn with_pre_leet_username(self, pre_leet_username : bool) -> Self {
+        PasswordMaker{ pre_leet_username, ..self }
+    }
+
+    /// Sets whether leet that is applied before generation also applies to `modifier`.
+    /// See [`with_pre_leet_username`][PasswordMaker::with_pre_leet_username] for details.
+    #[must_use]
+    pub fn with_pre_leet_modifier(self, pre_leet_modifier : bool) -> Self {
+        PasswordMaker{ pre_leet_modifier, ..self }
+    }
+
+    /// Returns false if the `characters` passed on creation are not used to generate the password.
+    /// 
+    /// This is the case for the V0.6 algorithms (see [`HashAlgorithm::is_legacy_v06`]) and for all [`OutputEncoding`]s apart
+    /// from `Characters`, which have a fixed set of output characters. User interfaces can use this to disable or flag the
+    /// character set input, so users don't wonder why their carefully chosen characters don't show up in the password.
+    #[must_use]
+    pub fn charset_is_effective(&self) -> bool {
+        self.password_part_parameters.uses_characters()
+    }
+
+    /// Returns the number of output characters, meaning the base the hashes are converted to.
+    /// 
+    /// This is the number of grapheme clusters in `characters`, as generation counts them. If the charset is not effective
+    /// (see [`charset_is_effective`][PasswordMaker::charset_is_effective]), it is the size of the fixed set of characters
+    /// used instead, for instance 16 for the V0.6 algorithms.
+    #[must_use]
+    pub fn charset_len(&self) -> usize {
+        self.password_part_parameters.base()
+    }
+
+    /// Returns how many password parts (hashes) are needed to generate a password with these settings.
+    /// 
+    /// This only looks at the settings, no hashing is done. The number of characters a single hash yields depends on the size of
+    /// the hash and the number of output characters. If `password_length` minus prefix and suffix exceeds it, several hashes need to be
+    /// computed, which makes generation slower. User interfaces can use this to warn about long passwords with small character sets.
+    /// 
+    /// The estimate assumes the largest possible hash value. Apart from the V0.6 algorithms, leading zero digits are dropped, so
+    /// in rare cases one more part is needed. Leet applied after generation can only lengthen the parts, so it is not taken into account.
+    #[must_use]
+    pub fn estimated_parts(&self) -> usize {
+        self.estimate_parts()
+    }
+
+    /// Returns true if generating a password with these settings needs more than one hash, see [`estimated_parts`][PasswordMaker::estimated_parts].
+    #[must_use]
+    pub fn requires_multiple_parts(&self) -> bool {
+        self.estimated_parts() > 1
+    }
+
+    /// Returns how much of `prefix` and `suffix` ends up in the password, and how many characters are generated.
+    /// 
+    /// This only looks at the settings, no hashing is done. Prefix and suffix share `password_length` with the generated characters.
+    /// The suffix is kept first, then the prefix, and the generated characters get what is left. If the suffix alone is longer
+    /// than `password_length`, its end is cut. Otherwise, if prefix and suffix together are too long, the end of the prefix is cut.
+    /// User interfaces can use this to warn that, for instance, only 3 of the 6 characters of the suffix appear in the password.
+    /// All lengths are in the unit set with [`with_length_unit`][PasswordMaker::with_length_unit], grapheme clusters by default.
+    #[must_use]
+    pub fn affix_budget(&self) -> AffixBudget {
+        self.assembly_settings.affix_budget()
+    }
+
+    /// Returns the maximum number of characters a single hash can yield with these settings.
+    /// 
+    /// Each hash is converted to a number in base `characters.len()`, so the largest possible hash value determines how many
+    /// characters one password part can have. For MD5 and the 94 characters of PasswordMaker Pro's default charset this is 20,
+    /// for SHA-256 with hexadecimal output it is 64. Passwords that need more characters (not counting prefix and suffix) are made
+    /// of several password parts, see [`estimated_parts`][PasswordMaker::estimated_parts]. As leading zeros are usually dropped,
+    /// a particular hash might yield fewer characters. Leet applied after generation is not taken into account.
+    #[must_use]
+    pub fn max_single_hash_length(&self) -> usize {
+        self.password_part_parameters.max_digits_per_part()
+    }
+
+    /// Returns the number of different passwords these settings can yield, or `None` if it does not fit into a `u128`.
+    /// 
+    /// This is `charset_len()` to the power of `password_length` minus the lengths of prefix and suffix, as every generated
+    /// character can be any of the output characters. It only looks at the settings, and is meant to tell users how strong their
+    /// configuration is. `None` means the number is astronomically large (for instance more than 19 characters of the default charset).
+    /// 
+    /// This is an upper bound. A single hash can't yield more different values than its bit count allows, for instance 2^128 for MD5.
+    /// Leet applied after generation is not taken into account.
+    #[must_use]
+    pub fn search_space(&self) -> Option<u128> {
+        self.count_possible_passwords()
+    }
+
+    /// Sets an upper limit for the size of generated passwords in bytes, including prefix and suffix.
+    /// 
+    /// `password_length` counts grapheme clusters, which can take many bytes each. With a character set made of emoji, for instance,
+    /// a password takes at least four times as many bytes as it has characters. If a password would exceed the limit, generation
+    /// stops and fails with [`GenerationError::OutputTooLarge`]. This is meant for servers that generate passwords on behalf of
+    /// others, and want to bound the memory used per request. The default is `None`, meaning no limit.
+    #[must_use]
+    pub fn with_max_output_bytes(self, max_output_bytes : Option<usize>) -> Self {
+        PasswordMaker{ max_output_bytes, ..self }
+    }
+
+    /// Sets the minimum length of the master password (`key`), in grapheme clusters.
+    /// 
+    /// Generation fails with [`GenerationError::MasterPasswordTooShort`] if `key` is shorter. The length is counted in grapheme
+    /// clusters, not in bytes, so a password made of non-ASCII characters is not considered longer than it looks. For instance
+    /// "äöü" has a length of 3, even though it takes 6 bytes. The length is checked before Unicode normalization is applied.
+    /// The default is 1, meaning that any non-empty master password is accepted. An empty master password is always rejected
+    /// with [`GenerationError::MissingMasterPassword`].
+    #[must_use]
+    pub fn with_min_key_length(self, min_key_length : usize) -> Self {
+        PasswordMaker{ min_key_length, ..self }
+    }
+
+    /// Rejects characters that contain control characters, like tab or newline.
+    /// 
+    /// Control characters in the output characters are usually a mistake, for instance a trailing newline read from a file.
+    /// Passwords containing them can't be typed in most password fields. If enabled, generation fails with
+    /// [`GenerationError::NonPrintableCharsetEntry`] if any grapheme cluster of the characters contains a code point for which
+    /// [`char::is_control`] is true. Characters that are not used for the output (V0.6 algorithms, or an output encoding other
+    /// than [`OutputEncoding::Characters`]) are not checked. [`generate_batch`][PasswordMaker::generate_batch] checks the
+    /// characters of its [`OutputSpec`]s instead of those of this `PasswordMaker`. The default is `false`, which accepts any characters.
+    #[must_use]
+    pub fn with_strict_charset(self, strict_charset : bool) -> Self {
+        PasswordMaker{ strict_charset, ..self }
+    }
+
+    /// Rejects a `username` or `modifier` that starts or ends with whitespace.
+    /// 
+    /// Both are appended to the data as they are, so a trailing space, for instance copied along with a user name, silently yields
+    /// a different password. If enabled, generation fails with [`GenerationError::UntrimmedField`] in that case. Empty fields are
+    /// fine. The default is `false`, which accepts any `username` and `modifier`, same as PasswordMaker Pro.
+    #[must_use]
+    pub fn with_strict_whitespace(self, strict_whitespace : bool) -> Self {
+        PasswordMaker{ strict_whitespace, ..self }
+    }
+
+    /// Sets an upper limit for the length of `prefix` and `suffix`, in grapheme clusters.
+    /// 
+    /// If either of them is longer, generation fails with [`GenerationError::AffixTooLong`]. [`generate_batch`][PasswordMaker::generate_batch]
+    /// checks the prefixes and suffixes of its [`OutputSpec`]s instead of those of this `PasswordMaker`. Like
+    /// [`with_max_output_bytes`][PasswordMaker::with_max_output_bytes], this is meant for servers that take the settings from
+    /// others, and want to reject unreasonable input early. The check only counts up to the limit, so it's cheap even for huge
+    /// affixes. The default is `None`, meaning no limit.
+    #[must_use]
+    pub fn with_max_affix_length(self, max_affix_length : Option<usize>) -> Self {
+        PasswordMaker{ max_affix_length, ..self }
+    }
+
+    /// Rejects character sets for which the base conversion is not precomputed, and therefore slow.
+    /// 
+    /// Converting a hash of more than 16 bytes to base N, N being the number of output characters, needs the highest power of N
+    /// that fits into the hash. For common charset sizes it is precomputed, see [`is_base_precomputed`] and the crate documentation.
+    /// For all others it is computed at runtime, which takes much longer than the actual hashing, the first time for each size and
+    /// thread if the `cached_max_powers` feature is enabled (as long as the size stays in the cache), otherwise for every password part. If this is enabled, generation
+    /// fails with [`GenerationError::SlowCharset`] instead. This is meant for servers that want predictable latency. 16 byte hashes
+    /// (MD4, MD5, RIPEMD-128, and the V0.6 algorithms) and the Base58 and Base64 output encodings are always fast, and never rejected.
+    /// [`generate_batch`][PasswordMaker::generate_batch] checks the characters of its [`OutputSpec`]s instead of those of this `PasswordMaker`.
+    /// The default is `false`, which accepts any charset.
+    #[must_use]
+    pub fn with_require_fast_base(self, require_fast_base : bool) -> Self {
+        PasswordMaker{ require_fast_base, ..self }
+    }
+
+}
+
+impl<T : HashFunctions> PasswordMaker<'_, T>{
+    /// Checks the hash functions in `T` against the [`KNOWN_ANSWER_VECTORS`].
+    /// 
+    /// Generates a password for each of the vectors, and compares it to the password PasswordMaker Pro generates for the same input.
+    /// This catches mistakes in the [`HasherList`], for instance an SHA-1 implementation in the slot for RIPEMD-160, that
+    /// would otherwise silently produce wrong passwords. It is cheap enough to be run at application startup.
+    /// 
+    /// # Errors
+    /// Returns a [`SelfTestError`] that lists the algorithms for which the generated password did not match.
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn self_test() -> Result<(), SelfTestError> {
+        let failed_algorithms : Vec<_> = KNOWN_ANSWER_VECTORS.iter()
+            .filter(|vector| {
+                let password = Self::new(
+                    vector.hash_algorithm,
+                    vector.use_leet,
+                    vector.characters,
+                    vector.username,
+                    vector.modifier,
+                    vector.password_length,
+                    vector.prefix,
+                    vector.suffix,
+                ).ok().and_then(|pwm| pwm.generate(vector.data, vector.key).ok());
+                password.as_deref() != Some(vector.expected)
+            })
+            .map(|vector| vector.hash_algorithm)
+            .collect();
+        if failed_algorithms.is_empty() {
+            Ok(())
+        } else {
+            Err(SelfTestError { failed_algorithms })
+        }
+    }
+
+    /// Generates a password with each of the [`HashAlgorithm`]s, and groups the algorithms by the password they yield.
+    /// 
+    /// For short passwords and small character sets, different algorithms can yield the same password. This lets users pick an
+    /// algorithm that stands out for their inputs. The passwords are generated without leet, username, modifier, prefix and suffix.
+    /// The V0.6 algorithms ignore `characters`, see [`HashAlgorithm::is_legacy_v06`]. RIPEMD-128 and Tiger are only included if
+    /// `T` is [`Extended`].
+    /// 
+    /// Beware that the keys of the returned map are the generated passwords. Do not log or store them.
+    /// 
+    /// # Errors
+    /// Fails if either `data` or `key` has zero-length.
+    /// 
+    /// # Panics
+    /// Panics if a hash function returns a different number of bytes than the algorithm needs, see [`Hasher`].
+    pub fn compare_algorithms(data : &str, key : &str, characters : &PreparedCharset, password_length : usize) -> Result<BTreeMap<String, Vec<HashAlgorithm>>, GenerationError> {
+        let mut groups : BTreeMap<String, Vec<HashAlgorithm>> = BTreeMap::new();
+        for hash_algorithm in HashAlgorithm::all().iter().copied().filter(|algorithm| T::EXTENDED || !algorithm.needs_extended_hashers()) {
+            let pwm = PasswordMaker::<T>::new_with_prepared_charset(hash_algorithm, UseLeetWhenGenerating::NotAtAll, characters, "", "", password_length, "", "");
+            groups.entry(pwm.generate(data, key)?).or_default().push(hash_algorithm);
+        }
+        Ok(groups)
+    }
+
+    /// Runs the same checks on the input and settings as the generation methods, but reports all problems instead of only the first.
+    /// 
+    /// This is meant for forms, that should show every problem at once, for instance a missing master password and an overly long
+    /// prefix. Nothing is hashed. The limit set with [`with_max_output_bytes`][PasswordMaker::with_max_output_bytes] is not checked,
+    /// as it depends on the generated password.
+    /// 
+    /// # Errors
+    /// Fails with each of the problems listed at [`GenerationError`] that this input has, in the order listed there.
+    pub fn validate_all(&self, data : &str, key : &str) -> Result<(), Vec<GenerationError>> {
+        collect_errors(&self.input_checks(data, key))
+    }
+}
+
+//Not bound by HashFunctions, so that try_generate can use these, too.
+impl<T : passwordmaker::HashFunctionsImpl> PasswordMaker<'_, T>{
+    fn prepare_input(&self, data : String, key : String) -> Result<(String, String), GenerationError> {
+        self.prepare_input_for(data, key, None)
+    }
+
+    /// If `specs` are given, their output settings are checked instead of those of `self`, see `generate_batch`.
+    fn prepare_input_for(&self, data : String, key : String, specs : Option<&[OutputSpec]>) -> Result<(String, String), GenerationError> {
+        self.validate_input(&data, &key, specs)?;
+        #[cfg(feature = "unicode-normalization")]
+        let (data, key) = match self.normalize_unicode {
+            None => (data, key),
+            Some(form) => (form.normalize(&data), form.normalize(&key)),
+        };
+        Ok((data, key))
+    }
+
+    fn check_affix_length(&self, field : &'static str, affix : &str) -> Result<(), GenerationError> {
+        match self.max_affix_length {
+            Some(max) if split_into_graphemes(affix).nth(max).is_some() => Err(GenerationError::AffixTooLong { field, max }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_whitespace(&self, field : &'static str, value : &str) -> Result<(), GenerationError> {
+        if self.strict_whitespace && value.trim() != value {
+            Err(GenerationError::UntrimmedField { field })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_base(&self, base : usize) -> Result<(), GenerationError> {
+        if self.require_fast_base && self.password_part_parameters.needs_slow_base_conversion(base) {
+            Err(GenerationError::SlowCharset)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_hash_functions(&self) -> Result<(), GenerationError> {
+        if T::EXTENDED || !self.password_part_parameters.hash_algorithm().needs_extended_hashers() {
+            Ok(())
+        } else {
+            Err(GenerationError::UnavailableAlgorithm)
+        }
+    }
+
+    fn check_charset(&self, characters : Option<&GraphemeList>) -> Result<(), GenerationError> {
+        let control_character = if self.strict_charset { self.password_part_parameters.find_control_character(characters) } else { None };
+        control_character.map_or(Ok(()), |index| Err(GenerationError::NonPrintableCharsetEntry { index }))
+    }
+
+    /// The checks of the output settings. If given, those of `spec` are checked instead of those of `self`, see `generate_batch`.
+    fn output_checks(&self, spec : Option<&OutputSpec>) -> [Result<(), GenerationError>; 4] {
+        let characters = spec.map(|spec| GraphemeList::new(Cow::Borrowed(spec.characters)));
+        let (prefix, suffix) = spec.map_or((self.assembly_settings.prefix(), self.assembly_settings.suffix()), |spec| (spec.prefix, spec.suffix));
+        let base = characters.as_ref().map_or(self.password_part_parameters.base(), |characters| self.password_part_parameters.base_with(characters));
+        [
+            self.check_affix_length("prefix", prefix),
+            self.check_affix_length("suffix", suffix),
+            self.check_charset(characters.as_ref()),
+            self.check_base(base),
+        ]
+    }
+
+    /// All input checks, in the order in which the generation methods report them.
+    fn input_checks(&self, data : &str, key : &str) -> [Result<(), GenerationError>; 9] {
+        let [prefix, suffix, charset, base] = self.output_checks(None);
+        [
+            check_text_to_use(data),
+            check_master_password(key, self.min_key_length),
+            self.check_whitespace("username", &self.username),
+            self.check_whitespace("modifier", &self.modifier),
+            prefix,
+            suffix,
+            charset,
+            base,
+            self.check_hash_functions(),
+        ]
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(
+        algorithm = ?self.password_part_parameters.hash_algorithm(),
+        length = self.assembly_settings.password_length(),
+        min_key_length = self.min_key_length,
+    )))]
+    fn validate_input(&self, data : &str, key : &str, specs : Option<&[OutputSpec]>) -> Result<(), GenerationError> {
+        let checks = self.input_checks(data, key);
+        match specs {
+            None => checks.iter().copied().collect(),
+            Some(specs) => {
+                //Same order, but the output settings of self are not used, so the checks of the specs replace them.
+                let [text_to_use, master_password, username, modifier, _, _, _, _, hash_functions] = checks;
+                [text_to_use, master_password, username, modifier].iter().copied().collect::<Result<(), _>>()?;
+                for spec in specs {
+                    self.output_checks(Some(spec)).iter().copied().collect::<Result<(), _>>()?;
+                }
+                hash_functions
+            },
+        }
+    }
+}
+
+fn check_text_to_use(data : &str) -> Result<(), GenerationError> {
+    if data.is_empty() {
+        Err(GenerationError::MissingTextToUse)
+    } else {
+        Ok(())
+    }
+}
+
+fn check_master_password(key : &str, min_key_length : usize) -> Result<(), GenerationError> {
+    if key.is_empty() {
+        return Err(GenerationError::MissingMasterPassword);
+    }
+    //Only counting up to the minimum, as that's all we need to know. If it's too short, this is the actual length.
+    let got = split_into_graphemes(key).take(min_key_length).count();
+    if got < min_key_length {
+        Err(GenerationError::MasterPasswordTooShort { min : min_key_length, got })
+    } else {
+        Ok(())
+    }
+}
+
+fn collect_errors(checks : &[Result<(), GenerationError>]) -> Result<(), Vec<GenerationError>> {
+    let errors : Vec<_> = checks.iter().filter_map(|check| check.err()).collect();
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+impl<L : TryHasherList> PasswordMaker<'_, Fallible<L>> {
+    /// Same as [`generate`][PasswordMaker::generate], but for hash functions that can fail. See [`TryHasherList`].
+    /// 
+    /// Generation stops calling the hash functions after the first failure, and that failure is returned.
+    /// 
+    ///  # Errors
+    ///  Fails with [`TryGenerationError::Hasher`] if one of the hash functions failed.
+    ///  Fails with [`TryGenerationError::Generation`] in the cases listed at [`GenerationError`].
+    pub fn try_generate(&self, data: impl Into<String>, key: impl Into<String>) -> Result<String, TryGenerationError<L::Error>> {
+        let (data, key) = self.prepare_input(data.into(), key.into())?;
+        passwordmaker::catch_hasher_error(|| self.generate_password_verified_input(data, key, 0))
+    }
+}
+
+/// Settings, inputs and expected output of a single password generation, see [`KNOWN_ANSWER_VECTORS`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct KnownAnswerVector {
+    /// The hash algorithm under test.
+    pub hash_algorithm : HashAlgorithm,
+    /// When to apply leet.
+    pub use_leet : UseLeetWhenGenerating,
+    /// The output character set.
+    pub characters : &'static str,
+    /// The username.
+    pub username : &'static str,
+    /// The modifier.
+    pub modifier : &'static str,
+    /// The password length, including prefix and suffix.
+    pub password_length : usize,
+    /// The password prefix.
+    pub prefix : &'static str,
+    /// The password suffix.
+    pub suffix : &'static str,
+    /// The data (used text from the URL).
+    pub data : &'static str,
+    /// The key (master password).
+    pub key : &'static str,
+    /// The password PasswordMaker Pro generates for these settings and inputs.
+    pub expected : &'static str,
+}
+
+//Long data and key, so that HMAC needs to hash the key, and non-ASCII data, so that the V0.6 byte handling is covered.
+const EACH_ALGORITHM_VECTOR : KnownAnswerVector = KnownAnswerVector {
+    hash_algorithm : HashAlgorithm::Md4,
+    use_leet : UseLeetWhenGenerating::Before { level : LeetLevel::Nine },
+    characters : "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+    username : "max_mustermann",
+    modifier : "modification",
+    password_length : 64,
+    prefix : "pre",
+    suffix : "suf",
+    data : ".0123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789öä@€Whatever",
+    key : "0123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789",
+    expected : "preBhaY7RkI3FU2Dd1gGbkHGXHcaS9Rla5yOyBsxtRhYjUV4CoEKST1N73Ipmsuf",
+};
+
+/// Known-answer test vectors, used by [`PasswordMaker::self_test`].
+/// 
+/// There is one vector for each [`HashAlgorithm`] PasswordMaker Pro offers. RIPEMD-128 and Tiger are not covered: PasswordMaker
+/// Pro has neither, so there are no passwords generated by it to compare against.
+pub const KNOWN_ANSWER_VECTORS : &[KnownAnswerVector] = &[
+    EACH_ALGORITHM_VECTOR,
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::HmacMd4, expected : "preCWxYmOtw9SouPQCHRRxLWODlFQ5LFitOpYMLHdnELniLHtQpdH5U2eOAOHsuf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::Md5, expected : "preDPeKYAEZMwmB99f7i48vWApmx8ZLbz46s2nyG6KNO00G4nEElILxWAtGLGsuf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::Md5Version06, expected : "pred554290760c59fd928b7aae270c31fdbb8857442f34d92cdaca38fcfc0suf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::HmacMd5, expected : "preGWR5UvFWn69uJQqedOi07JErUQfxJLLB3ZlLPjltwUI0HFDoN6p5xhGdd5suf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::HmacMd5Version06, expected : "pread606e84133024f01831a2ce2f6728654bca7c4dd8098ce2e6f75693d2suf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::Sha1, expected : "preWiv8G0J7zvTurM7Mwjy7LeXKBfbJCqJtP6EOAR8dhgF8dFh6h3OCUybzwusuf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::HmacSha1, expected : "prekLwEUc8ccgo6cK6nct1E58HZu3x5q0yCN8HHLVMx0QzjKMAfHCMyGeZxFEsuf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::Sha256, expected : "pregtXH0tXq1nKXH6adaYb9dtXgCAFl2cqCPMQW3E7EeDggB5Oft4HaNdq5uRsuf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::HmacSha256, expected : "pre5oyv5RXFzY0NiZF4b5JWQj5RUtotkI5dbJOeRJmSjpiYllu5ZZ8FXZqyY4suf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::Ripemd160, expected : "preFJeYiiAXx8Aa1Fhvyy0ffw7D9AMU2qKLg5BBjwZxyT6rsbHctS1Yv1PhGjsuf", ..EACH_ALGORITHM_VECTOR },
+    KnownAnswerVector { hash_algorithm : HashAlgorithm::HmacRipemd160, expected : "preZ1zVB4UtRfvu6PhBvMPTkmAbX9WZ6Xzqb20OKFmKrFMfyF2eB4ImF2fhmWsuf", ..EACH_ALGORITHM_VECTOR },
+];
+
+/// A validated character set, that has already been split into grapheme clusters.
+/// 
+/// Splitting the characters into grapheme clusters is part of [`PasswordMaker::new`]. If many `PasswordMaker`s with the same
+/// characters are needed, a `PreparedCharset` can be created once, and then be passed to [`PasswordMaker::new_with_prepared_charset`].
+#[derive(Clone)]
+pub struct PreparedCharset {
+    graphemes : GraphemeList<'static>,
+}
+
+impl PreparedCharset {
+    /// Validates the characters and splits them into grapheme clusters.
+    /// 
+    /// # Errors
+    /// Fails if characters does not contain at least 2 grapheme clusters. See [`PasswordMaker::new`].
+    pub fn new(characters : impl Into<String>) -> Result<Self, SettingsError> {
+        let characters = characters.into();
+        validate_output_characters(&characters)?;
+        Ok(PreparedCharset { graphemes : GraphemeList::new(Cow::Owned(characters)) })
+    }
+
+    /// Creates a charset from a list of symbols. Each symbol is used as exactly one output character.
+    /// 
+    /// Unlike [`new`][PreparedCharset::new], the symbols are not split into grapheme clusters. This is useful if a symbol should be
+    /// used as a whole, even though Unicode segmentation would split it, or if neighbouring symbols would otherwise be merged,
+    /// like a letter followed by a combining accent. Accepts both `&[&str]` and `&[String]`. Empty symbols are skipped.
+    /// 
+    /// If leet is applied after generation, the leeted password parts are split into grapheme clusters again, as leet works on text.
+    /// 
+    /// # Errors
+    /// Fails if `symbols` does not contain at least 2 non-empty symbols.
+    pub fn from_symbols<S : AsRef<str>>(symbols : &[S]) -> Result<Self, SettingsError> {
+        let graphemes = GraphemeList::from_symbols(symbols);
+        validate_output_symbols(&graphemes)?;
+        Ok(PreparedCharset { graphemes })
+    }
+
+    /// The characters this charset was created from. For charsets created with [`from_symbols`][PreparedCharset::from_symbols],
+    /// this is the concatenation of the symbols.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.graphemes.as_str()
+    }
+
+    /// The symbols of this charset, in order. Each symbol is one character of the generated password, and the digits of the
+    /// hash are indices into this list, see [`PasswordMaker::generate_indices`].
+    /// 
+    /// For charsets created with [`new`][PreparedCharset::new] these are the grapheme clusters of the characters, the same as
+    /// returned by [`graphemes`]. For charsets created with [`from_symbols`][PreparedCharset::from_symbols] these are the
+    /// non-empty symbols.
+    /// 
+    /// ```
+    /// # use passwordmaker_rs::PreparedCharset;
+    /// let charset = PreparedCharset::new("ae\u{301}x").unwrap();
+    /// assert_eq!(charset.symbols().collect::<Vec<_>>(), ["a", "e\u{301}", "x"]);
+    /// let charset = PreparedCharset::from_symbols(&["a", "e", "\u{301}", "x"]).unwrap();
+    /// assert_eq!(charset.symbols().collect::<Vec<_>>(), ["a", "e", "\u{301}", "x"]);
+    /// ```
+    pub fn symbols(&self) -> impl Iterator<Item=&str> {
+        self.graphemes.symbols()
+    }
+
+    /// The number of symbols, see [`symbols`][PreparedCharset::symbols]. This is the base the hashes are converted to.
+    #[must_use]
+    pub fn symbol_count(&self) -> usize {
+        self.graphemes.len()
+    }
+}
+
+impl std::fmt::Debug for PreparedCharset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PreparedCharset").field(&self.as_str()).finish()
+    }
+}
+
+/// The output settings of a single password generated by [`PasswordMaker::generate_batch`].
+#[derive(Debug, Clone, Copy)]
+pub struct OutputSpec<'a> {
+    characters : &'a str,
+    password_length : usize,
+    prefix : &'a str,
+    suffix : &'a str,
+}
+
+impl<'a> OutputSpec<'a> {
+    /// Validates the output settings and returns an `OutputSpec` if they are valid.
+    /// The parameters have the same meaning as the parameters of the same name of [`PasswordMaker::new`].
+    /// 
+    /// # Errors
+    /// Fails if characters does not contain at least 2 grapheme clusters.
+    pub fn new(characters : &'a str, password_length : usize, prefix : &'a str, suffix : &'a str) -> Result<Self, SettingsError> {
+        validate_output_characters(characters)?;
+        Ok(OutputSpec { characters, password_length, prefix, suffix })
+    }
+}
+
+impl<T> PasswordMaker<'static, T>{
+    /// Same as [`new`][PasswordMaker::new], but takes ownership of the string parameters.
+    /// 
+    /// The returned `PasswordMaker` does not borrow anything, so it can for instance be stored in a struct
+    /// or moved to another thread without having to keep the settings strings around.
+    /// 
+    /// # Errors
+    /// Fails if characters does not contain at least 2 grapheme clusters. See [`new`][PasswordMaker::new].
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_owned(
+        hash_algorithm : HashAlgorithm,
+        use_leet : UseLeetWhenGenerating,
+        characters : String,
+        username : String,
+        modifier: String,
+        password_length : usize,
+        prefix : String,
+        suffix : String,
+    ) -> Result<Self, SettingsError> {
+        Self::from_cow_parameters(
+            hash_algorithm,
+            use_leet,
+            Cow::Owned(characters),
+            Cow::Owned(username),
+            Cow::Owned(modifier),
+            password_length,
+            Cow::Owned(prefix),
+            Cow::Owned(suffix),
+        )
+    }
+}
+
+/// A complete, validated set of settings, meant for frontends that read their configuration from text, like a command line tool.
+/// 
+/// # Description
+/// Holds everything needed to create a [`PasswordMaker`] and a [`UrlParsing`]. The settings are owned, so they can be kept
+/// around, and a `PasswordMaker` borrowing from them can be created whenever needed. The output characters have already been
+/// validated and split into grapheme clusters, see [`PreparedCharset`].
+/// 
+/// [`Settings::default`] returns the factory defaults of PasswordMaker Pro, see [`from_kv`][Settings::from_kv] for the values.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    hash_algorithm : HashAlgorithm,
+    use_leet : UseLeetWhenGenerating,
+    characters : PreparedCharset,
+    username : String,
+    modifier : String,
+    password_length : usize,
+    prefix : String,
+    suffix : String,
+    url_parsing : UrlParsing,
+}
+
+impl Settings {
+    /// Reads the settings from a flat map of strings, for instance parsed from a config file or from environment variables.
+    /// 
+    /// The following keys are understood. Missing keys take the default value, which is PasswordMaker Pro's default.
+    /// Keys not listed here are ignored, so the map may contain other entries too.
+    /// 
+    /// | Key          | Value                                                                           | Default                      |
+    /// |--------------|---------------------------------------------------------------------------------|------------------------------|
+    /// | `algorithm`  | A [`HashAlgorithm`] variant name, case-insensitive, e.g. `sha256`               | `md5`                        |
+    /// | `leet`       | `none`, or `before`, `after` or `both`, followed by `:` and a level 0-9, e.g. `after:6` | `none`                |
+    /// | `characters` | The output characters, at least 2 grapheme clusters                             | letters, digits and symbols  |
+    /// | `username`   | Any text                                                                        | empty                        |
+    /// | `modifier`   | Any text                                                                        | empty                        |
+    /// | `length`     | The password length, a non-negative integer                                     | `8`                          |
+    /// | `prefix`     | Any text                                                                        | empty                        |
+    /// | `suffix`     | Any text                                                                        | empty                        |
+    /// | `protocol`   | A [`ProtocolUsageMode`] variant name, case-insensitive, e.g. `used`            | `ignored`                    |
+    /// | `userinfo`   | `true` or `false`                                                               | `false`                      |
+    /// | `subdomains` | `true` or `false`                                                               | `false`                      |
+    /// | `domain`     | `true` or `false`                                                               | `true`                       |
+    /// | `port_path`  | `true` or `false`                                                               | `false`                      |
+    /// 
+    /// The default characters are the 94 characters of PasswordMaker Pro's "Alphanumerics + Symbols" set.
+    /// 
+    /// # Errors
+    /// Fails with [`SettingsError::InvalidValue`] if a value cannot be parsed, and with [`SettingsError::InsufficientCharset`] if
+    /// `characters` does not contain at least 2 grapheme clusters.
+    pub fn from_kv(map : &BTreeMap<String, String>) -> Result<Self, SettingsError> {
+        settings::from_kv(map)
+    }
+
+    /// Creates a [`PasswordMaker`] for these settings. The options that go beyond PasswordMaker Pro are disabled, and can be
+    /// enabled on the returned instance with the respective `with_` methods.
+    #[must_use]
+    pub fn password_maker<T>(&self) -> PasswordMaker<'_, T> {
+        PasswordMaker::new_with_prepared_charset(
+            self.hash_algorithm,
+            self.use_leet,
+            &self.characters,
+            &self.username,
+            &self.modifier,
+            self.password_length,
+            &self.prefix,
+            &self.suffix,
+        )
+    }
+
+    /// Checks whether a password can be generated from `data` and `key` with the [`PasswordMaker`] returned by
+    /// [`password_maker`][Settings::password_maker], and reports all problems instead of only the first.
+    /// 
+    /// The settings themselves were already validated when they were created. If the returned `PasswordMaker` is configured
+    /// further, for instance with [`with_strict_whitespace`][PasswordMaker::with_strict_whitespace], use
+    /// [`PasswordMaker::validate_all`] instead, so that those options are checked too.
+    /// 
+    /// # Errors
+    /// Fails with [`GenerationError::MissingTextToUse`] if `data` is empty, and with [`GenerationError::MissingMasterPassword`]
+    /// if `key` is empty. If both are empty, both errors are returned.
+    pub fn validate_all(&self, data : &str, key : &str) -> Result<(), Vec<GenerationError>> {
+        //A PasswordMaker created by password_maker() accepts any non-empty key.
+        collect_errors(&[check_text_to_use(data), check_master_password(key, 1)])
+    }
+
+    /// The URL parsing settings.
+    #[must_use]
+    pub fn url_parsing(&self) -> &UrlParsing {
+        &self.url_parsing
+    }
+}
+
+impl Default for Settings {
+    /// Returns the factory defaults of PasswordMaker Pro: MD5, no leet, the 94 characters of the "Alphanumerics + Symbols"
+    /// set, a length of 8, no username, modifier, prefix or suffix, and only the domain of the URL is used.
+    fn default() -> Self {
+        settings::factory_defaults()
+    }
+}
+
+/// Intermediate values recorded by [`PasswordMaker::generate_with_trace`].
+/// 
+/// # Description
+/// PasswordMaker Pro generates long passwords by concatenating several password parts. Each part is computed by hashing
+/// the input (with a different key for each part), and converting the hash to a number in base N, N being the number of
+/// grapheme clusters in the output character set. The digits of that number are then used as indices into the character set.
+/// This trace contains one entry for each password part that was computed, in order.
+#[derive(Debug, Clone)]
+pub struct GenerationTrace {
+    parts : Vec<PasswordPartTrace>,
+}
+
+impl GenerationTrace {
+    /// The recorded password parts, in the order they were generated.
+    #[must_use]
+    pub fn parts(&self) -> &[PasswordPartTrace] {
+        &self.parts
+    }
+}
+
+/// Intermediate values of a single password part. See [`GenerationTrace`].
+#[derive(Debug, Clone)]
+pub struct PasswordPartTrace {
+    hmac_key : Option<Vec<u8>>,
+    hasher_input : Vec<u8>,
+    hash : Vec<u8>,
+    digits : Vec<usize>,
+}
+
+impl PasswordPartTrace {
+    /// The key bytes passed to the HMAC function, after leet and (where applicable) UTF-16 conversion have been applied. `None` for non-HMAC algorithms.
+    #[must_use]
+    pub fn hmac_key(&self) -> Option<&[u8]> {
+        self.hmac_key.as_deref()
+    }
+    /// The message bytes passed to the hash (or HMAC) function, after leet and (where applicable) UTF-16 conversion have been applied.
+    #[must_use]
+    pub fn hasher_input(&self) -> &[u8] {
+        &self.hasher_input
+    }
+    /// The raw output of the hash (or HMAC) function.
+    #[must_use]
+    pub fn hash(&self) -> &[u8] {
+        &self.hash
+    }
+    /// The hash converted to base N, most significant digit first. Leading zeros are already removed, unless a V0.6 algorithm is used.
+    /// Each digit is an index into the list of grapheme clusters of the output character set.
+    /// If an [`OutputEncoding`] other than `Characters` is selected, these are the digits of that encoding instead.
+    #[must_use]
+    pub fn digits(&self) -> &[usize] {
+        &self.digits
+    }
+}
+
+/// The leet level to use. The higher the value, the more obfuscated the results.
+#[cfg_attr(test, derive(strum_macros::EnumIter))]
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
+#[derive(Debug,Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LeetLevel {
+    /// Leet level that does not replace any letters:\
+    /// `["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z"]`
+    /// 
+    /// Meant for user interfaces that offer leet levels from 0 to 9. Beware that this is not the same as
+    /// [`UseLeetWhenGenerating::NotAtAll`]: Like every leet level, it converts its input to lower case. For instance with
+    /// [`UseLeetWhenGenerating::Before`], `"Example.com"` and `"example.com"` yield the same password.
+    Zero,
+    /// First Leet level:\
+    /// `["4", "b", "c", "d", "3", "f", "g", "h", "i", "j", "k", "1", "m", "n", "0", "p", "9", "r", "s", "7", "u", "v", "w", "x", "y", "z"]`
+    One,
+    /// Second Leet level:\
+    /// `["4", "b", "c", "d", "3", "f", "g", "h", "1", "j", "k", "1", "m", "n", "0", "p", "9", "r", "5", "7", "u", "v", "w", "x", "y", "2"]`
+    Two,
+    /// Third Leet level:\
+    /// `["4", "8", "c", "d", "3", "f", "6", "h", "'", "j", "k", "1", "m", "n", "0", "p", "9", "r", "5", "7", "u", "v", "w", "x", "'/", "2"]`
+    Three,
+    /// Fourth Leet level:\
+    /// `["@", "8", "c", "d", "3", "f", "6", "h", "'", "j", "k", "1", "m", "n", "0", "p", "9", "r", "5", "7", "u", "v", "w", "x", "'/", "2"]`
+    Four,
+    /// Fifth Leet level:\
+    /// `["@", "|3", "c", "d", "3", "f", "6", "#", "!", "7", "|<", "1", "m", "n", "0", "|>", "9", "|2", "$", "7", "u", "\\/", "w", "x", "'/", "2"]`
+    Five,
+    /// Sixth Leet level:\
+    /// `["@", "|3", "c", "|)", "&", "|=", "6", "#", "!", ",|", "|<", "1", "m", "n", "0", "|>", "9", "|2", "$", "7", "u", "\\/", "w", "x", "'/", "2"]`
+    Six,
+    /// Seventh Leet level:\
+    /// `["@", "|3", "[", "|)", "&", "|=", "6", "#", "!", ",|", "|<", "1", "^^", "^/", "0", "|*", "9", "|2", "5", "7", "(_)", "\\/", "\\/\\/", "><", "'/", "2"]`
+    Seven,
+    /// Eigth Leet level:\
+    /// `["@", "8", "(", "|)", "&", "|=", "6", "|-|", "!", "_|", "|(", "1", "|\\/|", "|\\|", "()", "|>", "(,)", "|2", "$", "|", "|_|", "\\/", "\\^/", ")(", "'/", "\"/_"]`
+    Eight,
+    /// Ninth Leet level:\
+    /// `["@", "8", "(", "|)", "&", "|=", "6", "|-|", "!", "_|", "|{", "|_", "/\\/\\", "|\\|", "()", "|>", "(,)", "|2", "$", "|", "|_|", "\\/", "\\^/", ")(", "'/", "\"/_"]`
+    Nine,
+}
+
+impl LeetLevel {
+    /// Returns the replacements this leet level uses for the letters a to z, in that order.
+    /// 
+    /// Letters that are not replaced map to themselves. Meant for previews in user interfaces.
+    /// Beware that leet also converts its whole input to lower case, what this table can't show.
+    #[must_use]
+    pub fn replacement_table(&self) -> [&'static str; 26] {
+        *LeetReplacementTable::get(*self).replacements()
+    }
+}
+
+/// The hash algorithm to use, as shown in the GUI of the JavaScript edition of PasswordMaker Pro.
+/// 
+/// # Description 
+/// 
+/// Most algorithms work by computing the hash of the input values and doing a number system base conversion to indices into
+/// the supplied character array.
+/// Notable exceptions are the HMAC algorithms, which not only compute the HMAC for the input, but also, before that, encode the
+/// input as UTF-16 and discard all upper bytes.
+/// The `Md5Version06` variant is for compatibility with ancient versions of PasswordMaker Pro. Not only does it also do the conversion
+/// to UTF-16 and the discarding of the upper bytes, in addition it disregards the user-supplied character set completely, and instead
+/// just outputs the hash encoded as hexadecimal numbers.
+/// The `HmacMd5Version06` is similarly ignoring the supplied characters and using hexadecimal numbers as output.
+/// 
+/// Each password part of the V0.6 algorithms yields exactly 32 hexadecimal digits, including leading zeros. Longer passwords
+/// are made of several parts, exactly like for the other algorithms and in PasswordMaker Pro: The second part hashes the
+/// same data with `"\n1"` appended to the key, the third one with `"\n2"`, and so on. The parts are concatenated, and the
+/// result is cut to the password length. So a V0.6 password of length 33 is the password of length 32, followed by the first
+/// digit of the second part.
+/// 
+/// Further algorithms may be added in minor releases. See [Forward compatibility](crate#forward-compatibility).
+#[cfg_attr(test, derive(strum_macros::EnumIter))]
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
+#[derive(Debug,Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HashAlgorithm {
+    /// Regular Md4 PasswordMaker Pro setting.
+    Md4,
+    /// HAMC Md4 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
+    HmacMd4,
+    /// Regular Md5 PasswordMaker Pro setting.
+    Md5,
+    /// Md5 as computed by PasswordMaker Pro version 0.6. Encodes input as UTF-16 and discards upper byte and outputs MD5 as hex number.
+    Md5Version06,
+    /// HMAC Md5 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
+    HmacMd5,
+    /// HMAC Md5 as computed by PasswordMaker Pro version 0.6. Encodes input as UTF-16 and discards upper byte and outputs MD5 as hex number.
+    HmacMd5Version06,
+    /// Regular Sha1 PasswordMaker Pro setting.
+    Sha1,
+    /// HAMC Sha1 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
+    HmacSha1,
+    /// Regular Sha256 PasswordMaker Pro setting.
+    Sha256,
+    /// HAMC Sha256 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
+    HmacSha256,
+    /// Regular Ripemd160 PasswordMaker Pro setting.
+    Ripemd160,
+    /// HAMC Ripemd160 PasswordMaker Pro setting. Encodes input as UTF-16 and discards upper byte (just as PasswordMaker Pro does for HMAC).
+    HmacRipemd160,
+    /// Ripemd128. Not offered by PasswordMaker Pro, but by some other legacy PasswordMaker compatible tools.
+    /// Needs an [`ExtendedHasherList`].
+    Ripemd128,
+    /// HMAC Ripemd128. Not offered by PasswordMaker Pro, but by some other legacy PasswordMaker compatible tools.
+    /// Needs an [`ExtendedHasherList`].
+    HmacRipemd128,
+    /// Tiger. Not offered by PasswordMaker Pro, but by some other legacy PasswordMaker compatible tools.
+    /// Needs an [`ExtendedHasherList`].
+    Tiger,
+    /// HMAC Tiger. Not offered by PasswordMaker Pro, but by some other legacy PasswordMaker compatible tools.
+    /// Needs an [`ExtendedHasherList`].
+    HmacTiger,
+}
+
+impl HashAlgorithm {
+    /// Returns all variants, in the order they are declared in. This is the order PasswordMaker Pro lists them in,
+    /// followed by the algorithms that PasswordMaker Pro does not offer.
+    /// 
+    /// Meant to fill a selection list in a user interface. The entries' [`Display`] output is the variant name, which is also
+    /// what `FromStr` (with the `strum` feature) expects.
+    #[must_use]
+    pub fn all() -> &'static [HashAlgorithm] {
+        &[
+            HashAlgorithm::Md4,
+            HashAlgorithm::HmacMd4,
+            HashAlgorithm::Md5,
+            HashAlgorithm::Md5Version06,
+            HashAlgorithm::HmacMd5,
+            HashAlgorithm::HmacMd5Version06,
+            HashAlgorithm::Sha1,
+            HashAlgorithm::HmacSha1,
+            HashAlgorithm::Sha256,
+            HashAlgorithm::HmacSha256,
+            HashAlgorithm::Ripemd160,
+            HashAlgorithm::HmacRipemd160,
+            HashAlgorithm::Ripemd128,
+            HashAlgorithm::HmacRipemd128,
+            HashAlgorithm::Tiger,
+            HashAlgorithm::HmacTiger,
+        ]
+    }
+
+    /// Returns true for the HMAC variants, including [`HmacMd5Version06`][HashAlgorithm::HmacMd5Version06].
+    #[must_use]
+    pub fn is_hmac(self) -> bool {
+        match self {
+            HashAlgorithm::HmacMd4
+             | HashAlgorithm::HmacMd5
+             | HashAlgorithm::HmacMd5Version06
+             | HashAlgorithm::HmacSha1
+             | HashAlgorithm::HmacSha256
+             | HashAlgorithm::HmacRipemd160
+             | HashAlgorithm::HmacRipemd128
+             | HashAlgorithm::HmacTiger => true,
+            HashAlgorithm::Md4
+             | HashAlgorithm::Md5
+             | HashAlgorithm::Md5Version06
+             | HashAlgorithm::Sha1
+             | HashAlgorithm::Sha256
+             | HashAlgorithm::Ripemd160
+             | HashAlgorithm::Ripemd128
+             | HashAlgorithm::Tiger => false,
+        }
+    }
+
+    /// Returns true for the algorithms of PasswordMaker Pro version 0.6, [`Md5Version06`][HashAlgorithm::Md5Version06] and
+    /// [`HmacMd5Version06`][HashAlgorithm::HmacMd5Version06]. They ignore the output characters, and always generate hexadecimal digits.
+    #[must_use]
+    pub fn is_legacy_v06(self) -> bool {
+        matches!(self, HashAlgorithm::Md5Version06 | HashAlgorithm::HmacMd5Version06)
+    }
+
+    /// Returns the hash function this algorithm is built on, regardless of HMAC or V0.6 handling.
+    #[must_use]
+    pub fn base_algorithm(self) -> BaseAlgorithm {
+        match self {
+            HashAlgorithm::Md4 | HashAlgorithm::HmacMd4 => BaseAlgorithm::Md4,
+            HashAlgorithm::Md5
+             | HashAlgorithm::Md5Version06
+             | HashAlgorithm::HmacMd5
+             | HashAlgorithm::HmacMd5Version06 => BaseAlgorithm::Md5,
+            HashAlgorithm::Sha1 | HashAlgorithm::HmacSha1 => BaseAlgorithm::Sha1,
+            HashAlgorithm::Sha256 | HashAlgorithm::HmacSha256 => BaseAlgorithm::Sha256,
+            HashAlgorithm::Ripemd160 | HashAlgorithm::HmacRipemd160 => BaseAlgorithm::Ripemd160,
+            HashAlgorithm::Ripemd128 | HashAlgorithm::HmacRipemd128 => BaseAlgorithm::Ripemd128,
+            HashAlgorithm::Tiger | HashAlgorithm::HmacTiger => BaseAlgorithm::Tiger,
+        }
+    }
+
+    /// Returns the length of the hash in bytes: 16 for MD4, MD5 and RIPEMD-128 (including the V0.6 algorithms), 20 for SHA-1
+    /// and RIPEMD-160, 24 for Tiger, and 32 for SHA-256. HMAC does not change the length.
+    /// 
+    /// This is the length the hash functions in a [`HasherList`] need to return. It determines how many characters a single hash
+    /// yields, see [`PasswordMaker::max_single_hash_length`], and can be passed to [`is_base_precomputed`].
+    #[must_use]
+    pub fn output_bytes(self) -> usize {
+        match self.base_algorithm() {
+            BaseAlgorithm::Md4 | BaseAlgorithm::Md5 | BaseAlgorithm::Ripemd128 => 16,
+            BaseAlgorithm::Sha1 | BaseAlgorithm::Ripemd160 => 20,
+            BaseAlgorithm::Tiger => 24,
+            BaseAlgorithm::Sha256 => 32,
+        }
+    }
+
+    /// Whether the hash function comes from an [`ExtendedHasherList`], meaning it's RIPEMD-128 or Tiger.
+    fn needs_extended_hashers(self) -> bool {
+        matches!(self.base_algorithm(), BaseAlgorithm::Ripemd128 | BaseAlgorithm::Tiger)
+    }
+}
+
+impl Display for HashAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        //The Debug output of a fieldless enum is just the variant name.
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The hash function a [`HashAlgorithm`] is built on, see [`HashAlgorithm::base_algorithm`].
+/// 
+/// Further hash functions may be added in minor releases, together with new [`HashAlgorithm`] variants.
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BaseAlgorithm {
+    /// MD4, see [`HasherList::MD4`].
+    Md4,
+    /// MD5, see [`HasherList::MD5`].
+    Md5,
+    /// SHA-1, see [`HasherList::SHA1`].
+    Sha1,
+    /// SHA-256, see [`HasherList::SHA256`].
+    Sha256,
+    /// RIPEMD-160, see [`HasherList::RIPEMD160`].
+    Ripemd160,
+    /// RIPEMD-128, see [`ExtendedHasherList::RIPEMD128`].
+    Ripemd128,
+    /// Tiger, see [`ExtendedHasherList::TIGER`].
+    Tiger,
+}
+
+/// When the Leet replacement as illustrated in [`LeetLevel`] is applied.
+/// 
+/// # Description
+/// If Leet is enabled, the input will be converted to lower case.
+/// It is always applied to each password part when the required password length
+/// is longer than the length obtained by computing a single hash. This is important if the input data or output charset contains certain
+/// characters where the lower case representation depends on context (e.g. 'Σ').
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumDiscriminants, strum_macros::VariantNames), strum_discriminants(derive(strum_macros::EnumString)))]
+#[derive(Debug,Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum UseLeetWhenGenerating {
+    /// Do not apply Leet on input or output.
+    NotAtAll,
+    /// Apply Leet on the input before computing a password part.
+    Before {
+        /// The Leet level to apply to the input.
+        level : LeetLevel,
+    },
+    /// Apply Leet on the generated password-part. Beware that this will force the password to lower-case characters.
+    After {
+        /// The Leet level to apply to the generated password parts.
+        level : LeetLevel,
+    },
+    /// Apply Leet both, to the input for the hasher, and the generated password parts. Beware that this will force the password to lower-case characters.
+    BeforeAndAfter {
+        /// The Leet level to apply to both, input and generated password parts.
+        level : LeetLevel,
+    },
+}
+
+/// Error returned when parsing a [`UseLeetWhenGenerating`] from a string fails.
+/// 
+/// # Description
+/// The accepted strings are `none`, and `before:N`, `after:N` or `both:N`, where `N` is the leet level from 0 to 9.
+/// Keywords are case-insensitive. For instance `after:6` yields [`UseLeetWhenGenerating::After`] with [`LeetLevel::Six`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseUseLeetError {
+    /// The keyword before the `:` is not one of `none`, `before`, `after` and `both`.
+    UnknownKeyword,
+    /// The keyword needs a leet level, but there was no `:` followed by one.
+    MissingLevel,
+    /// The leet level is not a number from 0 to 9, or a level was given for `none`.
+    InvalidLevel,
+}
+
+impl Display for ParseUseLeetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseUseLeetError::UnknownKeyword => write!(f, "Expected one of \"none\", \"before\", \"after\" and \"both\"."),
+            ParseUseLeetError::MissingLevel => write!(f, "Missing leet level, expected for instance \"after:6\"."),
+            ParseUseLeetError::InvalidLevel => write!(f, "The leet level must be a number from 0 to 9."),
+        }
+    }
+}
+impl Error for ParseUseLeetError{}
+
+/// Settings for the parsing of the user's input URL.
+/// This is used to generate the `data` parameter for [`PasswordMaker`].
+#[allow(clippy::struct_excessive_bools, clippy::struct_field_names)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UrlParsing {
+    use_protocol : ProtocolUsageMode,
+    use_userinfo : bool,
+    use_subdomains : bool,
+    use_domain : bool,
+    use_port_path : bool,
+    trim_input : bool,
+    percent_decode_path : bool,
+    percent_decode_userinfo : bool,
+    authority_without_slashes : bool,
+    use_query : bool,
+    use_fragment : bool,
+    stripped_subdomain_label : Option<String>,
+    reverse_domain_labels : bool,
+    userinfo_separator : Option<String>,
+    subdomain_separator : Option<String>,
+    whole_input_as_domain : bool,
+    passthrough : bool,
+}
+
+#[allow(clippy::fn_params_excessive_bools)]
+impl UrlParsing {
+    /// Creates a new `UrlParsing` instance with the given settings.
+    /// 
+    /// Options that go beyond what PasswordMaker Pro offers are disabled, and can be enabled with the respective `with_` methods.
+    #[must_use]
+    pub fn new(
+        use_protocol : ProtocolUsageMode,
+        use_userinfo : bool,
+        use_subdomains : bool,
+        use_domain : bool,
+        use_port_path : bool,
+    ) -> Self{
+        UrlParsing{ use_protocol, use_userinfo, use_subdomains, use_domain, use_port_path, trim_input : false, percent_decode_path : false, percent_decode_userinfo : false, authority_without_slashes : false, use_query : true, use_fragment : true, stripped_subdomain_label : None, reverse_domain_labels : false, userinfo_separator : None, subdomain_separator : None, whole_input_as_domain : false, passthrough : false, }
+    }
+
+    /// Creates a `UrlParsing` instance that does not parse at all: [`parse`][UrlParsing::parse] returns the input unchanged.
+    /// 
+    /// This is meant for callers that sometimes get input that is already the `data` for [`PasswordMaker`], for instance a command
+    /// line tool with a "raw" option, and want to use the same code path for both cases. Enabling all components with
+    /// [`new`][UrlParsing::new] is not the same, as that still splits the input and joins the parts again, which can for instance
+    /// add `"://"` or `"undefined"`. The input is not trimmed. [`parse_components`][UrlParsing::parse_components] returns the
+    /// whole input as the domain. The `with_` methods have no effect on the returned instance, so all identity instances compare equal.
+    #[must_use]
+    pub fn identity() -> Self{
+        UrlParsing{ passthrough : true, ..UrlParsing::default() }
+    }
+
+    /// Discards the changes of a `with_` method on an [`identity`][UrlParsing::identity] instance. They would have no effect
+    /// anyhow, and this way all identity instances compare equal.
+    fn unless_identity(self) -> Self{
+        if self.passthrough { UrlParsing::identity() } else { self }
+    }
+
+    /// Creates a `UrlParsing` instance from the URL settings of a PasswordMaker Pro profile, for instance when importing them.
+    /// 
+    /// PasswordMaker Pro has no setting for the userinfo, so it is not used. `use_path` enables the port and path. The
+    /// `protocol` value is mapped onto a [`ProtocolUsageMode`], case-insensitively and ignoring surrounding whitespace:
+    /// - `""`, `"false"`, `"0"`, `"no"` and `"off"` mean that the protocol is not used, [`ProtocolUsageMode::Ignored`].
+    /// - The names of the [`ProtocolUsageMode`] variants, like `"used"`, select that variant.
+    /// - Any other value, like `"true"`, `"1"` or `"protocol"`, means that the protocol is used. This selects
+    ///   [`ProtocolUsageMode::UsedWithUndefinedIfEmpty`], as that is what PasswordMaker Pro does.
+    /// 
+    /// All options that go beyond what PasswordMaker Pro offers are disabled, same as for [`new`][UrlParsing::new].
+    #[must_use]
+    pub fn from_pmpro_flags(protocol : &str, use_subdomain : bool, use_domain : bool, use_path : bool) -> Self{
+        UrlParsing::new(Self::pmpro_protocol_usage_mode(protocol), false, use_subdomain, use_domain, use_path)
+    }
+
+    /// Sets whether leading and trailing whitespace should be removed from the input before parsing.
+    /// 
+    /// Copy-pasted URLs often come with stray spaces or a trailing newline. PasswordMaker Pro does not trim its input,
+    /// so this is disabled by default. Beware that enabling it changes the generated passwords for inputs with
+    /// surrounding whitespace.
+    #[must_use]
+    pub fn with_trim_input(self, trim_input : bool) -> Self{
+        UrlParsing{ trim_input, ..self }.unless_identity()
+    }
+
+    /// Sets whether percent-encoded characters (like `%7E` for `~`) in the path, query and fragment should be decoded.
+    /// 
+    /// Without this, `example.com/%7Euser` and `example.com/~user` yield different passwords. Sequences that are not
+    /// valid percent-encoding (like `%zz`), or that do not decode to valid UTF-8, are left as they are.
+    /// PasswordMaker Pro does not decode its input, so this is disabled by default.
+    #[must_use]
+    pub fn with_percent_decode_path(self, percent_decode_path : bool) -> Self{
+        UrlParsing{ percent_decode_path, ..self }.unless_identity()
+    }
+
+    /// Same as [`with_percent_decode_path`][UrlParsing::with_percent_decode_path], but for the userinfo part of the URL.
+    /// Disabled by default.
+    #[must_use]
+    pub fn with_percent_decode_userinfo(self, percent_decode_userinfo : bool) -> Self{
+        UrlParsing{ percent_decode_userinfo, ..self }.unless_identity()
+    }
+
+    /// Sets whether inputs like `mailto:jane@example.com` should be split into userinfo and host.
+    /// 
+    /// By default, everything after a protocol that is not followed by `//` is treated as path. For `mailto:` this means that
+    /// the domain setting has no effect, and the whole address ends up in the path. If this option is enabled, and the part
+    /// after the protocol has the shape `userinfo@host` (optionally followed by a query or fragment), it is parsed as if
+    /// it was preceded by `//`. Inputs without `@`, like `tel:+1234`, are not affected.
+    /// PasswordMaker Pro does not do this, so it's disabled by default.
+    #[must_use]
+    pub fn with_authority_without_slashes(self, authority_without_slashes : bool) -> Self{
+        UrlParsing{ authority_without_slashes, ..self }.unless_identity()
+    }
+
+    /// Sets whether the query (the part starting with `?`) should be used, if the path is used.
+    /// 
+    /// Queries often contain volatile data, like session tokens or tracking parameters. If this is disabled, they are
+    /// left out, while the path is kept. This has no effect if `use_port_path` is disabled. PasswordMaker Pro treats
+    /// path, query and fragment as one, so this is enabled by default.
+    #[must_use]
+    pub fn with_use_query(self, use_query : bool) -> Self{
+        UrlParsing{ use_query, ..self }.unless_identity()
+    }
+
+    /// Same as [`with_use_query`][UrlParsing::with_use_query], but for the fragment (the part starting with `#`).
+    /// Enabled by default.
+    #[must_use]
+    pub fn with_use_fragment(self, use_fragment : bool) -> Self{
+        UrlParsing{ use_fragment, ..self }.unless_identity()
+    }
+
+    /// Sets a subdomain label, typically `"www"`, that is removed if it is the first label of the subdomain.
+    /// 
+    /// Users usually expect `www.example.com` and `example.com` to yield the same password. Disabling `use_subdomains` does that,
+    /// but also gives `mail.example.com` the same password. With `Some("www")`, only a leading `www` label is removed:
+    /// `www.example.com` becomes `example.com`, and `www.mail.example.com` becomes `mail.example.com`, while `mail.example.com`
+    /// and `www2.example.com` are kept as they are. This has no effect if `use_subdomains` is disabled.
+    /// PasswordMaker Pro has no such option, so it's `None` by default.
+    #[must_use]
+    pub fn with_stripped_subdomain_label(self, stripped_subdomain_label : Option<&str>) -> Self{
+        UrlParsing{ stripped_subdomain_label : stripped_subdomain_label.map(str::to_owned), ..self }.unless_identity()
+    }
+
+    /// Sets whether the labels of the host are put out in reverse order, for instance `com.example` instead of `example.com`.
+    /// 
+    /// This applies to the used part of the host, meaning the domain, and the subdomain if `use_subdomains` is enabled:
+    /// `www.example.com` becomes `com.example.www`. Some users prefer this order, as it makes the grouping by organization explicit.
+    /// Beware that it also reverses IPv4 addresses. PasswordMaker Pro has no such option, so it's disabled by default.
+    #[must_use]
+    pub fn with_reversed_domain_labels(self, reverse_domain_labels : bool) -> Self{
+        UrlParsing{ reverse_domain_labels, ..self }.unless_identity()
+    }
+
+    /// Sets the text that joins the userinfo and the rest of the URL, instead of `"@"`.
+    /// 
+    /// This only matters for exact compatibility with other tools that join the parts differently. `None`, the default,
+    /// uses `"@"`, like PasswordMaker Pro.
+    #[must_use]
+    pub fn with_userinfo_separator(self, userinfo_separator : Option<&str>) -> Self{
+        UrlParsing{ userinfo_separator : userinfo_separator.map(str::to_owned), ..self }.unless_identity()
+    }
+
+    /// Sets the text that joins the subdomain and the domain, instead of `"."`.
+    /// 
+    /// This only matters for exact compatibility with other tools that join the parts differently. `None`, the default,
+    /// uses `"."`, like PasswordMaker Pro. The labels within the subdomain and within the domain are kept as they are. If
+    /// [`with_reversed_domain_labels`][UrlParsing::with_reversed_domain_labels] is enabled, all labels are joined with `"."`,
+    /// and this has no effect.
+    #[must_use]
+    pub fn with_subdomain_separator(self, subdomain_separator : Option<&str>) -> Self{
+        UrlParsing{ subdomain_separator : subdomain_separator.map(str::to_owned), ..self }.unless_identity()
+    }
+
+    /// Sets whether the whole input should be treated as the domain, without looking for a protocol, userinfo, port or path.
+    /// 
+    /// This is meant for inputs that are not URLs, like the reverse-DNS application identifier `com.example.myapp`, or
+    /// arbitrary labels. Leading and trailing whitespace is removed, and the rest is used as it is, provided that `use_domain`
+    /// is enabled. The other `use_` settings have no effect then, as the other components are empty.
+    /// PasswordMaker Pro has no such option, so it's disabled by default.
+    #[must_use]
+    pub fn with_whole_input_as_domain(self, whole_input_as_domain : bool) -> Self{
+        UrlParsing{ whole_input_as_domain, ..self }.unless_identity()
+    }
+
+    /// Returns true if [`parse`][UrlParsing::parse] puts the literal string "undefined" into the output for inputs without protocol.
+    /// 
+    /// This is only the case for [`ProtocolUsageMode::UsedWithUndefinedIfEmpty`], see [`ProtocolUsageMode::injects_undefined`].
+    /// The other modes never produce "undefined", unless it is part of the input. User interfaces can use this to warn users
+    /// that they selected the bug-compatible mode, as it is easily confused with [`ProtocolUsageMode::Used`].
+    #[must_use]
+    pub fn injects_undefined(&self) -> bool {
+        self.use_protocol.injects_undefined()
+    }
+
+    /// Parses an input string, applying the settings in `self`, and generates a string suitable for
+    /// the `data` parameter of [`PasswordMaker`]
+    /// 
+    /// `file:` URLs are split like any other URL with an authority. In `file:///etc/hosts` the host is empty and the path is
+    /// `/etc/hosts`, while in `file://server/share` the host (and therefore the domain) is `server`.
+    /// 
+    /// This function does not panic, no matter the input.
+    #[must_use]
+    pub fn parse(&self, input : &str) -> String{
+        self.make_used_text_from_url(input)
+    }
+
+    /// Same as [`parse`][UrlParsing::parse], but fails instead of returning an empty string.
+    /// 
+    /// An empty result would make [`PasswordMaker::generate`] fail with [`GenerationError::MissingTextToUse`], which is confusing
+    /// if the user did enter something. For instance, if only `use_port_path` is enabled, `example.com` yields an empty string.
+    /// The error tells those cases apart, so that users can be told to change either their input or their settings.
+    /// 
+    /// # Errors
+    /// Fails with [`UrlParseError::EmptyInput`] if `input` is empty or only contains whitespace, and with
+    /// [`UrlParseError::EverythingFilteredOut`] if the settings in `self` remove everything from a non-empty `input`.
+    pub fn try_parse(&self, input : &str) -> Result<String, UrlParseError>{
+        let used_text = self.parse(input);
+        if !used_text.is_empty() {
+            Ok(used_text)
+        } else if input.trim().is_empty() {
+            Err(UrlParseError::EmptyInput)
+        } else {
+            Err(UrlParseError::EverythingFilteredOut)
+        }
+    }
+
+    /// Splits an input string into its components, without filtering or recombining them.
+    /// 
+    /// This is meant for callers that want to build their own `data` strings, for instance one password for the domain
+    /// and another one for the full URL. The options of `self` that change how the input is parsed (trimming, percent
+    /// decoding, `mailto:`-style authorities) are applied, while the `use_` settings passed to [`new`][UrlParsing::new]
+    /// are ignored. See [`ParsedComponents`] for how [`parse`][UrlParsing::parse] puts the components back together.
+    /// 
+    /// This function does not panic, no matter the input.
+    #[must_use]
+    pub fn parse_components<'a>(&self, input : &'a str) -> ParsedComponents<'a>{
+        self.make_components_from_url(input)
+    }
+}
+
+impl Default for UrlParsing {
+    /// Returns the default settings of PasswordMaker Pro: Only the domain is used.
+    /// All options that go beyond what PasswordMaker Pro offers are disabled.
+    fn default() -> Self {
+        UrlParsing::new(ProtocolUsageMode::default(), false, false, true, false)
+    }
+}
+
+/// The components of a URL, as returned by [`UrlParsing::parse_components`].
+/// 
+/// # Description
+/// The split follows PasswordMaker Pro, not the URI standard. For instance, the last two labels of the host are the
+/// domain, and everything in front of them is the subdomain. Each component is returned without its separators.
+/// 
+/// [`UrlParsing::parse`] drops the components that are not enabled in its settings, and joins the remaining ones like this:
+/// - The protocol is followed by `://`. If the protocol is used but empty, and [`ProtocolUsageMode::UsedWithUndefinedIfEmpty`]
+///   is selected, `undefined` is used instead, without separator.
+/// - The userinfo is followed by `@`, if any other component except the protocol follows.
+/// - Subdomain and domain are separated by `.`, if both are present.
+/// - The port is preceded by `:`, if userinfo, subdomain or domain precede it.
+/// - The path, query and fragment are appended as they are. They start with `/`, `?` or `#` if the input had an authority.
+///   Query and fragment can be left out separately, see [`UrlParsing::with_use_query`] and [`UrlParsing::with_use_fragment`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedComponents<'a> {
+    protocol : &'a str,
+    userinfo : Cow<'a, str>,
+    subdomain : &'a str,
+    domain : &'a str,
+    port : &'a str,
+    path_query_fragment : Cow<'a, str>,
+    path_length : usize,
+    query_length : usize,
+}
+
+impl ParsedComponents<'_> {
+    /// The protocol (scheme), for instance `https`.
+    #[must_use]
+    pub fn protocol(&self) -> &str {
+        self.protocol
+    }
+    /// The userinfo, for instance `user:password`. Percent-decoded if enabled in the [`UrlParsing`] settings.
+    #[must_use]
+    pub fn userinfo(&self) -> &str {
+        &self.userinfo
+    }
+    /// Everything of the host in front of the domain, for instance `www` in `www.example.com`.
+    #[must_use]
+    pub fn subdomain(&self) -> &str {
+        self.subdomain
+    }
+    /// The last two labels of the host, for instance `example.com` in `www.example.com`.
+    #[must_use]
+    pub fn domain(&self) -> &str {
+        self.domain
+    }
+    /// The port, for instance `8080`.
+    #[must_use]
+    pub fn port(&self) -> &str {
+        self.port
+    }
+    /// Path, query and fragment, for instance `/index.html?a=b#top`. Percent-decoded if enabled in the [`UrlParsing`] settings.
+    #[must_use]
+    pub fn path_query_fragment(&self) -> &str {
+        &self.path_query_fragment
+    }
+    /// The path, for instance `/index.html`. Percent-decoded if enabled in the [`UrlParsing`] settings.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path_query_fragment[..self.path_length]
+    }
+    /// The query including the leading `?`, for instance `?a=b`. Percent-decoded if enabled in the [`UrlParsing`] settings.
+    #[must_use]
+    pub fn query(&self) -> &str {
+        &self.path_query_fragment[self.path_length..self.path_length + self.query_length]
+    }
+    /// The fragment including the leading `#`, for instance `#top`. Percent-decoded if enabled in the [`UrlParsing`] settings.
+    #[must_use]
+    pub fn fragment(&self) -> &str {
+        &self.path_query_fragment[self.path_length + self.query_length..]
+    }
+    /// Returns which components are present (non-empty) in the input.
+    /// 
+    /// Unlike [`UrlParsing::parse`], this does not depend on which components are enabled in the settings. A user interface can
+    /// use it to show what the URL consists of, for instance "protocol, domain, path".
+    #[must_use]
+    pub fn present_components(&self) -> ComponentFlags {
+        ComponentFlags {
+            protocol : !self.protocol().is_empty(),
+            userinfo : !self.userinfo().is_empty(),
+            subdomain : !self.subdomain().is_empty(),
+            domain : !self.domain().is_empty(),
+            port : !self.port().is_empty(),
+            path : !self.path().is_empty(),
+            query : !self.query().is_empty(),
+            fragment : !self.fragment().is_empty(),
+        }
+    }
+}
+
+/// Which components of a URL are present, as returned by [`ParsedComponents::present_components`].
+/// 
+/// Each field is true if the respective getter of [`ParsedComponents`] returns a non-empty string.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct ComponentFlags {
+    /// The URL has a protocol, like `https`.
+    pub protocol : bool,
+    /// The URL has userinfo, like `user:password`.
+    pub userinfo : bool,
+    /// The host has a subdomain, like `www`.
+    pub subdomain : bool,
+    /// The URL has a domain, like `example.com`.
+    pub domain : bool,
+    /// The URL has a port, like `8080`.
+    pub port : bool,
+    /// The URL has a path, like `/index.html`.
+    pub path : bool,
+    /// The URL has a query, like `?a=b`.
+    pub query : bool,
+    /// The URL has a fragment, like `#top`.
+    pub fragment : bool,
+}
+
+/// Unicode normalization forms, see [`PasswordMaker::with_unicode_normalization`].
+/// 
+/// # Description
+/// See [Unicode Standard Annex #15](https://www.unicode.org/reports/tr15/) for details. If unsure, use `Nfc`.
+/// The compatibility forms (`Nfkc` and `Nfkd`) additionally unify characters that only differ in formatting, for instance
+/// "ﬁ" and "fi", or full-width and normal digits.
+/// 
+/// Only available with the `unicode-normalization` feature.
+#[cfg(feature = "unicode-normalization")]
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NormalizationForm {
+    /// Canonical decomposition, followed by canonical composition.
+    Nfc,
+    /// Canonical decomposition.
+    Nfd,
+    /// Compatibility decomposition, followed by canonical composition.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+/// Where the password part counter is appended, see [`PasswordMaker::with_part_counter_placement`].
+/// 
+/// # Description
+/// If a single hash does not yield enough characters for the desired password length, further password parts are generated.
+/// To make them differ, `"\n"` followed by the part's index is appended to one of the inputs, starting at the second part.
+/// PasswordMaker Pro appends it to the key, but some ports of it append it elsewhere, and therefore generate different long passwords.
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PartCounterPlacement {
+    /// The counter is appended to the key (master password). This is what PasswordMaker Pro does.
+    Key,
+    /// The counter is appended to the data (the used text from the URL), before username and modifier are appended.
+    Data,
+    /// The counter is appended to the modifier, meaning after the data, username and modifier.
+    Modifier,
+}
+
+impl Default for PartCounterPlacement {
+    /// Returns [`Key`][PartCounterPlacement::Key], which is PasswordMaker Pro's behaviour.
+    fn default() -> Self {
+        PartCounterPlacement::Key
+    }
+}
+
+/// How the hash of each password part is turned into characters, see [`PasswordMaker::with_output_encoding`].
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OutputEncoding {
+    /// The hash is converted to a number in base `characters.len()`, and each digit selects one of the `characters`.
+    /// Leading zero digits are dropped, except for the V0.6 algorithms, or if [`OutputPadding::LeadingZeros`] is set.
+    /// This is what PasswordMaker Pro does.
+    Characters,
+    /// The hash is written as lowercase hexadecimal number, two digits per byte, leading zeros included. The `characters` are ignored.
+    /// For the V0.6 algorithms this is the same as `Characters`.
+    Hex,
+    /// The hash is encoded with the URL-safe Base64 alphabet from RFC 4648, without padding. The `characters` are ignored.
+    Base64,
+    /// The hash is encoded with the Base58 alphabet used by Bitcoin. Like in the usual Base58 encoding, each leading zero byte
+    /// of the hash becomes a `1`. The `characters` are ignored.
+    Base58,
+}
+
+impl Default for OutputEncoding {
+    /// Returns [`Characters`][OutputEncoding::Characters], which is PasswordMaker Pro's behaviour.
+    fn default() -> Self {
+        OutputEncoding::Characters
+    }
+}
+
+/// How the input of the non-HMAC modern algorithms is turned into bytes, see [`PasswordMaker::with_input_encoding`].
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InputEncoding {
+    /// The input is hashed as UTF-8. This is what PasswordMaker Pro does for the non-HMAC modern algorithms.
+    Utf8,
+    /// The input is encoded as UTF-16, and only the lower byte of each code unit is hashed. This is what PasswordMaker Pro
+    /// does for the HMAC and V0.6 algorithms. Different characters can yield the same byte, for instance `"Ā"` and `"\u{0}"`.
+    Utf16DiscardHighByte,
+}
+
+impl Default for InputEncoding {
+    /// Returns [`Utf8`][InputEncoding::Utf8], which is PasswordMaker Pro's behaviour.
+    fn default() -> Self {
+        InputEncoding::Utf8
+    }
+}
+
+/// Whether leading zero digits of a hash end up in the password, see [`PasswordMaker::with_output_padding`].
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OutputPadding {
+    /// Leading zero digits are dropped. This is what PasswordMaker Pro does for all but the V0.6 algorithms.
+    None,
+    /// Leading zero digits are kept, so each hash yields the same number of characters. This is what the V0.6 algorithms do.
+    LeadingZeros,
+}
+
+impl Default for OutputPadding {
+    /// Returns [`None`][OutputPadding::None], which is PasswordMaker Pro's behaviour.
+    fn default() -> Self {
+        OutputPadding::None
+    }
+}
+
+/// The unit `password_length` and the lengths of prefix and suffix are counted in, see [`PasswordMaker::with_length_unit`].
+#[cfg_attr(feature = "strum", derive(strum_macros::EnumString, strum_macros::VariantNames))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LengthUnit {
+    /// Grapheme clusters, meaning user-perceived characters. An emoji with a skin tone modifier counts as one.
+    GraphemeClusters,
+    /// Unicode code points. An emoji with a skin tone modifier counts as two.
+    CodePoints,
+    /// UTF-16 code units, which is what the `length` of a Javascript string counts. An emoji with a skin tone modifier counts as four.
+    Utf16CodeUnits,
+}
+
+impl Default for LengthUnit {
+    /// Returns [`GraphemeClusters`][LengthUnit::GraphemeClusters], so a password of length 8 is 8 characters long for the user.
+    fn default() -> Self {
+        LengthUnit::GraphemeClusters
+    }
+}
+
+/// How to handle the URL protocol, or the absence of it, during [`UrlParsing`].
+/// 
+/// # Description
 /// The "Use Protocol" checkbox in PasswordMaker Pro Javascript Edition has some weird behaviour, that's probably a bug.
 /// This enum lets you select how to hande the case that the user wants to use the Protocol, but the input string doesn't contain one.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum ProtocolUsageMode{
     /// The protocol part of the URI is not used in the output.
     Ignored,
@@ -310,15 +2525,95 @@ pub enum ProtocolUsageMode{
     UsedWithUndefinedIfEmpty,
 }
 
+impl ProtocolUsageMode {
+    /// Returns true if this mode puts the literal string "undefined" into the output for inputs without protocol.
+    /// 
+    /// This is the case for [`UsedWithUndefinedIfEmpty`][ProtocolUsageMode::UsedWithUndefinedIfEmpty], which reproduces a bug of
+    /// PasswordMaker Pro. User interfaces can use this to warn users that they selected the bug-compatible mode.
+    #[must_use]
+    pub fn injects_undefined(&self) -> bool {
+        match self {
+            ProtocolUsageMode::Ignored
+             | ProtocolUsageMode::Used
+             => false,
+            ProtocolUsageMode::UsedWithUndefinedIfEmpty => true,
+        }
+    }
+}
+
+impl Default for ProtocolUsageMode {
+    /// Returns [`Ignored`][ProtocolUsageMode::Ignored], which is also PasswordMaker Pro's default.
+    fn default() -> Self {
+        ProtocolUsageMode::Ignored
+    }
+}
+
 
 
 /// Error returned if the supplied input did not meet expectations.
+/// 
+/// The generation functions of [`PasswordMaker`] check their input before hashing anything, and fail with the first problem
+/// they find. The checks are, in this order:
+/// 1. [`MissingTextToUse`][GenerationError::MissingTextToUse] if `data` is empty.
+/// 2. [`MissingMasterPassword`][GenerationError::MissingMasterPassword] if `key` is empty, or
+///    [`MasterPasswordTooShort`][GenerationError::MasterPasswordTooShort] if it is shorter than the minimum set with
+///    [`PasswordMaker::with_min_key_length`].
+/// 3. [`UntrimmedField`][GenerationError::UntrimmedField] if `username` or `modifier` start or end with whitespace, and
+///    [`PasswordMaker::with_strict_whitespace`] is enabled.
+/// 4. [`AffixTooLong`][GenerationError::AffixTooLong] if `prefix` or `suffix` exceed the limit set with
+///    [`PasswordMaker::with_max_affix_length`].
+/// 5. [`NonPrintableCharsetEntry`][GenerationError::NonPrintableCharsetEntry] if the characters contain control characters, and
+///    [`PasswordMaker::with_strict_charset`] is enabled.
+/// 6. [`SlowCharset`][GenerationError::SlowCharset] if [`PasswordMaker::with_require_fast_base`] is enabled, and the charset is
+///    slow to convert to.
+/// 7. [`UnavailableAlgorithm`][GenerationError::UnavailableAlgorithm] if the algorithm needs hash functions that were not provided.
+/// 
+/// Functions that return the whole password then also fail with [`OutputTooLarge`][GenerationError::OutputTooLarge] if it
+/// exceeds the limit set with [`PasswordMaker::with_max_output_bytes`]. [`PasswordMaker::validate_all`] reports all problems
+/// of an input at once, except for that last one.
 #[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
 pub enum GenerationError {
     /// Password generation failed, because the user did not supply a master password.
     MissingMasterPassword,
     /// Password generation failed, because the user did not supply a text-to-use.
     MissingTextToUse,
+    /// Password generation failed, because the password would be larger than the limit set with [`PasswordMaker::with_max_output_bytes`].
+    OutputTooLarge,
+    /// Password generation failed, because the master password is shorter than the minimum set with [`PasswordMaker::with_min_key_length`].
+    MasterPasswordTooShort {
+        /// The minimum length, in grapheme clusters.
+        min : usize,
+        /// The length of the given master password, in grapheme clusters.
+        got : usize,
+    },
+    /// Password generation failed, because [`PasswordMaker::with_strict_charset`] is enabled, and an entry of the characters
+    /// contains a control character.
+    NonPrintableCharsetEntry {
+        /// The index of the offending entry, in grapheme clusters.
+        index : usize,
+    },
+    /// Writing the password failed, because the target of [`PasswordMaker::write_to`] returned an error.
+    WriteFailed,
+    /// Password generation failed, because [`PasswordMaker::with_strict_whitespace`] is enabled, and a field starts or ends
+    /// with whitespace.
+    UntrimmedField {
+        /// The offending field, either `"username"` or `"modifier"`.
+        field : &'static str,
+    },
+    /// Password generation failed, because the prefix or suffix is longer than the limit set with
+    /// [`PasswordMaker::with_max_affix_length`].
+    AffixTooLong {
+        /// The offending field, either `"prefix"` or `"suffix"`.
+        field : &'static str,
+        /// The maximum length, in grapheme clusters.
+        max : usize,
+    },
+    /// Password generation failed, because [`PasswordMaker::with_require_fast_base`] is enabled, and the base conversion for the
+    /// number of output characters is not precomputed.
+    SlowCharset,
+    /// Password generation failed, because the hash algorithm is RIPEMD-128 or Tiger, and the hash functions are not [`Extended`].
+    UnavailableAlgorithm,
 }
 
 impl Display for GenerationError {
@@ -326,11 +2621,51 @@ impl Display for GenerationError {
         match self {
             GenerationError::MissingMasterPassword => write!(f, "No master password given."),
             GenerationError::MissingTextToUse => write!(f, "No text to use. Would just hash the master password."),
+            GenerationError::OutputTooLarge => write!(f, "The password would exceed the configured maximum size in bytes."),
+            GenerationError::MasterPasswordTooShort { min, got } => write!(f, "The master password needs at least {} characters, but has only {}.", min, got),
+            GenerationError::NonPrintableCharsetEntry { index } => write!(f, "Character {} of the charset contains a control character.", index),
+            GenerationError::WriteFailed => write!(f, "Writing the password failed."),
+            GenerationError::UntrimmedField { field } => write!(f, "The {} starts or ends with whitespace.", field),
+            GenerationError::AffixTooLong { field, max } => write!(f, "The {} is longer than {} characters.", field, max),
+            GenerationError::SlowCharset => write!(f, "The number of characters is not supported for fast password generation."),
+            GenerationError::UnavailableAlgorithm => write!(f, "No hash function was provided for the selected algorithm."),
         }
     }
 }
 impl Error for GenerationError{}
 
+/// Error returned by [`PasswordMaker::try_generate`].
+#[derive(Debug, Clone, Copy)]
+pub enum TryGenerationError<E> {
+    /// Password generation failed, see [`GenerationError`].
+    Generation(GenerationError),
+    /// One of the hash functions failed.
+    Hasher(E),
+}
+
+impl<E> From<GenerationError> for TryGenerationError<E> {
+    fn from(error : GenerationError) -> Self {
+        TryGenerationError::Generation(error)
+    }
+}
+
+impl<E : Display> Display for TryGenerationError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TryGenerationError::Generation(error) => error.fmt(f),
+            TryGenerationError::Hasher(error) => write!(f, "Hashing failed: {}", error),
+        }
+    }
+}
+impl<E : Error + 'static> Error for TryGenerationError<E>{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TryGenerationError::Generation(error) => Some(error),
+            TryGenerationError::Hasher(error) => Some(error),
+        }
+    }
+}
+
 
 /// Error returned if creation of a `PasswordMaker` object failed due to invalid settings.
 /// 
@@ -338,17 +2673,264 @@ impl Error for GenerationError{}
 /// `InsufficientCharset` means that the output character set does not contain at least two grapheme clusters.
 /// Since the output string is computed by doing a base system conversion from binary to number-of-grapheme-clusters,
 /// any number of grapheme clusters lower than 2 forms a nonsensical input. There simply is no base-1 or base-0 number system.
+/// 
+/// `InsufficientCharset` carries the number of grapheme clusters that were found. This is a (minor) breaking change compared to
+/// version 0.2, where it had no fields. Code that matched on `SettingsError::InsufficientCharset` now needs to use
+/// `SettingsError::InsufficientCharset { .. }`.
+/// 
+/// `InvalidValue` is only returned by [`Settings::from_kv`], if one of the values could not be parsed.
 #[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
 pub enum SettingsError {
     /// Password generation failed, because the character set supplied by the user did not contain at least 2 grapheme clusters.
-    InsufficientCharset,
+    InsufficientCharset {
+        /// The number of grapheme clusters found in the character set. Either 0 or 1.
+        found : usize,
+    },
+    /// The value for a setting could not be parsed.
+    InvalidValue {
+        /// The key of the setting, see [`Settings::from_kv`].
+        key : &'static str,
+    },
 }
 
 impl Display for SettingsError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            SettingsError::InsufficientCharset => write!(f, "Charset needs to have at least 2 characters."),
+            SettingsError::InsufficientCharset { found } => write!(f, "Charset needs to have at least 2 characters, but only {} found.", found),
+            SettingsError::InvalidValue { key } => write!(f, "The value of setting \"{}\" is invalid.", key),
+        }
+    }
+}
+impl Error for SettingsError{}
+
+/// Error returned by [`UrlParsing::try_parse`], if nothing is left of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UrlParseError {
+    /// The input was empty, or only contained whitespace.
+    EmptyInput,
+    /// The input was not empty, but none of the URL components that are enabled in the [`UrlParsing`] settings are present in it.
+    EverythingFilteredOut,
+}
+
+impl Display for UrlParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UrlParseError::EmptyInput => write!(f, "No input given."),
+            UrlParseError::EverythingFilteredOut => write!(f, "None of the URL parts that are used by the settings are present in the input."),
+        }
+    }
+}
+impl Error for UrlParseError{}
+
+/// Error returned by [`map_indices`], if an index does not select a grapheme cluster of the charset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MapError {
+    /// The index at `position` in the input is not smaller than the number of grapheme clusters in the charset.
+    IndexOutOfRange {
+        /// The position of the offending index in the input.
+        position : usize,
+        /// The offending index.
+        index : usize,
+        /// The number of grapheme clusters in the charset.
+        charset_length : usize,
+    },
+}
+
+impl Display for MapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapError::IndexOutOfRange { position, index, charset_length } => write!(f, "Index {} at position {} is out of range for a charset of {} characters.", index, position, charset_length),
+        }
+    }
+}
+impl Error for MapError{}
+
+/// Describes a password generation, see [`PasswordMaker::generate_with_context`].
+/// 
+/// # Description
+/// Contains neither the key (master password) nor the password itself, only its length. It can therefore be logged,
+/// for instance to record which input a password was generated for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationContext {
+    data : String,
+    /// In bytes. Marks where the username starts in `data`.
+    text_to_use_length : usize,
+    /// In bytes.
+    username_length : usize,
+    hash_algorithm : HashAlgorithm,
+    password_length : usize,
+}
+
+impl GenerationContext {
+    /// The data that was hashed: The text-to-use, followed by username and modifier, without separators. This is after Unicode
+    /// normalization (see `PasswordMaker::with_unicode_normalization`), but before leet is applied and before the counter of
+    /// further password parts is added. The context tag set with [`PasswordMaker::with_context_tag`] is not included.
+    #[must_use]
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// The part of [`data`][GenerationContext::data] that is the text-to-use.
+    #[must_use]
+    pub fn text_to_use(&self) -> &str {
+        &self.data[..self.text_to_use_length]
+    }
+
+    /// The part of [`data`][GenerationContext::data] that is the username. Empty if there is no username.
+    #[must_use]
+    pub fn username(&self) -> &str {
+        &self.data[self.text_to_use_length..self.text_to_use_length + self.username_length]
+    }
+
+    /// The part of [`data`][GenerationContext::data] that is the modifier. Empty if there is no modifier.
+    #[must_use]
+    pub fn modifier(&self) -> &str {
+        &self.data[self.text_to_use_length + self.username_length..]
+    }
+
+    /// The hash algorithm that was used.
+    #[must_use]
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.hash_algorithm
+    }
+
+    /// The length of the generated password, in grapheme clusters, including prefix and suffix.
+    #[must_use]
+    pub fn password_length(&self) -> usize {
+        self.password_length
+    }
+}
+
+/// How much of prefix and suffix ends up in the password, see [`PasswordMaker::affix_budget`].
+/// 
+/// All lengths are in the [`LengthUnit`] of the [`PasswordMaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AffixBudget {
+    prefix_length : usize,
+    prefix_kept : usize,
+    suffix_length : usize,
+    suffix_kept : usize,
+    generated_length : usize,
+}
+
+impl AffixBudget {
+    /// The full length of the prefix.
+    #[must_use]
+    pub fn prefix_length(&self) -> usize {
+        self.prefix_length
+    }
+
+    /// The length of the part of the prefix that ends up in the password. Less than [`prefix_length`][AffixBudget::prefix_length]
+    /// if the end of the prefix is cut.
+    #[must_use]
+    pub fn prefix_kept(&self) -> usize {
+        self.prefix_kept
+    }
+
+    /// The full length of the suffix.
+    #[must_use]
+    pub fn suffix_length(&self) -> usize {
+        self.suffix_length
+    }
+
+    /// The length of the part of the suffix that ends up in the password. Less than [`suffix_length`][AffixBudget::suffix_length]
+    /// if the end of the suffix is cut.
+    #[must_use]
+    pub fn suffix_kept(&self) -> usize {
+        self.suffix_kept
+    }
+
+    /// The number of generated characters between prefix and suffix. Zero if prefix and suffix take up the whole password.
+    #[must_use]
+    pub fn generated_length(&self) -> usize {
+        self.generated_length
+    }
+}
+
+/// Error returned by [`PasswordMaker::self_test`], if the hash functions did not produce the expected passwords.
+#[derive(Debug, Clone)]
+pub struct SelfTestError {
+    failed_algorithms : Vec<HashAlgorithm>,
+}
+
+impl SelfTestError {
+    /// The algorithms for which the generated password did not match the [`KNOWN_ANSWER_VECTORS`].
+    #[must_use]
+    pub fn failed_algorithms(&self) -> &[HashAlgorithm] {
+        &self.failed_algorithms
+    }
+}
+
+impl Display for SelfTestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Known-answer test failed for:")?;
+        for algorithm in &self.failed_algorithms {
+            write!(f, " {}", algorithm)?;
+        }
+        Ok(())
+    }
+}
+impl Error for SelfTestError{}
+#[cfg(test)]
+mod hash_algorithm_tests {
+    use super::{BaseAlgorithm, HashAlgorithm};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn all_contains_every_variant_in_order(){
+        let all = HashAlgorithm::all();
+        assert_eq!(all.len(), HashAlgorithm::iter().count());
+        for (listed, declared) in all.iter().zip(HashAlgorithm::iter()) {
+            assert_eq!(*listed, declared);
+        }
+    }
+
+    #[test]
+    fn algorithm_groups(){
+        assert!(HashAlgorithm::HmacSha256.is_hmac());
+        assert!(!HashAlgorithm::HmacSha256.is_legacy_v06());
+        assert_eq!(HashAlgorithm::HmacSha256.base_algorithm(), BaseAlgorithm::Sha256);
+        assert!(!HashAlgorithm::Ripemd128.is_hmac());
+        assert_eq!(HashAlgorithm::Ripemd128.base_algorithm(), BaseAlgorithm::Ripemd128);
+        assert!(!HashAlgorithm::Md5Version06.is_hmac());
+        assert!(HashAlgorithm::Md5Version06.is_legacy_v06());
+        assert_eq!(HashAlgorithm::Md5Version06.base_algorithm(), BaseAlgorithm::Md5);
+        assert!(HashAlgorithm::HmacMd5Version06.is_hmac());
+        assert!(HashAlgorithm::HmacMd5Version06.is_legacy_v06());
+        assert_eq!(HashAlgorithm::HmacTiger.base_algorithm(), BaseAlgorithm::Tiger);
+        //Every base algorithm has exactly one plain and one HMAC variant that are not V0.6.
+        for algorithm in HashAlgorithm::iter().filter(|a| !a.is_legacy_v06()) {
+            let same_base = HashAlgorithm::iter().filter(|a| !a.is_legacy_v06() && a.base_algorithm() == algorithm.base_algorithm());
+            assert_eq!(same_base.filter(|a| a.is_hmac() != algorithm.is_hmac()).count(), 1);
+        }
+    }
+
+    #[test]
+    fn output_bytes(){
+        for algorithm in HashAlgorithm::iter() {
+            let expected = match algorithm {
+                HashAlgorithm::Md4 | HashAlgorithm::HmacMd4
+                    | HashAlgorithm::Md5 | HashAlgorithm::HmacMd5
+                    | HashAlgorithm::Md5Version06 | HashAlgorithm::HmacMd5Version06
+                    | HashAlgorithm::Ripemd128 | HashAlgorithm::HmacRipemd128 => 16,
+                HashAlgorithm::Sha1 | HashAlgorithm::HmacSha1
+                    | HashAlgorithm::Ripemd160 | HashAlgorithm::HmacRipemd160 => 20,
+                HashAlgorithm::Tiger | HashAlgorithm::HmacTiger => 24,
+                HashAlgorithm::Sha256 | HashAlgorithm::HmacSha256 => 32,
+            };
+            assert_eq!(algorithm.output_bytes(), expected, "{}", algorithm);
+        }
+    }
+
+    #[cfg(feature = "strum")]
+    #[test]
+    fn display_round_trips_through_from_str(){
+        for algorithm in HashAlgorithm::all() {
+            let parsed : HashAlgorithm = algorithm.to_string().parse().unwrap();
+            assert_eq!(parsed, *algorithm);
         }
     }
 }
-impl Error for SettingsError{}
\ No newline at end of file
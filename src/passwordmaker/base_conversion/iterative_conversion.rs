@@ -23,7 +23,8 @@ pub(crate) struct IterativeBaseConversion<V,B>{
 #[allow(clippy::trait_duplication_in_bounds)] //That's obviously a false-positive in clippy...
 impl<V,B> IterativeBaseConversion<V,B> 
     where V: for<'a> From<&'a B> +                          //could be replaced by num::traits::identities::One.
-             PrecomputedMaxPowers<B>,
+             PrecomputedMaxPowers<B> +
+             MaxPowersCache<B>,
           for<'a> &'a V : Mul<&'a B, Output = Option<V>> +  //used to get the first current_base_power.
                           Mul<&'a V, Output = Option<V>>
 {
@@ -41,7 +42,13 @@ impl<V,B> IterativeBaseConversion<V,B>
     #[allow(clippy::map_unwrap_or)] //current code seems to be measurably faster.
     fn find_highest_fitting_power(base : &B) -> PowerAndExponent<V> {
         V::lookup(base).map(|(power,count)| PowerAndExponent{ power, exponent: count })
-            .unwrap_or_else(|| Self::find_highest_fitting_power_non_cached(base))
+            .unwrap_or_else(|| {
+                let (power, exponent) = V::get_or_insert_with(base, || {
+                    let PowerAndExponent{ power, exponent } = Self::find_highest_fitting_power_non_cached(base);
+                    (power, exponent)
+                });
+                PowerAndExponent{ power, exponent }
+            })
     }
 
     //public for unit tests in cache, which is not a sub-module of this.
@@ -113,8 +120,14 @@ pub(crate) trait PrecomputedMaxPowers<B> where Self : Sized{
     fn lookup(_base : &B) -> Option<(Self, usize)> { None }
 }
 
+/// Runtime cache for the bases that [`PrecomputedMaxPowers`] does not cover. The default implementation does not cache anything.
+pub(crate) trait MaxPowersCache<B> where Self : Sized{
+    fn get_or_insert_with<F : FnOnce() -> (Self, usize)>(_base : &B, compute : F) -> (Self, usize) { compute() }
+}
+
 //tests general behaviour, using primitive types.
 #[cfg(test)]
+#[allow(clippy::unreadable_literal, clippy::cast_lossless)]
 mod iterative_conversion_tests{
     use std::{ops::Mul, convert::{From, TryFrom}};
 
@@ -125,14 +138,14 @@ mod iterative_conversion_tests{
     impl Mul<&u64> for &MyU128 {
         type Output = Option<MyU128>;
         fn mul(self, rhs: &u64) -> Self::Output {
-            self.0.checked_mul(*rhs as u128).map(|s| MyU128(s))
+            self.0.checked_mul(*rhs as u128).map(MyU128)
         }
     }
 
     impl Mul<&MyU128> for &MyU128 {
         type Output = Option<MyU128>;
         fn mul(self, rhs: &MyU128) -> Self::Output {
-            self.0.checked_mul(rhs.0).map(|s| MyU128(s))
+            self.0.checked_mul(rhs.0).map(MyU128)
         }
     }
 
@@ -151,7 +164,7 @@ mod iterative_conversion_tests{
     }
     impl From<&u64> for MyU128{
         fn from(v: &u64) -> Self {
-            MyU128(v.clone() as u128)
+            MyU128(*v as u128)
         }
     }
 
@@ -170,6 +183,7 @@ mod iterative_conversion_tests{
     }
 
     impl PrecomputedMaxPowers<u64> for MyU128{}
+    impl MaxPowersCache<u64> for MyU128{}
 
     #[test]
     fn test_simple_u128_to_hex_conversion(){
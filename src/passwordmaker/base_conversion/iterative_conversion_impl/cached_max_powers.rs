@@ -0,0 +1,112 @@
+//! Runtime cache of max fitting powers and exponents for the character set lengths that are not precomputed.
+//! Large custom character sets (for instance a couple of hundred Unicode symbols) would otherwise need to compute them
+//! again for every password part.
+//!
+//! The caches are thread-local, so there is no locking. Every thread fills its own cache, which is fine, given that there are
+//! usually only a handful of different character set lengths in use. Each cache holds at most [`CAPACITY`] entries, and drops
+//! the least recently used one if full, so that character sets supplied by untrusted users can't make it grow without bound.
+
+use std::cell::RefCell;
+use std::thread::LocalKey;
+
+use super::super::iterative_conversion::MaxPowersCache;
+use super::ArbitraryBytes;
+
+/// Number of character set lengths each cache remembers, per thread.
+const CAPACITY : usize = 8;
+
+//Ordered from most to least recently used. Small enough that a linear search beats any map.
+type Cache<const N : usize> = RefCell<Vec<(usize, (ArbitraryBytes<N>, usize))>>;
+
+thread_local!{
+    static MAX_POWER_CACHE_5 : Cache<5> = RefCell::new(Vec::with_capacity(CAPACITY));
+    static MAX_POWER_CACHE_6 : Cache<6> = RefCell::new(Vec::with_capacity(CAPACITY));
+    static MAX_POWER_CACHE_8 : Cache<8> = RefCell::new(Vec::with_capacity(CAPACITY));
+}
+
+impl MaxPowersCache<usize> for ArbitraryBytes<5>{
+    fn get_or_insert_with<F : FnOnce() -> (Self, usize)>(base : &usize, compute : F) -> (Self, usize) {
+        get_or_insert_with(&MAX_POWER_CACHE_5, *base, compute)
+    }
+}
+
+impl MaxPowersCache<usize> for ArbitraryBytes<6>{
+    fn get_or_insert_with<F : FnOnce() -> (Self, usize)>(base : &usize, compute : F) -> (Self, usize) {
+        get_or_insert_with(&MAX_POWER_CACHE_6, *base, compute)
+    }
+}
+
+impl MaxPowersCache<usize> for ArbitraryBytes<8>{
+    fn get_or_insert_with<F : FnOnce() -> (Self, usize)>(base : &usize, compute : F) -> (Self, usize) {
+        get_or_insert_with(&MAX_POWER_CACHE_8, *base, compute)
+    }
+}
+
+fn get_or_insert_with<const N : usize, F>(cache : &'static LocalKey<Cache<N>>, base : usize, compute : F) -> (ArbitraryBytes<N>, usize)
+    where F : FnOnce() -> (ArbitraryBytes<N>, usize)
+{
+    cache.with(|cache| {
+        let cached = {
+            let mut entries = cache.borrow_mut();
+            entries.iter().position(|(cached_base, _)| *cached_base == base).map(|index| {
+                entries[..=index].rotate_right(1);
+                entries[0].1.clone()
+            })
+        };
+        //compute() runs while the cache is not borrowed.
+        cached.unwrap_or_else(|| {
+            let result = compute();
+            let mut entries = cache.borrow_mut();
+            entries.truncate(CAPACITY - 1);
+            entries.insert(0, (base, result.clone()));
+            result
+        })
+    })
+}
+
+#[cfg(test)]
+mod cached_max_powers_tests{
+    use super::super::super::iterative_conversion::{IterativeBaseConversion, MaxPowersCache};
+    use super::{ArbitraryBytes, CAPACITY};
+
+    fn compute_6(base : usize) -> (ArbitraryBytes<6>, usize) {
+        let result = IterativeBaseConversion::<ArbitraryBytes<6>,usize>::find_highest_fitting_power_non_cached(&base);
+        (result.power, result.exponent)
+    }
+
+    #[test]
+    fn cache_matches_non_cached_8(){
+        for base in 130..300usize {
+            let non_cached_result = IterativeBaseConversion::<ArbitraryBytes<8>,usize>::find_highest_fitting_power_non_cached(&base);
+            let (power, exponent) = ArbitraryBytes::<8>::get_or_insert_with(&base, || (non_cached_result.power.clone(), non_cached_result.exponent));
+            assert_eq!(exponent, non_cached_result.exponent);
+            assert_eq!(power, non_cached_result.power);
+        }
+    }
+
+    #[test]
+    fn second_lookup_is_cached(){
+        let base = 200usize;
+        let computed = ArbitraryBytes::<5>::get_or_insert_with(&base, || {
+            let result = IterativeBaseConversion::<ArbitraryBytes<5>,usize>::find_highest_fitting_power_non_cached(&base);
+            (result.power, result.exponent)
+        });
+        let cached = ArbitraryBytes::<5>::get_or_insert_with(&base, || unreachable!("The value for this base is already cached."));
+        assert_eq!(cached, computed);
+    }
+
+    #[test]
+    fn least_recently_used_entry_is_dropped(){
+        let bases : Vec<usize> = (300..).take(CAPACITY + 1).collect();
+        for &base in &bases[..CAPACITY] {
+            ArbitraryBytes::<6>::get_or_insert_with(&base, || compute_6(base));
+        }
+        //Using the oldest entry makes the second-oldest the least recently used one.
+        ArbitraryBytes::<6>::get_or_insert_with(&bases[0], || unreachable!("The value for this base is already cached."));
+        ArbitraryBytes::<6>::get_or_insert_with(&bases[CAPACITY], || compute_6(bases[CAPACITY]));
+        ArbitraryBytes::<6>::get_or_insert_with(&bases[0], || unreachable!("The value for this base is still cached."));
+        let mut recomputed = false;
+        ArbitraryBytes::<6>::get_or_insert_with(&bases[1], || { recomputed = true; compute_6(bases[1]) });
+        assert!(recomputed);
+    }
+}
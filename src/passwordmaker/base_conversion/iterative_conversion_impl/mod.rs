@@ -8,13 +8,15 @@
 mod precomputed_constants;
 #[cfg(all(not(feature="precomputed_max_powers"),feature="precomputed_common_max_powers"))]
 mod precomputed_common_constants;
+#[cfg(feature="cached_max_powers")]
+mod cached_max_powers;
 
 use std::ops::{DivAssign, Mul, MulAssign};
 use std::convert::{TryFrom, TryInto};
 use std::fmt::Display;
 use std::error::Error;
 
-use super::iterative_conversion::{RemAssignWithQuotient, PrecomputedMaxPowers};
+use super::iterative_conversion::{RemAssignWithQuotient, PrecomputedMaxPowers, MaxPowersCache};
 
 //Type to be used as V, with usize as B.
 pub(crate) struct SixteenBytes(u128);
@@ -76,6 +78,8 @@ impl MulAssign<&usize> for SixteenBytes {
 }
 
 impl PrecomputedMaxPowers<usize> for SixteenBytes{}
+//Computing the max power of u128 is cheap enough that a cache lookup would not be faster.
+impl MaxPowersCache<usize> for SixteenBytes{}
 
 //--------------------------------------------------------------------------------------------------------------------------------------
 //and now the hard part: The same for [u32;N].
@@ -87,8 +91,17 @@ pub(crate) struct ArbitraryBytes<const N : usize>([u32;N]);
 #[cfg(not(any(feature="precomputed_max_powers", feature="precomputed_common_max_powers")))]
 impl PrecomputedMaxPowers<usize> for ArbitraryBytes<5>{}
 #[cfg(not(any(feature="precomputed_max_powers", feature="precomputed_common_max_powers")))]
+impl PrecomputedMaxPowers<usize> for ArbitraryBytes<6>{}
+#[cfg(not(any(feature="precomputed_max_powers", feature="precomputed_common_max_powers")))]
 impl PrecomputedMaxPowers<usize> for ArbitraryBytes<8>{}
 
+#[cfg(not(feature="cached_max_powers"))]
+impl MaxPowersCache<usize> for ArbitraryBytes<5>{}
+#[cfg(not(feature="cached_max_powers"))]
+impl MaxPowersCache<usize> for ArbitraryBytes<6>{}
+#[cfg(not(feature="cached_max_powers"))]
+impl MaxPowersCache<usize> for ArbitraryBytes<8>{}
+
 #[allow(clippy::cast_possible_truncation)]
 const fn from_usize<const N : usize>(x : usize) -> ArbitraryBytes<N> {
     let mut result = [0;N]; //from Godbolt it looks like the compiler is smart enough to skip the unnecessary inits.
@@ -106,7 +119,7 @@ impl<const N : usize> From<&usize> for ArbitraryBytes<N>{
 impl<const N : usize> From<&u32> for ArbitraryBytes<N>{
     fn from(x: &u32) -> Self {
         let mut result = [0;N];
-        if let Some(l) = result.last_mut() { *l = *x };
+        if let Some(l) = result.last_mut() { *l = *x }
         ArbitraryBytes(result)
     }
 }
@@ -136,6 +149,21 @@ impl PadWithAZero for ArbitraryBytes<5>{
     }
 }
 
+impl PadWithAZero for ArbitraryBytes<6>{
+    type Output = ArbitraryBytes<7>;
+    fn pad_with_a_zero(&self) -> Self::Output {
+        ArbitraryBytes::<7>([
+            0,
+            self.0[0],
+            self.0[1],
+            self.0[2],
+            self.0[3],
+            self.0[4],
+            self.0[5],
+        ])
+    }
+}
+
 impl PadWithAZero for ArbitraryBytes<8>{
     type Output = ArbitraryBytes<9>;
     fn pad_with_a_zero(&self) -> Self::Output {
@@ -173,6 +201,27 @@ impl PaddedShiftLeft for ArbitraryBytes<5>{
     }
 }
 
+impl PaddedShiftLeft for ArbitraryBytes<6>{
+    type Output = ArbitraryBytes::<7>;
+
+    fn padded_shift_left(&self, shift : u32) -> Self::Output {
+        debug_assert!(shift < 32);
+        if shift == 0 {
+            self.pad_with_a_zero()
+        } else {
+            ArbitraryBytes([
+                                        self.0[0] >> (32-shift),
+                (self.0[0] << shift) | (self.0[1] >> (32-shift)),
+                (self.0[1] << shift) | (self.0[2] >> (32-shift)),
+                (self.0[2] << shift) | (self.0[3] >> (32-shift)),
+                (self.0[3] << shift) | (self.0[4] >> (32-shift)),
+                (self.0[4] << shift) | (self.0[5] >> (32-shift)),
+                 self.0[5] << shift
+            ])
+        }
+    }
+}
+
 impl PaddedShiftLeft for ArbitraryBytes<8>{
     type Output = ArbitraryBytes::<9>;
 
@@ -276,6 +325,7 @@ macro_rules! make_mul {
                 $cfn(self, rhs)
             }
         }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_lossless)] //truncation is intentional, and From is not const.
         const fn $cfn<const N : usize>(mut lhs : ArbitraryBytes<N>, rhs: $t) -> Option<ArbitraryBytes<N>> {
             //sorry for this fugly non-idiomatic syntax, but Rust const functions seem to be severely limited right now :-(
             let mut carry = 0 as $long_t;
@@ -310,7 +360,7 @@ impl<const N : usize> Mul<&usize> for &ArbitraryBytes<N>{
 
 //Done separately, because "mut references not allowed in const contexts"
 impl<const N : usize> MulAssign<&usize> for ArbitraryBytes<N>{
-    #[allow(clippy::cast_possible_truncation)] //truncation is intentional here.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_lossless)] //truncation is intentional here.
     fn mul_assign(&mut self, rhs: &usize) {
         #[cfg(target_pointer_width = "64")]
         type Long = u128;
@@ -340,7 +390,7 @@ impl<const N : usize> Mul<&ArbitraryBytes<N>> for &ArbitraryBytes<N> where Arbit
             });
             carry.filter(|x| !x).map(|_|())
         });
-        no_overflow.map(|_| result)
+        no_overflow.map(|()| result)
     }
 }
 
@@ -370,6 +420,7 @@ impl<const N : usize, const M : usize> RemAssignWithQuotient for ArbitraryBytes<
 macro_rules! make_div_assign_with_remainder {
     ($name:ident, $t_divisor:ty, $t_long:ty) => {
         /// Replaces self with Quotient and returns Remainder
+        #[allow(clippy::cast_possible_truncation, clippy::cast_lossless)] //macro is used for different types. Truncation is intentional.
         fn $name(&mut self, rhs: $t_divisor) -> $t_divisor {
             debug_assert!((<$t_long>::MAX >> 32) as u128 >= <$t_divisor>::MAX as u128);
 
@@ -528,6 +579,7 @@ fn u64_from_u32s(m : u32, l : u32) -> u64{
 }
 
 #[cfg(test)]
+#[allow(clippy::unreadable_literal, clippy::cast_lossless, clippy::semicolon_if_nothing_returned, clippy::doc_markdown)]
 mod arbitrary_bytes_tests{
     use std::iter::successors;
 
@@ -737,6 +789,13 @@ mod arbitrary_bytes_tests{
         assert_eq!(b.0,[0x21, 0x53DF817F,0xFFFFFFE3, 0x89C5EA89, 0x1A2B3C55, 0xE6F00900]);
     }
 
+    #[test]
+    fn shift_left_test_6() {
+        let a = ArbitraryBytes::new([0x074c4d0a,0x42a7bf02,0xffffffff,0xc7138bd5,0x12345678,0xabcde012]);
+        let b = a.padded_shift_left(7);
+        assert_eq!(b.0,[0x03, 0xA626_8521, 0x53DF_817F, 0xFFFF_FFE3, 0x89C5_EA89, 0x1A2B_3C55, 0xE6F0_0900]);
+    }
+
     #[test]
     fn shift_left_test_8() {
         let a = ArbitraryBytes::new([0x4631abcd,0x35a40be4,0x074c4d0a,0x42a7bf02,0xffffffff,0xc7138bd5,0x12345678,0xabcde012]);
@@ -925,6 +984,13 @@ mod arbitrary_bytes_tests{
         assert_eq!(b.0[1..], a.0);
     }
     #[test]
+    fn pad_with_a_zero_6(){
+        let a = ArbitraryBytes::new([0x074c4d0a,0x42a7bf02,0xffffffff,0xc7138bd5,0x12345678,0xabcde012]);
+        let b = a.pad_with_a_zero();
+        assert_eq!(*b.0.first().unwrap(),0);
+        assert_eq!(b.0[1..], a.0);
+    }
+    #[test]
     fn pad_with_a_zero_8(){
         let a = ArbitraryBytes::new([0x4631abcd,0x35a40be4,0x074c4d0a,0x42a7bf02,0xffffffff,0xc7138bd5,0x12345678,0xabcde012]);
         let b = a.pad_with_a_zero();
@@ -992,6 +1058,24 @@ mod arbitrary_bytes_tests{
         }
     }
     #[test]
+    fn compare_conversion_by_division_randoms_6(){
+        let mut rng = Xoshiro256Plus::seed_from_u64(0);
+        for _ in 0..10000 {
+            let v = ArbitraryBytes::new([
+                rng.next_u32(),
+                rng.next_u32(),
+                rng.next_u32(),
+                rng.next_u32(),
+                rng.next_u32(),
+                rng.next_u32(),
+            ]);
+            let b = rng.next_u32() as usize;
+            let i1 = super::super::IterativeBaseConversion::new(v.clone(),b).skip_while(|v| *v == 0);
+            let i2 = convert_by_division(v,b);
+            assert!(i1.eq(i2));
+        }
+    }
+    #[test]
     fn compare_conversion_by_division_randoms_5(){
         let mut rng = Xoshiro256Plus::seed_from_u64(0);
         for _ in 0..10000 {
@@ -23,6 +23,20 @@ impl PrecomputedMaxPowers<usize> for ArbitraryBytes<5>{
     }
 }
 
+impl PrecomputedMaxPowers<usize> for ArbitraryBytes<6>{
+    fn lookup(base : &usize) -> Option<(Self, usize)> { 
+        match base {
+            10 => Some((ArbitraryBytes([0x28C8_7CB5, 0xC89A_2571, 0xEBFD_CB54, 0x864A_DA83, 0x4A00_0000, 0x0000_0000]), 57)),
+            16 => Some((ArbitraryBytes([0x1000_0000, 0x0000_0000, 0x0000_0000, 0x0000_0000, 0x0000_0000, 0x0000_0000]), 47)),
+            32 => Some((ArbitraryBytes([0x4000_0000, 0x0000_0000, 0x0000_0000, 0x0000_0000, 0x0000_0000, 0x0000_0000]), 38)),
+            52 => Some((ArbitraryBytes([0x1152_5C90, 0xB214_B25F, 0x0CA1_4F85, 0x8FC9_FA34, 0x0000_0000, 0x0000_0000]), 33)),
+            62 => Some((ArbitraryBytes([0x5CAF_A7CA, 0xB31F_865A, 0x521F_DFC0, 0x32F3_ABD7, 0x7DD7_BC01, 0x0000_0000]), 32)),
+            94 => Some((ArbitraryBytes([0x43CB_27E4, 0x89BF_96D3, 0xE5F9_0506, 0x3A68_F7DF, 0xB314_1BED, 0xE000_0000]), 29)),
+            _ => None
+        }
+    }
+}
+
 impl PrecomputedMaxPowers<usize> for ArbitraryBytes<8>{
     fn lookup(base : &usize) -> Option<(Self, usize)> { 
         match base {
@@ -57,6 +71,19 @@ mod precomputed_common_constants_tests{
         assert!(count > 0);
     }
     #[test]
+    fn highest_fitting_power_consistency_6(){
+        let mut count = 0;
+        for base in 2..200 {
+            if let Some(precomputed) = ArbitraryBytes::<6>::lookup(&base) {
+                let non_cached_result = IterativeBaseConversion::<ArbitraryBytes<6>,usize>::find_highest_fitting_power_non_cached(&base);
+                assert_eq!(non_cached_result.exponent, precomputed.1);
+                assert_eq!(non_cached_result.power, precomputed.0);
+                count += 1;
+            }
+        }
+        assert!(count > 0);
+    }
+    #[test]
     fn highest_fitting_power_consistency_8(){
         let mut count = 0;
         for base in 2..200 {
@@ -8,6 +8,12 @@ impl PrecomputedMaxPowers<usize> for ArbitraryBytes<5>{
     }
 }
 
+impl PrecomputedMaxPowers<usize> for ArbitraryBytes<6>{
+    fn lookup(base : &usize) -> Option<(Self, usize)> { 
+        get_from_cache(*base, &CONSTANT_MAX_POWER_CACHE_6)
+    }
+}
+
 impl PrecomputedMaxPowers<usize> for ArbitraryBytes<8>{
     fn lookup(base : &usize) -> Option<(Self, usize)> { 
         get_from_cache(*base, &CONSTANT_MAX_POWER_CACHE_8)
@@ -20,6 +26,7 @@ fn get_from_cache<const N : usize>(base : usize, cache : &[([u32;N], usize)]) ->
 }
 
 const CONSTANT_MAX_POWER_CACHE_5 : [([u32;5],usize);128] = gen_const_max_power_cache();
+const CONSTANT_MAX_POWER_CACHE_6 : [([u32;6],usize);128] = gen_const_max_power_cache();
 const CONSTANT_MAX_POWER_CACHE_8 : [([u32;8],usize);128] = gen_const_max_power_cache();
 
 //-----------------------------------------------------------------------------------------
@@ -61,7 +68,16 @@ mod iterative_conversion_constants_tests{
         let entries = super::CONSTANT_MAX_POWER_CACHE_8.iter().enumerate()
             .map(|(i,(p,e))| (i+2, ArbitraryBytes(*p), *e));
         for (base, power, _exponent) in entries {
-            assert!((power * base).is_none())
+            assert!((power * base).is_none());
+        }
+    }
+    #[test]
+    fn test_overlows_6()
+    {
+        let entries = super::CONSTANT_MAX_POWER_CACHE_6.iter().enumerate()
+            .map(|(i,(p,e))| (i+2, ArbitraryBytes(*p), *e));
+        for (base, power, _exponent) in entries {
+            assert!((power * base).is_none());
         }
     }
     #[test]
@@ -70,7 +86,7 @@ mod iterative_conversion_constants_tests{
         let entries = super::CONSTANT_MAX_POWER_CACHE_5.iter().enumerate()
             .map(|(i,(p,e))| (i+2, ArbitraryBytes(*p), *e));
         for (base, power, _exponent) in entries {
-            assert!((power * base).is_none())
+            assert!((power * base).is_none());
         }
     }
     #[test]
@@ -88,6 +104,20 @@ mod iterative_conversion_constants_tests{
         }
     }
     #[test]
+    fn test_exponent_6()
+    {
+        let entries = super::CONSTANT_MAX_POWER_CACHE_6.iter().enumerate()
+            .map(|(i,(p,e))| (i+2, ArbitraryBytes(*p), *e));
+        for (base, mut power, exponent) in entries {
+            //exponent is the largest fitting exponent. Soo, if we divide exponent times, we should end up with 1.
+            for _i in 0..exponent  {
+                let remainder = power.div_assign_with_remainder_usize(base);
+                assert_eq!(remainder, 0);
+            }
+            assert_eq!(power, (&1usize).into());
+        }
+    }
+    #[test]
     fn test_exponent_5()
     {
         let entries = super::CONSTANT_MAX_POWER_CACHE_5.iter().enumerate()
@@ -113,6 +143,17 @@ mod iterative_conversion_constants_tests{
         }
     }
     #[test]
+    fn highest_fitting_power_consistency_6(){
+        use super::super::super::iterative_conversion::IterativeBaseConversion;
+        let entries = super::CONSTANT_MAX_POWER_CACHE_6.iter().enumerate()
+            .map(|(i,(p,e))| (i+2, ArbitraryBytes(*p), *e));
+        for (base, power, exponent) in entries {
+            let non_cached_result = IterativeBaseConversion::<ArbitraryBytes<6>,usize>::find_highest_fitting_power_non_cached(&base);
+            assert_eq!(non_cached_result.exponent,exponent);
+            assert_eq!(non_cached_result.power, power);
+        }
+    }
+    #[test]
     fn highest_fitting_power_consistency_8(){
         use super::super::super::iterative_conversion::IterativeBaseConversion;
         let entries = super::CONSTANT_MAX_POWER_CACHE_8.iter().enumerate()
@@ -1,9 +1,10 @@
 use std::convert::TryInto;
+use std::iter::successors;
 use iterative_conversion_impl::PaddedShiftLeft;
 pub(super) use iterative_conversion::IterativeBaseConversion;
 pub(super) use iterative_conversion_impl::{SixteenBytes, ArbitraryBytes};
 
-use self::iterative_conversion::PrecomputedMaxPowers;
+use self::iterative_conversion::{PrecomputedMaxPowers, MaxPowersCache};
 
 mod iterative_conversion;
 mod iterative_conversion_impl;
@@ -17,7 +18,7 @@ pub(super) trait BaseConversion {
 #[allow(clippy::trait_duplication_in_bounds)] //False positive in clippy. usize != u32.
 impl<T, const N : usize, const M : usize> BaseConversion for T 
     where T : ToArbitraryBytes<Output = ArbitraryBytes<N>>,
-        for<'a> T::Output: From<&'a usize> + From<&'a u32> + PaddedShiftLeft<Output = ArbitraryBytes<M>> + PrecomputedMaxPowers<usize>,
+        for<'a> T::Output: From<&'a usize> + From<&'a u32> + PaddedShiftLeft<Output = ArbitraryBytes<M>> + PrecomputedMaxPowers<usize> + MaxPowersCache<usize>,
 {
     type Output = IterativeBaseConversion<ArbitraryBytes<N>, usize>;
     fn convert_to_base(self, base : usize) -> Self::Output {
@@ -55,6 +56,20 @@ impl ToArbitraryBytes for [u8;20] {
     }
 }
 
+impl ToArbitraryBytes for [u8;24] {
+    type Output = ArbitraryBytes<6>;
+    fn to_arbitrary_bytes(self) -> ArbitraryBytes<6> {
+        ArbitraryBytes::new([
+            u32::from_be_bytes(self[0..4].try_into().unwrap()),
+            u32::from_be_bytes(self[4..8].try_into().unwrap()),
+            u32::from_be_bytes(self[8..12].try_into().unwrap()),
+            u32::from_be_bytes(self[12..16].try_into().unwrap()),
+            u32::from_be_bytes(self[16..20].try_into().unwrap()),
+            u32::from_be_bytes(self[20..24].try_into().unwrap()),
+        ])
+    }
+}
+
 impl ToArbitraryBytes for [u8;32] {
     type Output = ArbitraryBytes<8>;
     fn to_arbitrary_bytes(self) -> ArbitraryBytes<8> {
@@ -69,4 +84,231 @@ impl ToArbitraryBytes for [u8;32] {
             u32::from_be_bytes(self[28..32].try_into().unwrap()),
         ])
     }
-}
\ No newline at end of file
+}
+/// Writes the `max_count` most significant digits of `value` in `base` to `digits`, most significant first. Returns the number
+/// of digits written. Leading zeros are skipped, unless they are needed to reach `min_digits` digits in total.
+/// 
+/// Unlike [`IterativeBaseConversion`], which needs a `u128` division for every digit, this splits `value` into chunks of the
+/// largest power of `base` that fits into a `u32`, and then only needs `u32` divisions within each chunk. The catch is that the
+/// number of digits is only known after all chunks are computed, so this can't yield digits lazily.
+pub(super) fn u128_leading_digits(mut value : u128, base : u32, min_digits : usize, max_count : usize, digits : &mut [u32;128]) -> usize {
+    let (chunk_base, chunk_digits) = successors(Some((base, 1)), |(power, exponent)| power.checked_mul(base).map(|power| (power, exponent + 1)))
+        .last()
+        .expect("Cannot fail, the first entry is Some.");
+    //Least significant first. Each chunk holds more than 16 bits, so there are at most 8.
+    let mut chunks = [0u32;8];
+    let mut chunk_count = 0;
+    while value != 0 {
+        let quotient = value / u128::from(chunk_base);
+        //Cannot truncate, the remainder is smaller than chunk_base.
+        #[allow(clippy::cast_possible_truncation)]
+        let remainder = (value - quotient * u128::from(chunk_base)) as u32;
+        chunks[chunk_count] = remainder;
+        chunk_count += 1;
+        value = quotient;
+    }
+    let top_chunk_digits = chunk_count.checked_sub(1)
+        .map_or(0, |top| successors(Some(chunks[top]), |rest| Some(rest / base).filter(|rest| *rest != 0)).count());
+    let total_digits = (chunk_count.saturating_sub(1) * chunk_digits + top_chunk_digits).max(min_digits);
+    let count = total_digits.min(max_count);
+    //Position of a digit, counted from the least significant one. Only positions from total_digits - count upwards are written.
+    let lowest_position = total_digits - count;
+    let mut written = 0;
+    for chunk_index in (lowest_position / chunk_digits..(total_digits + chunk_digits - 1) / chunk_digits).rev() {
+        let mut chunk = chunks.get(chunk_index).copied().unwrap_or(0);
+        let mut chunk_buffer = [0u32;32];
+        for digit in chunk_buffer.iter_mut().take(chunk_digits) {
+            *digit = chunk % base;
+            chunk /= base;
+        }
+        let first_position = chunk_index * chunk_digits;
+        let positions = first_position.max(lowest_position)..(first_position + chunk_digits).min(total_digits);
+        for position in positions.rev() {
+            digits[written] = chunk_buffer[position - first_position];
+            written += 1;
+        }
+    }
+    written
+}
+
+/// Returns true if the highest power of `base` that fits into a hash of `hash_bytes` bytes is among the precomputed constants.
+/// 16 byte hashes are stored in a `u128`, for which nothing is precomputed, as computing the power is cheap anyhow.
+pub(crate) fn is_base_precomputed(base : usize, hash_bytes : usize) -> bool {
+    match hash_bytes {
+        20 => ArbitraryBytes::<5>::lookup(&base).is_some(),
+        24 => ArbitraryBytes::<6>::lookup(&base).is_some(),
+        32 => ArbitraryBytes::<8>::lookup(&base).is_some(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unreadable_literal)]
+mod twenty_byte_conversion_tests{
+    use super::*;
+    use num_bigint::BigUint;
+    use rand::RngCore;
+    use rand_xoshiro::rand_core::SeedableRng;
+    use rand_xoshiro::Xoshiro256Plus;
+
+    /// SHA-1 and RIPEMD-160 both end up here, so the bases cover the character set sizes users typically pick, and some odd ones.
+    const BASES : [usize;12] = [2, 3, 7, 10, 16, 17, 26, 36, 62, 94, 255, 257];
+
+    fn check_against_reference(input : [u8;20], base : usize){
+        let converted = input.convert_to_base(base);
+        let expected_len = converted.len();
+        let digits : Vec<_> = converted.collect();
+        assert_eq!(digits.len(), expected_len);
+        let mut reference = reference_digits(&input, base);
+        assert!(reference.len() <= digits.len(), "Too few digits for base {} and input {:x?}", base, input);
+        let padding = digits.len() - reference.len();
+        reference.splice(0..0, std::iter::repeat(0).take(padding));
+        assert_eq!(digits, reference, "Mismatch for base {} and input {:x?}", base, input);
+    }
+
+    /// Plain repeated division, because `BigUint::to_radix_be` only supports bases up to 256.
+    fn reference_digits(input : &[u8], base : usize) -> Vec<usize>{
+        let mut number = BigUint::from_bytes_be(input);
+        let base = BigUint::from(base);
+        let mut digits = Vec::new();
+        while number != BigUint::from(0u32) {
+            let digit = &number % &base;
+            digits.push(digit.to_u32_digits().first().copied().unwrap_or(0) as usize);
+            number /= &base;
+        }
+        digits.reverse();
+        digits
+    }
+
+    fn from_limbs(limbs : [u32;5]) -> [u8;20]{
+        let mut result = [0u8;20];
+        for (chunk, limb) in result.chunks_exact_mut(4).zip(limbs.iter()) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+        result
+    }
+
+    #[test]
+    fn leading_zero_limbs_match_reference(){
+        for zero_limbs in 0..5 {
+            let mut limbs = [0x9e3779b9_u32, 0x7f4a7c15, 0x01234567, 0x89abcdef, 0xfedcba98];
+            for limb in limbs.iter_mut().take(zero_limbs) {
+                *limb = 0;
+            }
+            for base in &BASES {
+                check_against_reference(from_limbs(limbs), *base);
+            }
+        }
+        for base in &BASES {
+            check_against_reference([0;20], *base);
+        }
+    }
+
+    #[test]
+    fn high_bit_limbs_match_reference(){
+        let patterns = [
+            [u32::MAX;5],
+            [0x8000_0000;5],
+            [0x8000_0000, 0, 0, 0, 0],
+            [0, 0x8000_0000, 0, 0, 1],
+            [0, 0, u32::MAX, 0x8000_0000, u32::MAX],
+            [0x8000_0000, u32::MAX, 0, u32::MAX, 0x8000_0000],
+            [0, 0, 0, 0, u32::MAX],
+            [0, 0, 0, 0x8000_0000, 0],
+        ];
+        for limbs in &patterns {
+            for base in &BASES {
+                check_against_reference(from_limbs(*limbs), *base);
+            }
+        }
+    }
+
+    #[test]
+    fn random_inputs_match_reference(){
+        let mut rng = Xoshiro256Plus::seed_from_u64(20);
+        for _ in 0..2000 {
+            let mut input = [0u8;20];
+            rng.fill_bytes(&mut input);
+            //mask out some leading bytes, to get more digit counts.
+            let leading_zeros = (rng.next_u32() % 21) as usize;
+            input.iter_mut().take(leading_zeros).for_each(|b| *b = 0);
+            let base = (rng.next_u32() % 300 + 2) as usize;
+            check_against_reference(input, base);
+        }
+    }
+
+    #[test]
+    fn sha1_and_ripemd160_digests_match_reference(){
+        use digest::Digest;
+        for message in &["", "abc", "www.example.com", "\u{1f4a9}"] {
+            let sha1 : [u8;20] = sha1::Sha1::digest(message.as_bytes()).into();
+            let ripemd : [u8;20] = ripemd::Ripemd160::digest(message.as_bytes()).into();
+            for base in &BASES {
+                check_against_reference(sha1, *base);
+                check_against_reference(ripemd, *base);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod leading_digits_tests{
+    use super::*;
+    use rand::RngCore;
+    use rand_xoshiro::rand_core::SeedableRng;
+    use rand_xoshiro::Xoshiro256Plus;
+
+    fn check_against_iterative(value : u128, base : u32, padded : bool, max_count : usize){
+        let converted = value.to_be_bytes().convert_to_base(base as usize);
+        let min_digits = if padded { converted.len() } else { 0 };
+        let iterative : Vec<_> = converted.skip_while(|digit| !padded && *digit == 0).take(max_count).collect();
+        let mut digits = [0;128];
+        let count = u128_leading_digits(value, base, min_digits, max_count, &mut digits);
+        let leading : Vec<_> = digits[..count].iter().map(|digit| *digit as usize).collect();
+        assert_eq!(leading, iterative, "Mismatch for base {}, value {:x}, padded {}, max_count {}", base, value, padded, max_count);
+    }
+
+    #[test]
+    fn edge_cases_match_iterative(){
+        for base in &[2, 3, 10, 16, 94, 255, 65535, 65536, 65537, u32::MAX] {
+            for value in &[0, 1, u128::from(*base) - 1, u128::from(*base), u128::from(u32::MAX), u128::from(u64::MAX), u128::MAX] {
+                for max_count in &[0, 1, 5, 12, 128] {
+                    check_against_iterative(*value, *base, false, *max_count);
+                    check_against_iterative(*value, *base, true, *max_count);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_inputs_match_iterative(){
+        let mut rng = Xoshiro256Plus::seed_from_u64(16);
+        for _ in 0..5000 {
+            let value = u128::from(rng.next_u64()) << 64 | u128::from(rng.next_u64());
+            let value = value >> (rng.next_u32() % 128);
+            let base = rng.next_u32() % 300 + 2;
+            let max_count = (rng.next_u32() % 130) as usize;
+            check_against_iterative(value, base, rng.next_u32() % 2 == 0, max_count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod precomputed_bases_tests{
+    use super::is_base_precomputed;
+
+    #[test]
+    fn common_bases(){
+        let expected = cfg!(any(feature="precomputed_max_powers", feature="precomputed_common_max_powers"));
+        for hash_bytes in &[20, 24, 32] {
+            assert_eq!(is_base_precomputed(94, *hash_bytes), expected);
+            assert_eq!(is_base_precomputed(62, *hash_bytes), expected);
+            assert_eq!(is_base_precomputed(63, *hash_bytes), cfg!(feature="precomputed_max_powers"));
+            assert!(!is_base_precomputed(200, *hash_bytes));
+            assert!(!is_base_precomputed(0, *hash_bytes));
+            assert!(!is_base_precomputed(1, *hash_bytes));
+        }
+        assert!(!is_base_precomputed(94, 16));
+        assert!(!is_base_precomputed(94, 48));
+    }
+}
@@ -0,0 +1,30 @@
+use std::fmt::{Debug, Formatter, Result};
+
+use super::leet::LeetReplacementTable;
+
+/// Stands in for the inputs of generation in the `Debug` output.
+struct Redacted;
+
+impl Debug for Redacted {
+    fn fmt(&self, f : &mut Formatter<'_>) -> Result {
+        f.write_str("\"<redacted>\"")
+    }
+}
+
+//Hand-written instead of derived, so that it doesn't print the hashers, and so that adding fields later can't leak anything.
+//PasswordMaker never stores key or data. They are listed as redacted anyway, so nobody reading a log has to wonder.
+impl<H> Debug for crate::PasswordMaker<'_, H> {
+    fn fmt(&self, f : &mut Formatter<'_>) -> Result {
+        let parameters = &self.password_part_parameters;
+        f.debug_struct("PasswordMaker")
+            .field("hash_algorithm", &parameters.hash_algorithm.to_public_parameters())
+            .field("pre_leet", &parameters.pre_leet_level.as_ref().map(LeetReplacementTable::level))
+            .field("post_leet", &self.post_leet.as_ref().map(LeetReplacementTable::level))
+            .field("charset_len", &parameters.base())
+            .field("output_encoding", &parameters.output_encoding)
+            .field("password_length", &self.assembly_settings.password_length)
+            .field("key", &Redacted)
+            .field("data", &Redacted)
+            .finish()
+    }
+}
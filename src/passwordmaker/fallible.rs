@@ -0,0 +1,89 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use crate::{Fallible, GenerationError, Hasher, TryGenerationError, TryHasher, TryHasherList};
+use super::hash_functions::{HashFunctionsImpl, Unavailable};
+
+/// Turns a [`TryHasher`] into a [`Hasher`]. Being in a private module, this cannot be named outside of this crate, so only
+/// [`try_generate`][crate::PasswordMaker::try_generate] can call it, and always does so from within [`catch_hasher_error`].
+pub struct FallibleHasher<T>(PhantomData<fn() -> T>);
+
+impl<L : TryHasherList> HashFunctionsImpl for Fallible<L> {
+    type MD4 = FallibleHasher<L::MD4>;
+    type MD5 = FallibleHasher<L::MD5>;
+    type SHA1 = FallibleHasher<L::SHA1>;
+    type SHA256 = FallibleHasher<L::SHA256>;
+    type RIPEMD160 = FallibleHasher<L::RIPEMD160>;
+    type RIPEMD128 = Unavailable<16>;
+    type TIGER = Unavailable<24>;
+    const EXTENDED : bool = false;
+}
+
+//Hasher::hash has no self and no return channel for errors, so the first error is stashed per thread, and picked up
+//by catch_hasher_error once generation is done. Boxed as Any, because a static can't be generic over the error type.
+thread_local! {
+    static HASHER_ERROR : RefCell<State> = RefCell::new(State::Inactive);
+}
+
+enum State {
+    /// Not inside [`catch_hasher_error`]. [`FallibleHasher`]s are not called then.
+    Inactive,
+    /// Inside [`catch_hasher_error`], no error so far.
+    Active,
+    /// Inside [`catch_hasher_error`], and a hash function failed.
+    Failed(Box<dyn Any>),
+}
+
+/// Restores the previous state when dropped, also if generation panics.
+struct Guard(Option<State>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            HASHER_ERROR.with(|state| *state.borrow_mut() = previous);
+        }
+    }
+}
+
+/// Runs `generate`, and returns the first error of a [`FallibleHasher`] that occurred meanwhile, if any.
+/// `E` must be the error type of all `FallibleHasher`s used by `generate`.
+pub(crate) fn catch_hasher_error<E : 'static, R, G : FnOnce() -> Result<R, GenerationError>>(generate : G) -> Result<R, TryGenerationError<E>> {
+    let _guard = Guard(Some(HASHER_ERROR.with(|state| state.replace(State::Active))));
+    let result = generate();
+    match HASHER_ERROR.with(|state| state.replace(State::Active)) {
+        State::Failed(error) => Err(TryGenerationError::Hasher(*error.downcast().expect("All hashers of a TryHasherList share the error type"))),
+        State::Inactive | State::Active => Ok(result?),
+    }
+}
+
+impl<T : TryHasher> Hasher for FallibleHasher<T> where T::Output : Default + AsRef<[u8]> + AsMut<[u8]>, T::Error : 'static {
+    type Output = T::Output;
+    fn hash(input : &[u8]) -> Self::Output {
+        //Once failed, the result is discarded anyhow. No need to bother the hash function any more.
+        if HASHER_ERROR.with(|state| matches!(&*state.borrow(), State::Failed(_))) {
+            return dummy_hash();
+        }
+        match T::try_hash(input) {
+            Ok(hash) => hash,
+            Err(error) => {
+                HASHER_ERROR.with(|state| {
+                    let mut state = state.borrow_mut();
+                    debug_assert!(matches!(&*state, State::Active), "FallibleHasher called outside of catch_hasher_error");
+                    *state = State::Failed(Box::new(error));
+                });
+                dummy_hash()
+            },
+        }
+    }
+}
+
+/// Stands in for the hashes after a failure, until generation is done. Must not be zero: A zero hash yields no digits
+/// for most algorithms, and generation would never finish.
+fn dummy_hash<O : Default + AsMut<[u8]>>() -> O {
+    let mut hash = O::default();
+    for byte in hash.as_mut() {
+        *byte = 0xff;
+    }
+    hash
+}
@@ -0,0 +1,167 @@
+use std::hash::Hasher;
+
+#[cfg(feature = "unicode-normalization")]
+use crate::NormalizationForm;
+use crate::{InputEncoding, LengthUnit, OutputEncoding, OutputPadding, PartCounterPlacement};
+use super::leet::LeetReplacementTable;
+use super::{AlgoSelection, Algorithm, HmacOrNot, V06HmacOrNot};
+
+impl<H> crate::PasswordMaker<'_, H> {
+    pub(crate) fn compute_settings_fingerprint(&self) -> u64 {
+        let mut hasher = Fnv1a64::default();
+        let parameters = &self.password_part_parameters;
+        hasher.write_u8(algorithm_id(&parameters.hash_algorithm));
+        write_leet_table(&mut hasher, parameters.pre_leet_level.as_ref());
+        write_leet_table(&mut hasher, self.post_leet.as_ref());
+        hasher.write_u64(parameters.characters.len() as u64);
+        for grapheme in parameters.characters.iter() {
+            write_str(&mut hasher, grapheme.get());
+        }
+        write_str(&mut hasher, &self.username);
+        write_str(&mut hasher, &self.modifier);
+        hasher.write_u64(self.assembly_settings.password_length as u64);
+        write_str(&mut hasher, &self.assembly_settings.prefix);
+        write_str(&mut hasher, &self.assembly_settings.suffix);
+        //Appended last, and only if set, so fingerprints of settings without normalization stay the same as before it was added.
+        #[cfg(feature = "unicode-normalization")]
+        if let Some(form) = self.normalize_unicode {
+            hasher.write_u8(normalization_form_id(form));
+        }
+        //Same for the leet fallback. Its marker byte is distinct from all normalization form ids.
+        if self.post_leet_fallback {
+            hasher.write_u8(0x80);
+        }
+        //And for the counter placement.
+        match self.part_counter_placement {
+            PartCounterPlacement::Key => {},
+            PartCounterPlacement::Data => hasher.write_u8(0x81),
+            PartCounterPlacement::Modifier => hasher.write_u8(0x82),
+        }
+        match parameters.output_encoding {
+            OutputEncoding::Characters => {},
+            OutputEncoding::Hex => hasher.write_u8(0x83),
+            OutputEncoding::Base64 => hasher.write_u8(0x84),
+            OutputEncoding::Base58 => hasher.write_u8(0x85),
+        }
+        if !self.revision.is_empty() {
+            hasher.write_u8(0x86);
+            write_str(&mut hasher, &self.revision);
+        }
+        if !self.pre_leet_username {
+            hasher.write_u8(0x87);
+        }
+        if !self.pre_leet_modifier {
+            hasher.write_u8(0x88);
+        }
+        if self.part_counter_separator != "\n" {
+            hasher.write_u8(0x89);
+            write_str(&mut hasher, &self.part_counter_separator);
+        }
+        match parameters.output_padding {
+            OutputPadding::None => {},
+            OutputPadding::LeadingZeros => hasher.write_u8(0x8a),
+        }
+        match self.assembly_settings.length_unit {
+            LengthUnit::GraphemeClusters => {},
+            LengthUnit::CodePoints => hasher.write_u8(0x8b),
+            LengthUnit::Utf16CodeUnits => hasher.write_u8(0x8c),
+        }
+        match parameters.input_encoding {
+            InputEncoding::Utf8 => {},
+            InputEncoding::Utf16DiscardHighByte => hasher.write_u8(0x8d),
+        }
+        if !self.context_tag.is_empty() {
+            hasher.write_u8(0x8e);
+            write_str(&mut hasher, &self.context_tag);
+        }
+        hasher.finish()
+    }
+}
+
+//Everything is length-prefixed, so that for instance prefix "ab" and suffix "c" don't collide with prefix "a" and suffix "bc".
+fn write_str(hasher : &mut Fnv1a64, s : &str) {
+    hasher.write_u64(s.len() as u64);
+    hasher.write(s.as_bytes());
+}
+
+fn write_leet_table(hasher : &mut Fnv1a64, table : Option<&LeetReplacementTable>) {
+    match table {
+        None => hasher.write_u8(0),
+        Some(table) => {
+            hasher.write_u8(1);
+            for replacement in table.replacements() {
+                write_str(hasher, replacement);
+            }
+        },
+    }
+}
+
+//Explicit numbers instead of discriminants, so reordering the enums doesn't change fingerprints.
+fn algorithm_id(algorithm : &AlgoSelection) -> u8 {
+    let modern_id = |a : &Algorithm| match a {
+        Algorithm::Md4 => 0,
+        Algorithm::Md5 => 1,
+        Algorithm::Sha1 => 2,
+        Algorithm::Sha256 => 3,
+        Algorithm::Ripemd160 => 4,
+        Algorithm::Ripemd128 => 5,
+        Algorithm::Tiger => 6,
+    };
+    match algorithm {
+        AlgoSelection::V06(V06HmacOrNot::NonHmac) => 0,
+        AlgoSelection::V06(V06HmacOrNot::Hmac) => 1,
+        AlgoSelection::Modern(HmacOrNot::NonHmac(a)) => 16 + modern_id(a),
+        AlgoSelection::Modern(HmacOrNot::Hmac(a)) => 32 + modern_id(a),
+    }
+}
+
+#[cfg(feature = "unicode-normalization")]
+fn normalization_form_id(form : NormalizationForm) -> u8 {
+    match form {
+        NormalizationForm::Nfc => 0,
+        NormalizationForm::Nfd => 1,
+        NormalizationForm::Nfkc => 2,
+        NormalizationForm::Nfkd => 3,
+    }
+}
+
+/// 64 bit FNV-1a. Not cryptographic, but stable across runs and platforms, unlike the hasher of `std::collections::HashMap`.
+struct Fnv1a64(u64);
+
+impl Default for Fnv1a64 {
+    fn default() -> Self {
+        Fnv1a64(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a64 {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes : &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    //The default implementations use native endianness. We want the same fingerprint everywhere.
+    fn write_u64(&mut self, i : u64) {
+        self.write(&i.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod fingerprint_tests {
+    use super::*;
+
+    /// Reference values from the FNV specification. If these change, all fingerprints change.
+    #[test]
+    fn fnv1a64_reference_values(){
+        let hash = |input : &[u8]| { let mut h = Fnv1a64::default(); h.write(input); h.finish() };
+        assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+}
@@ -1,4 +1,6 @@
+use std::borrow::Cow;
 use unicode_segmentation::UnicodeSegmentation;
+use crate::LengthUnit;
 #[derive(Clone)]
 pub(super) struct Grapheme<'a>(&'a str);
 
@@ -7,5 +9,115 @@ impl<'a> Grapheme<'a> {
         string.graphemes(true).map(Self::extract_grapheme_unchecked)
     }
     pub(super) fn get<'b>(&'b self) -> &'a str { self.0 }
-    fn extract_grapheme_unchecked(s : &str) -> Grapheme { Grapheme(s) }
-}
\ No newline at end of file
+    fn extract_grapheme_unchecked(s : &str) -> Grapheme<'_> { Grapheme(s) }
+    /// Splits the grapheme cluster into the pieces that truncation to `length_unit` must not cut.
+    pub(super) fn length_units(self, length_unit : LengthUnit) -> LengthUnits<'a> {
+        LengthUnits { rest : self.0, length_unit }
+    }
+}
+
+/// The pieces of a grapheme cluster, each with its length in a [`LengthUnit`]. For grapheme clusters that's the whole cluster,
+/// otherwise each code point is a piece of its own. The pieces are still [`Grapheme`]s, as they end up in the output just the same.
+pub(super) struct LengthUnits<'a> {
+    rest : &'a str,
+    length_unit : LengthUnit,
+}
+
+impl<'a> Iterator for LengthUnits<'a> {
+    type Item = (Grapheme<'a>, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let character = self.rest.chars().next()?;
+        let (piece_bytes, units) = match self.length_unit {
+            LengthUnit::GraphemeClusters => (self.rest.len(), 1),
+            LengthUnit::CodePoints => (character.len_utf8(), 1),
+            LengthUnit::Utf16CodeUnits => (character.len_utf8(), character.len_utf16()),
+        };
+        let (piece, rest) = self.rest.split_at(piece_bytes);
+        self.rest = rest;
+        Some((Grapheme(piece), units))
+    }
+}
+
+/// A string, split into grapheme clusters. Stores the end of each grapheme cluster instead of slices,
+/// so that the string itself can be owned.
+#[derive(Clone)]
+pub(crate) struct GraphemeList<'a> {
+    string : Cow<'a, str>,
+    ends : Cow<'a, [usize]>,
+    max_grapheme_len : usize,
+}
+
+impl<'a> GraphemeList<'a> {
+    pub(crate) fn new(string : Cow<'a, str>) -> Self {
+        let ends : Vec<_> = string.grapheme_indices(true).map(|(start, grapheme)| start + grapheme.len()).collect();
+        let max_grapheme_len = ends.iter().scan(0, |start, end| { let len = end - *start; *start = *end; Some(len) }).max().unwrap_or(0);
+        GraphemeList { string, ends : Cow::Owned(ends), max_grapheme_len }
+    }
+    /// Each non-empty symbol becomes exactly one entry, without being split into grapheme clusters.
+    pub(crate) fn from_symbols<S : AsRef<str>>(symbols : &[S]) -> GraphemeList<'static> {
+        let mut string = String::new();
+        let mut ends = Vec::with_capacity(symbols.len());
+        let mut max_grapheme_len = 0;
+        for symbol in symbols.iter().map(AsRef::as_ref).filter(|symbol| !symbol.is_empty()) {
+            string.push_str(symbol);
+            ends.push(string.len());
+            max_grapheme_len = max_grapheme_len.max(symbol.len());
+        }
+        GraphemeList { string : Cow::Owned(string), ends : Cow::Owned(ends), max_grapheme_len }
+    }
+    /// A `GraphemeList` that borrows from this one. Doesn't need to split the string into graphemes again.
+    pub(crate) fn borrow(&self) -> GraphemeList<'_> {
+        GraphemeList { string : Cow::Borrowed(&self.string), ends : Cow::Borrowed(&self.ends), max_grapheme_len : self.max_grapheme_len }
+    }
+    pub(crate) fn as_str(&self) -> &str { &self.string }
+    pub(crate) fn len(&self) -> usize { self.ends.len() }
+    /// The length in bytes of the longest grapheme cluster in the list.
+    pub(super) fn max_grapheme_len(&self) -> usize { self.max_grapheme_len }
+    pub(super) fn get(&self, index : usize) -> Option<Grapheme<'_>> {
+        let end = *self.ends.get(index)?;
+        let start = index.checked_sub(1).map_or(0, |previous| self.ends[previous]);
+        Some(Grapheme(&self.string[start..end]))
+    }
+    //Not segmenting the string again, as lists made from symbols might be split differently.
+    pub(super) fn iter(&self) -> impl Iterator<Item=Grapheme<'_>> {
+        (0..self.len()).filter_map(move |index| self.get(index))
+    }
+    pub(crate) fn symbols(&self) -> impl Iterator<Item=&str> {
+        self.iter().map(|grapheme| grapheme.get())
+    }
+}
+
+/// The grapheme ends of a string made of `N` single-byte characters: `[1, 2, ..., N]`.
+const fn single_byte_ends<const N : usize>() -> [usize; N] {
+    let mut ends = [0; N];
+    let mut i = 0;
+    while i < N {
+        ends[i] = i + 1;
+        i += 1;
+    }
+    ends
+}
+
+static HEX_DIGIT_ENDS : [usize; 16] = single_byte_ends();
+/// Lowercase hexadecimal digits. Used by the V0.6 algorithms, and by [`OutputEncoding::Hex`][crate::OutputEncoding::Hex].
+pub(super) static HEX_DIGITS : GraphemeList<'static> = GraphemeList {
+    string : Cow::Borrowed("0123456789abcdef"),
+    ends : Cow::Borrowed(&HEX_DIGIT_ENDS),
+    max_grapheme_len : 1,
+};
+
+static BASE64_DIGIT_ENDS : [usize; 64] = single_byte_ends();
+/// The URL-safe Base64 alphabet from RFC 4648. Used by [`OutputEncoding::Base64`][crate::OutputEncoding::Base64].
+pub(super) static BASE64_URL_DIGITS : GraphemeList<'static> = GraphemeList {
+    string : Cow::Borrowed("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"),
+    ends : Cow::Borrowed(&BASE64_DIGIT_ENDS),
+    max_grapheme_len : 1,
+};
+
+static BASE58_DIGIT_ENDS : [usize; 58] = single_byte_ends();
+/// The Base58 alphabet used by Bitcoin. Used by [`OutputEncoding::Base58`][crate::OutputEncoding::Base58].
+pub(super) static BASE58_DIGITS : GraphemeList<'static> = GraphemeList {
+    string : Cow::Borrowed("123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"),
+    ends : Cow::Borrowed(&BASE58_DIGIT_ENDS),
+    max_grapheme_len : 1,
+};
@@ -0,0 +1,49 @@
+//! The hash functions password generation is generic over. See [`HashFunctions`][crate::HashFunctions] for the public side.
+
+use crate::{Extended, ExtendedHasherList, Hasher, HasherList};
+
+/// The hash functions of each algorithm. Being in a private module, this cannot be named outside of this crate, what makes
+/// [`HashFunctions`][crate::HashFunctions] a sealed trait.
+pub trait HashFunctionsImpl {
+    type MD4 : Hasher;
+    type MD5 : Hasher;
+    type SHA1 : Hasher;
+    type SHA256 : Hasher;
+    type RIPEMD160 : Hasher;
+    type RIPEMD128 : Hasher;
+    type TIGER : Hasher;
+    /// If `false`, `RIPEMD128` and `TIGER` are [`Unavailable`], and must not be called.
+    const EXTENDED : bool;
+}
+
+impl<T : HasherList> HashFunctionsImpl for T {
+    type MD4 = T::MD4;
+    type MD5 = T::MD5;
+    type SHA1 = T::SHA1;
+    type SHA256 = T::SHA256;
+    type RIPEMD160 = T::RIPEMD160;
+    type RIPEMD128 = Unavailable<16>;
+    type TIGER = Unavailable<24>;
+    const EXTENDED : bool = false;
+}
+
+impl<L : ExtendedHasherList> HashFunctionsImpl for Extended<L> {
+    type MD4 = L::MD4;
+    type MD5 = L::MD5;
+    type SHA1 = L::SHA1;
+    type SHA256 = L::SHA256;
+    type RIPEMD160 = L::RIPEMD160;
+    type RIPEMD128 = L::RIPEMD128;
+    type TIGER = L::TIGER;
+    const EXTENDED : bool = true;
+}
+
+/// Stands in for the hash functions a plain [`HasherList`] does not have. Input validation rejects the algorithms that would need them.
+pub struct Unavailable<const N : usize>;
+
+impl<const N : usize> Hasher for Unavailable<N> {
+    type Output = [u8;N];
+    fn hash(_ : &[u8]) -> Self::Output {
+        unreachable!("Algorithms without hash function are rejected by input validation.")
+    }
+}
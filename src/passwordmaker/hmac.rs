@@ -1,23 +1,81 @@
 use crate::Hasher;
 
-pub(super) fn hmac<T, M>(key : &[u8], data : M) -> T::Output
+pub(super) fn hmac<T>(key : &[u8], data : &[u8]) -> T::Output
     where T : Hasher,
-    T::Output : AsRef<[u8]>,
-    M : Iterator<Item=u8>,
 {
+    //Caching the key hash (or the pads derived from it) between password parts does not work: With the default counter placement
+    //each part uses a different key (see PartInputs), and the counter is appended to the key before it gets hashed. Since Hasher does not expose
+    //the state of the hash function, there is no intermediate state we could keep around either.
     //Sorry for this uglyness. key_hash is an Option because we don't want to compute it if we don't need it, but
     //we also want to be able to reference it in case it's needed.
-    let key_hash = if key.len() > 64 { Some(T::hash(&key)) } else { None };
-    let key = key_hash.as_ref().map(T::Output::as_ref).map(<&[u8]>::into_iter)
-        .unwrap_or_else(|| (&key).into_iter()).copied();
+    let key_hash = if key.len() > 64 { Some(T::hash(key)) } else { None };
+    let key = key_hash.as_ref().map_or(key, T::Output::as_ref);
 
-    let key = key
-        .chain(std::iter::repeat(0)) //if key[i] does not exist, use 0 instead.
-        .take(64); //and the pads have 64 bytes
+    //if key[i] does not exist, use 0 instead. The pads have 64 bytes.
+    let mut inner_pad = [0x36; 64];
+    let mut outer_pad = [0x5C; 64];
+    for ((i, o), k) in inner_pad.iter_mut().zip(outer_pad.iter_mut()).zip(key) {
+        *i ^= k;
+        *o ^= k;
+    }
 
-    let inner_pad = key.clone().map(|k| k ^ 0x36);
-    let outer_pad = key.map(|k| k ^ 0x5C);
+    //Passed as chunks, so hashers that support incremental hashing don't need to copy the data.
+    let hash = T::hash_chunks([&inner_pad[..], data].iter().copied());
+    T::hash_chunks([&outer_pad[..], hash.as_ref()].iter().copied())
+}
 
-    let hash = T::hash(&inner_pad.chain(data).collect::<Vec<_>>());
-    T::hash(&outer_pad.chain(hash.as_ref().iter().copied()).collect::<Vec<_>>())
-}
\ No newline at end of file
+#[cfg(test)]
+mod hmac_tests {
+    use super::*;
+    use std::cell::RefCell;
+    use digest::Digest;
+
+    struct Md5;
+    impl Hasher for Md5 {
+        type Output = [u8;16];
+        fn hash(input : &[u8]) -> Self::Output {
+            md5::Md5::digest(input).into()
+        }
+    }
+
+    thread_local! {
+        static CHUNK_LENGTHS : RefCell<Vec<Vec<usize>>> = RefCell::new(Vec::new());
+    }
+
+    /// Hashes incrementally, and records the length of each chunk it gets.
+    struct StreamingMd5;
+    impl Hasher for StreamingMd5 {
+        type Output = [u8;16];
+        fn hash(input : &[u8]) -> Self::Output {
+            Md5::hash(input)
+        }
+        fn hash_chunks<'a, I : Iterator<Item=&'a [u8]>>(chunks : I) -> Self::Output {
+            let mut hasher = md5::Md5::new();
+            let mut lengths = Vec::new();
+            for chunk in chunks {
+                lengths.push(chunk.len());
+                hasher.update(chunk);
+            }
+            CHUNK_LENGTHS.with(|l| l.borrow_mut().push(lengths));
+            hasher.finalize().into()
+        }
+    }
+
+    #[test]
+    fn hmac_passes_pad_and_data_as_chunks(){
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let streamed = hmac::<StreamingMd5>(b"key", data);
+        assert_eq!(streamed, hmac::<Md5>(b"key", data));
+        //RFC 2104 style reference value for HMAC-MD5.
+        assert_eq!(streamed, [0x80,0x07,0x07,0x13,0x46,0x3e,0x77,0x49,0xb9,0x0c,0x2d,0xc2,0x49,0x11,0xe2,0x75]);
+        CHUNK_LENGTHS.with(|l| assert_eq!(*l.borrow(), vec![vec![64, data.len()], vec![64, 16]]));
+    }
+
+    #[test]
+    fn hmac_long_key(){
+        let key = [0xaa; 80];
+        let data = b"Test Using Larger Than Block-Size Key - Hash Key First";
+        //From RFC 2202.
+        assert_eq!(hmac::<StreamingMd5>(&key, data), [0x6b,0x1a,0xb7,0xfe,0x4b,0xd7,0xbf,0x8f,0x0b,0x62,0xe6,0xce,0x61,0xb9,0xd0,0xcd]);
+    }
+}
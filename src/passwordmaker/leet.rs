@@ -1,7 +1,9 @@
 use crate::LeetLevel;
 
+#[derive(Clone)]
 pub(crate) struct LeetReplacementTable{
     lookup_table : &'static [&'static str; 26],
+    level : LeetLevel,
 }
 
 enum CharOrSlice{
@@ -13,6 +15,7 @@ impl LeetReplacementTable {
     /// Gets the appropriate leet replacement table for a given leet level.
     pub(crate) fn get(leet_level : LeetLevel) -> LeetReplacementTable {
         let lookup_table = match leet_level {
+            LeetLevel::Zero => &["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z"],
             LeetLevel::One => &["4", "b", "c", "d", "3", "f", "g", "h", "i", "j", "k", "1", "m", "n", "0", "p", "9", "r", "s", "7", "u", "v", "w", "x", "y", "z"],
             LeetLevel::Two => &["4", "b", "c", "d", "3", "f", "g", "h", "1", "j", "k", "1", "m", "n", "0", "p", "9", "r", "5", "7", "u", "v", "w", "x", "y", "2"],
             LeetLevel::Three => &["4", "8", "c", "d", "3", "f", "6", "h", "'", "j", "k", "1", "m", "n", "0", "p", "9", "r", "5", "7", "u", "v", "w", "x", "'/", "2"],
@@ -23,24 +26,38 @@ impl LeetReplacementTable {
             LeetLevel::Eight => &["@", "8", "(", "|)", "&", "|=", "6", "|-|", "!", "_|", "|(", "1", "|\\/|", "|\\|", "()", "|>", "(,)", "|2", "$", "|", "|_|", "\\/", "\\^/", ")(", "'/", "\"/_"],
             LeetLevel::Nine => &["@", "8", "(", "|)", "&", "|=", "6", "|-|", "!", "_|", "|{", "|_", "/\\/\\", "|\\|", "()", "|>", "(,)", "|2", "$", "|", "|_|", "\\/", "\\^/", ")(", "'/", "\"/_"],
         };
-        LeetReplacementTable { lookup_table }
+        LeetReplacementTable { lookup_table, level : leet_level }
+    }
+
+    /// The leet level this table was created for.
+    pub(crate) fn level(&self) -> LeetLevel {
+        self.level
+    }
+
+    /// The replacements for the letters a to z, in that order.
+    pub(crate) fn replacements(&self) -> &'static [&'static str; 26] {
+        self.lookup_table
     }
 
     /// Applies this replacement table to an input string slice.
     /// Needs an intermediate allocation.
     pub(super) fn leetify(&self, input: &str) -> String{
+        let mut result = String::with_capacity(input.len());
+        self.leetify_into(input, &mut result);
+        result
+    }
+
+    /// Same as [`leetify`][LeetReplacementTable::leetify], but appends the result to `output`, to save an allocation.
+    pub(super) fn leetify_into(&self, input: &str, output : &mut String){
         //PasswordMaker Pro is converting input to lower-case before leet is applied.
         //We must apply to_lowercase on the whole input. PasswordMaker Pro is properly treating Final_Sigma, what we cannot do if we just
         //iterate on a per-char basis.
-        input.to_lowercase().chars()
-            .map(|c| self.conditionally_replace(c))
-            .fold(String::with_capacity(input.len()), |mut result, c| {
-                match c {
-                    CharOrSlice::Char(c) => result.push(c),
-                    CharOrSlice::Slice(s) => result.push_str(s),
-                };
-                result
-            })
+        for c in input.to_lowercase().chars().map(|c| self.conditionally_replace(c)) {
+            match c {
+                CharOrSlice::Char(c) => output.push(c),
+                CharOrSlice::Slice(s) => output.push_str(s),
+            }
+        }
     }
 
     fn conditionally_replace(&self, character : char) -> CharOrSlice {
@@ -60,6 +77,7 @@ mod leet_tests{
     }
     fn get_icelandic_test_result(level : LeetLevel) -> &'static str {
         match level {
+            LeetLevel::Zero => "kæmi ný öxi hér, ykist þjófum nú bæði víl og ádrepa.",
             LeetLevel::One => "kæmi ný öxi hér, ykis7 þjófum nú bæði ví1 0g ádr3p4.",
             LeetLevel::Two => "kæm1 ný öx1 hér, yk157 þjófum nú bæð1 ví1 0g ádr3p4.",
             LeetLevel::Three => "kæm' ný öx' hér, '/k'57 þjófum nú 8æð' ví1 06 ádr3p4.",
@@ -97,4 +115,15 @@ mod leet_tests{
             assert_eq!(result, expected);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn replacement_table_matches_leetify(){
+        assert_eq!(LeetLevel::One.replacement_table()[..4], ["4", "b", "c", "d"]);
+        for leet_level in LeetLevel::iter(){
+            let table = leet_level.replacement_table();
+            for (letter, replacement) in ('a'..='z').zip(table.iter()) {
+                assert_eq!(LeetReplacementTable::get(leet_level).leetify(&letter.to_string()), *replacement);
+            }
+        }
+    }
+}
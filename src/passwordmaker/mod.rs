@@ -1,74 +1,325 @@
-use std::iter::SkipWhile;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::iter::{Chain, Repeat, SkipWhile, Take};
 
 use unicode_segmentation::UnicodeSegmentation;
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
 use leet::LeetReplacementTable;
-use grapheme::Grapheme;
+use grapheme::{Grapheme, GraphemeList, BASE58_DIGITS, BASE64_URL_DIGITS, HEX_DIGITS};
 
-use base_conversion::BaseConversion;
+use base_conversion::{BaseConversion, u128_leading_digits};
+use trace::{PasswordPartObserver, NoTrace, Tracer};
+pub(super) use base_conversion::is_base_precomputed;
+pub(super) use fallible::catch_hasher_error;
+pub(super) use hash_functions::HashFunctionsImpl;
 
-use self::base_conversion::{IterativeBaseConversion, SixteenBytes, ArbitraryBytes};
-
-use super::Hasher;
+use super::{Hasher, InputEncoding, LengthUnit, OutputEncoding, OutputPadding, PartCounterPlacement};
 
 mod base_conversion;
-mod grapheme;
+mod debug;
+mod fallible;
+mod fingerprint;
+pub(crate) mod grapheme;
+mod hash_functions;
 mod hmac;
 pub(crate) mod leet;
+mod trace;
 
-impl<'y, H : super::HasherList> super::PasswordMaker<'y, H>{
-    pub(super) fn is_suitable_as_output_characters(characters : &str) -> bool {
-        characters.graphemes(true).nth(1).is_some()
+#[cfg(feature = "unicode-normalization")]
+impl super::NormalizationForm {
+    pub(super) fn normalize(self, input : &str) -> String {
+        use super::NormalizationForm;
+        match self {
+            NormalizationForm::Nfc => input.nfc().collect(),
+            NormalizationForm::Nfd => input.nfd().collect(),
+            NormalizationForm::Nfkc => input.nfkc().collect(),
+            NormalizationForm::Nfkd => input.nfkd().collect(),
+        }
     }
+}
 
-    pub(super) fn generate_password_verified_input(&self, data : String, key : String) -> String {
-        let modified_data = data + self.username + self.modifier;
-        let get_modified_key = move |i : usize| { if i == 0 {key.clone()} else {key.clone() + "\n" + &i.to_string()}};
-    
+pub(super) fn validate_output_characters(characters : &str) -> Result<(), super::SettingsError> {
+    //No need to count further than 2. Charsets can be long.
+    let found = characters.graphemes(true).take(2).count();
+    if found < 2 {
+        Err(super::SettingsError::InsufficientCharset { found })
+    } else {
+        Ok(())
+    }
+}
+
+pub(super) fn split_into_graphemes(characters : &str) -> impl Iterator<Item=&str> {
+    Grapheme::iter_from_str(characters).map(|grapheme| grapheme.get())
+}
+
+pub(super) fn validate_output_symbols(symbols : &GraphemeList) -> Result<(), super::SettingsError> {
+    let found = symbols.len();
+    if found < 2 {
+        Err(super::SettingsError::InsufficientCharset { found })
+    } else {
+        Ok(())
+    }
+}
+
+//Nothing is hashed here, so these work for any hash functions, including Fallible ones.
+impl<H> super::PasswordMaker<'_, H>{
+    pub(super) fn estimate_parts(&self) -> usize {
+        let needed_password_length = self.assembly_settings.needed_password_length();
+        let digits_per_part = self.password_part_parameters.max_digits_per_part();
+        needed_password_length / digits_per_part + usize::from(needed_password_length % digits_per_part != 0)
+    }
+
+    pub(super) fn count_possible_passwords(&self) -> Option<u128> {
+        let base = u128::try_from(self.password_part_parameters.base()).ok()?;
+        //An exponent that does not fit into u32 overflows anyhow, as the base is at least 2.
+        let exponent = u32::try_from(self.assembly_settings.needed_password_length()).ok()?;
+        base.checked_pow(exponent)
+    }
+}
+
+impl<H : HashFunctionsImpl> super::PasswordMaker<'_, H>{
+    pub(super) fn generate_password_verified_input(&self, data : String, key : String, first_part_index : usize) -> Result<String, super::GenerationError> {
+        if first_part_index == 0 && self.post_leet.is_none() && self.estimate_parts() == 1 {
+            let inputs = self.part_inputs(data, key);
+            if let Some(password) = self.generate_single_part_password(&inputs) {
+                return password;
+            }
+            return self.generate_password_from_inputs(inputs, 0, &NoTrace);
+        }
+        self.generate_password_verified_input_observed(data, key, first_part_index, &NoTrace)
+    }
+
+    /// Fast path for the most common case, a short password that fits into a single hash.
+    /// Returns `None` if the hash has so many leading zeros that it doesn't yield enough characters. The caller then needs to use the
+    /// general path, which appends more password parts.
+    fn generate_single_part_password(&self, inputs : &PartInputs) -> Option<Result<String, super::GenerationError>> {
+        let parameters = &self.password_part_parameters;
+        let (data, key) = inputs.get(0);
+        //Non-HMAC algorithms append data to key. Reserving the space up front saves a reallocation.
+        let mut key_buffer = String::with_capacity(key.len() + data.len());
+        key_buffer.push_str(&key);
+        let hash = Self::hash_password_part(0, &data, key_buffer, parameters, inputs.pre_leet_level(parameters), &NoTrace);
+        let characters = parameters.output_characters();
+        let needed_password_length = self.assembly_settings.needed_password_length();
+        let max_password_bytes = needed_password_length.saturating_mul(characters.max_grapheme_len());
+        let mut count = 0;
+        let result = if let Some(digits) = hash.leading_digits(characters.len(), parameters.output_encoding, parameters.output_padding, needed_password_length) {
+            let password = digits.filter_map(|digit| characters.get(digit)).inspect(|_| count += 1);
+            combine_prefix_password_suffix(password, &self.assembly_settings, max_password_bytes, self.max_output_bytes)
+        } else {
+            let password = hash.to_graphemes(characters, parameters.output_encoding, parameters.output_padding, &NoTrace).inspect(|_| count += 1);
+            combine_prefix_password_suffix(password, &self.assembly_settings, max_password_bytes, self.max_output_bytes)
+        };
+        //An error is final. With more password parts the output would only get larger.
+        if count >= needed_password_length || result.is_err() { Some(result) } else { None }
+    }
+
+    pub(super) fn generate_password_verified_input_traced(&self, data : String, key : String) -> Result<(String, super::GenerationTrace), super::GenerationError> {
+        let tracer = Tracer::default();
+        let password = self.generate_password_verified_input_observed(data, key, 0, &tracer)?;
+        Ok((password, tracer.into_trace()))
+    }
+
+    pub(super) fn trace_password_part_verified_input(&self, data : String, key : String, part_index : usize) -> super::PasswordPartTrace {
+        let tracer = Tracer::default();
+        let inputs = self.part_inputs(data, key);
+        let (data, key) = inputs.get(part_index);
+        //The hash is recorded when the part is converted to graphemes. The graphemes themselves are not needed.
+        let _ = Self::generate_password_part(part_index, &data, key.into_owned(), &self.password_part_parameters, inputs.pre_leet_level(&self.password_part_parameters), &tracer);
+        tracer.into_trace().parts.pop().expect("Generating a password part records exactly one trace entry.")
+    }
+
+    fn generate_password_verified_input_observed<O : PasswordPartObserver>(&self, data : String, key : String, first_part_index : usize, observer : &O) -> Result<String, super::GenerationError> {
+        self.generate_password_from_inputs(self.part_inputs(data, key), first_part_index, observer)
+    }
+
+    fn generate_password_from_inputs<O : PasswordPartObserver>(&self, inputs : PartInputs<'_>, first_part_index : usize, observer : &O) -> Result<String, super::GenerationError> {
+        let get_part = move |i : usize| {
+            let (data, key) = inputs.get(i + first_part_index);
+            Self::generate_password_part(i + first_part_index, &data, key.into_owned(), &self.password_part_parameters, inputs.pre_leet_level(&self.password_part_parameters), observer)
+        };
+        self.assemble_password(get_part, &self.assembly_settings, self.password_part_parameters.output_characters().max_grapheme_len())
+    }
+
+    pub(super) fn generate_batch_verified_input(&self, data : String, key : String, specs : &[super::OutputSpec]) -> Result<Vec<String>, super::GenerationError> {
+        let inputs = self.part_inputs(data, key);
+        //The hashes do not depend on the output characters, so each one is computed once, when the first spec needs it.
+        let hashes = RefCell::new(Vec::new());
+        let get_hash = move |i : usize| {
+            let mut hashes = hashes.borrow_mut();
+            while hashes.len() <= i {
+                let (data, key) = inputs.get(hashes.len());
+                let hash = Self::hash_password_part(hashes.len(), &data, key.into_owned(), &self.password_part_parameters, inputs.pre_leet_level(&self.password_part_parameters), &NoTrace);
+                hashes.push(hash);
+            }
+            hashes[i].clone()
+        };
+        specs.iter().map(|spec| {
+            let parameters = &self.password_part_parameters;
+            let characters = if parameters.uses_characters() {
+                GraphemeList::new(Cow::Borrowed(spec.characters))
+            } else {
+                parameters.output_characters().clone()
+            };
+            let assembly_settings = PasswordAssemblyParameters::from_public_parameters(Cow::Borrowed(spec.prefix), Cow::Borrowed(spec.suffix), spec.password_length)
+                .with_length_unit(self.assembly_settings.length_unit);
+            let get_part = |i : usize| get_hash(i).to_graphemes(&characters, parameters.output_encoding, parameters.output_padding, &NoTrace);
+            self.assemble_password(get_part, &assembly_settings, characters.max_grapheme_len())
+        }).collect()
+    }
+
+    pub(super) fn generate_indices_verified_input(&self, data : String, key : String) -> Vec<usize> {
+        let inputs = self.part_inputs(data, key);
+        let needed_password_length = self.assembly_settings.needed_password_length();
+        (0..)
+            .flat_map(|i| {
+                let (data, key) = inputs.get(i);
+                Self::generate_password_part(i, &data, key.into_owned(), &self.password_part_parameters, inputs.pre_leet_level(&self.password_part_parameters), &NoTrace).into_indices()
+            })
+            .take(needed_password_length)
+            .collect()
+    }
+
+    pub(super) fn generate_bytes_verified_input(&self, data : String, key : String) -> Result<Vec<u8>, super::GenerationError> {
+        let characters = self.password_part_parameters.output_characters();
+        let assembly_settings = &self.assembly_settings;
+        //Single-byte graphemes are ASCII, so each index maps to exactly one byte. Affixes and leet need the string path.
+        if characters.max_grapheme_len() != 1 || self.post_leet.is_some() || !assembly_settings.prefix.is_empty() || !assembly_settings.suffix.is_empty() {
+            return self.generate_password_verified_input(data, key, 0).map(String::into_bytes);
+        }
+        if self.max_output_bytes.map_or(false, |max| assembly_settings.password_length > max) {
+            return Err(super::GenerationError::OutputTooLarge);
+        }
+        let alphabet : Vec<u8> = characters.iter().map(|grapheme| grapheme.get().as_bytes()[0]).collect();
+        Ok(self.generate_indices_verified_input(data, key).into_iter().map(|index| alphabet[index]).collect())
+    }
+
+    /// Yields the same graphemes as [`generate_password_verified_input`][Self::generate_password_verified_input], but only computes the
+    /// password parts when they are needed. Does not enforce `max_output_bytes`, as the password is never stored as a whole.
+    pub(super) fn generate_graphemes_verified_input(&self, data : String, key : String) -> Box<dyn Iterator<Item=Cow<'_, str>> + '_> {
+        let inputs = self.part_inputs(data, key);
+        let parameters = &self.password_part_parameters;
+        let get_part = move |i : usize| {
+            let (data, key) = inputs.get(i);
+            Self::generate_password_part(i, &data, key.into_owned(), parameters, inputs.pre_leet_level(parameters), &NoTrace)
+        };
+        match &self.post_leet {
+            None => Box::new(prefix_password_suffix((0..).flat_map(get_part), &self.assembly_settings).map(|grapheme| Cow::Borrowed(grapheme.get()))),
+            Some(leet_level) => {
+                //Leet after generation works on whole password parts, see assemble_password. So the password is computed up front.
+                let password = Self::generate_password_verified_with_post_leet(&get_part, &self.assembly_settings, leet_level, self.post_leet_fallback, None)
+                    .unwrap_or_else(|| Self::generate_password_verified_no_post_leet(&get_part, &self.assembly_settings, parameters.output_characters().max_grapheme_len(), None))
+                    .expect("Without max_output_bytes generation cannot fail.");
+                let mut offset = 0;
+                Box::new(std::iter::from_fn(move || {
+                    let grapheme = password[offset..].graphemes(true).next()?;
+                    offset += grapheme.len();
+                    Some(Cow::Owned(grapheme.to_owned()))
+                }))
+            },
+        }
+    }
+
+    fn part_inputs(&self, data : String, key : String) -> PartInputs<'_> {
+        //"\n@" instead of just "\n", so that revision "1" doesn't yield the second password part of revision "".
+        let key = if self.revision.is_empty() { key } else { key + "\n@" + &self.revision };
+        //"\n#" for the same reason, as the part counter can be placed in the modifier.
+        let context_tag = if self.context_tag.is_empty() { String::new() } else { String::from("\n#") + &self.context_tag };
+        match &self.password_part_parameters.pre_leet_level {
+            //If username or modifier are excluded from leet, each input is leeted on its own. Leet lower-cases its input, which
+            //depends on context (word-final sigma), so this can differ from leeting the concatenation, even for leeted parts.
+            Some(leet) if !(self.pre_leet_username && self.pre_leet_modifier) => {
+                let leet_if = |apply : bool, input : &str| if apply { leet.leetify(input) } else { input.to_owned() };
+                let data = leet.leetify(&data);
+                PartInputs {
+                    data_length : data.len(),
+                    data : data + &leet_if(self.pre_leet_username, &self.username) + &leet_if(self.pre_leet_modifier, &self.modifier) + &leet.leetify(&context_tag),
+                    key : leet.leetify(&key),
+                    placement : self.part_counter_placement,
+                    //The counter is appended later, so its separator needs to be leeted here too. Digits are not changed by leet.
+                    separator : Cow::Owned(leet.leetify(&self.part_counter_separator)),
+                    leet_applied : true,
+                }
+            },
+            _ => PartInputs {
+                data_length : data.len(),
+                data : data + &self.username + &self.modifier + &context_tag,
+                key,
+                placement : self.part_counter_placement,
+                separator : Cow::Borrowed(&self.part_counter_separator),
+                leet_applied : false,
+            },
+        }
+    }
+
+    /// `max_grapheme_len` is the length in bytes of the longest grapheme the password parts can contain. Used to pre-allocate the output.
+    fn assemble_password<'b, G : Fn(usize)->GetGraphemesIterator<'b>>(&self, get_part : G, assembly_settings : &'b PasswordAssemblyParameters<'_>, max_grapheme_len : usize) -> Result<String, super::GenerationError> {
         //In Passwordmaker Pro, leet is applied on a per-password-part basis. This means that if a password part ends in an upper-case Sigma,
         //the results would differ if we moved leeting to after all password parts were joined, or worse, did it on a per-character level.
         //However, this makes the code a lot more complex, as it forces us to create an owned string for each password part before combining.
         //Therefore, we treat that case special.
         match &self.post_leet {
-            None => Self::generate_password_verified_no_post_leet(&modified_data, get_modified_key, &self.assembly_settings, &self.password_part_parameters),
-            Some(leet_level) => Self::generate_password_verified_with_post_leet(&modified_data, get_modified_key,&self.assembly_settings , &self.password_part_parameters, leet_level),
+            None => Self::generate_password_verified_no_post_leet(get_part, assembly_settings, max_grapheme_len, self.max_output_bytes),
+            Some(leet_level) => Self::generate_password_verified_with_post_leet(&get_part, assembly_settings, leet_level, self.post_leet_fallback, self.max_output_bytes)
+                .unwrap_or_else(|| Self::generate_password_verified_no_post_leet(get_part, assembly_settings, max_grapheme_len, self.max_output_bytes)),
         }
     }
 
-    fn generate_password_verified_no_post_leet<G : Fn(usize)->String>(modified_data : &str, get_modified_key : G, assembly_settings : &PasswordAssemblyParameters, password_part_parameters : &PasswordPartParameters) -> String {
-        let password = (0..).flat_map(|i| Self::generate_password_part(modified_data, get_modified_key(i), password_part_parameters));
-        combine_prefix_password_suffix(password, assembly_settings)
+    fn generate_password_verified_no_post_leet<'b, G : Fn(usize)->GetGraphemesIterator<'b>>(get_part : G, assembly_settings : &'b PasswordAssemblyParameters<'_>, max_grapheme_len : usize, max_output_bytes : Option<usize>) -> Result<String, super::GenerationError> {
+        let password = (0..).flat_map(get_part);
+        let max_password_bytes = assembly_settings.needed_password_length().saturating_mul(max_grapheme_len);
+        combine_prefix_password_suffix(password, assembly_settings, max_password_bytes, max_output_bytes)
     }
 
     
-    fn generate_password_verified_with_post_leet<G : Fn(usize)->String>(modified_data : &str, get_modified_key : G, assembly_settings : &PasswordAssemblyParameters, password_part_parameters : &PasswordPartParameters, post_leet : &LeetReplacementTable) -> String {
-        let suffix_length = assembly_settings.suffix_length;
-        let prefix_length = assembly_settings.prefix_length;
-        let needed_password_length = assembly_settings.password_length.saturating_sub(suffix_length).saturating_sub(prefix_length);
-    
-        //Helper function that is used in try_fold below. Appends string part p to the input string, and counts graphemes.
-        //Once grapheme count in total is >= needed_password_length, it returns a ControlFlow::Break.
-        //Or, wait. Our target platform is limited to Rust 1.52 for now, so it's a Result::Err once the required length is reached.
-        let append_strings_till_needed_length = |s: (String, usize),p : String| {
-            let new_length = s.1 + p.graphemes(true).count();
-            let st = s.0 + &p;
-            if new_length >= needed_password_length  {
-                Err(st)
-            } else {
-                Ok((st, new_length))
-            }
-       };
-    
+    /// Returns `None` if `fall_back_if_expanded` is set, and leet more than doubled the length of the used password parts.
+    fn generate_password_verified_with_post_leet<'b, G : Fn(usize)->GetGraphemesIterator<'b>>(get_part : G, assembly_settings : &'b PasswordAssemblyParameters<'_>, post_leet : &LeetReplacementTable, fall_back_if_expanded : bool, max_output_bytes : Option<usize>) -> Option<Result<String, super::GenerationError>> {
+        let needed_password_length = assembly_settings.needed_password_length();
+
         //here we have to work on a string level... Because word-final sigma and leet's ToLower...
-        let password = (0..)
-            .map(|i| Self::generate_password_part(modified_data, get_modified_key(i), password_part_parameters))
-            .map(|i| i.map(|g| g.get()).collect::<String>()) //make string from password part...
-            .map(|non_leeted_password| post_leet.leetify(&non_leeted_password)) //leet it
-            .try_fold((String::new(), 0), append_strings_till_needed_length).unwrap_err();
-    
-        combine_prefix_password_suffix(Grapheme::iter_from_str(&password), assembly_settings)
+        //Each part is leeted on its own, but directly into the password, and only the graphemes of the appended part are counted.
+        //Not bounded by needed_password_length, because the full length of the used parts is needed for the fallback check.
+        let mut password = String::new();
+        let mut part = String::new();
+        let mut length = 0;
+        //Counts the graphemes before leet was applied.
+        let mut non_leeted_length = 0;
+        for graphemes in (0..).map(get_part) {
+            part.clear();
+            for grapheme in graphemes {
+                part.push_str(grapheme.get());
+                non_leeted_length += 1;
+            }
+            let part_start = password.len();
+            post_leet.leetify_into(&part, &mut password);
+            length += password[part_start..].graphemes(true).count();
+            if length >= needed_password_length {
+                break;
+            }
+        }
+
+        if fall_back_if_expanded && length > 2 * non_leeted_length {
+            return None;
+        }
+        Some(combine_prefix_password_suffix(Grapheme::iter_from_str(&password), assembly_settings, password.len(), max_output_bytes))
+    }
+
+    fn generate_password_part<'a, O : PasswordPartObserver>(part_index : usize, data : &str, key : String, parameters : &'a PasswordPartParameters<'a>, pre_leet_level : Option<&LeetReplacementTable>, observer : &O) -> GetGraphemesIterator<'a> {
+        Self::hash_password_part(part_index, data, key, parameters, pre_leet_level, observer).to_graphemes(parameters.output_characters(), parameters.output_encoding, parameters.output_padding, observer)
     }
 
-    fn generate_password_part<'a>(data : &str, key : String, parameters : &'a PasswordPartParameters<'a>) -> GetGraphemesIterator<'a> {
+    /// `pre_leet_level` is passed separately, because [`PartInputs`] might already have applied it.
+    /// `part_index` is only used for tracing. The part counter is already contained in `data` or `key`.
+    /// All paths that generate password parts hash them here, so this is where the per-part span is.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all, fields(
+        part = part_index,
+        algorithm = ?parameters.hash_algorithm(),
+        base = parameters.base(),
+    )))]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn hash_password_part<O : PasswordPartObserver>(part_index : usize, data : &str, key : String, parameters : &PasswordPartParameters, pre_leet_level : Option<&LeetReplacementTable>, observer : &O) -> PasswordPartHash {
         //Must follow PasswordMaker Pro closely here. For instance:
         // leet(key) + leet(data) != leet(key+data)
         //Soo, easiest way is to just make a _different_ function for each different combination of operations.
@@ -76,129 +327,288 @@ impl<'y, H : super::HasherList> super::PasswordMaker<'y, H>{
         
         match &parameters.hash_algorithm{
             AlgoSelection::V06(V06HmacOrNot::Hmac) => 
-                Self::generate_password_part_v06_hmac(data, key, &parameters.pre_leet_level, &parameters.characters),
+                Self::hash_password_part_v06_hmac(data, key, pre_leet_level, observer),
             AlgoSelection::V06(V06HmacOrNot::NonHmac) => 
-                Self::generate_password_part_v06(data, key, &parameters.pre_leet_level, &parameters.characters),
+                Self::hash_password_part_v06(data, key, pre_leet_level, observer),
             AlgoSelection::Modern(HmacOrNot::Hmac(a)) => 
-                Self::generate_password_part_modern_hmac(data, key, a, &parameters.pre_leet_level, &parameters.characters),
+                Self::hash_password_part_modern_hmac(data, key, a, pre_leet_level, observer),
             AlgoSelection::Modern(HmacOrNot::NonHmac(a)) => 
-                Self::generate_password_part_modern(data, key, a, &parameters.pre_leet_level, &parameters.characters),
+                Self::hash_password_part_modern(data, key, a, parameters.input_encoding, pre_leet_level, observer),
         }
     }
 
-    fn generate_password_part_v06<'a>(
+    fn hash_password_part_v06<O : PasswordPartObserver>(
         second_part : &str,
         message : String,
-        pre_leet_level: &Option<LeetReplacementTable>,
-        characters : &'a Vec<Grapheme<'a>>,
-    ) -> GetGraphemesIterator<'a> {
+        pre_leet_level: Option<&LeetReplacementTable>,
+        observer : &O,
+    ) -> PasswordPartHash {
         let message = message + second_part;
-        let message = pre_leet_level.as_ref().map(|l| l.leetify(&message)).unwrap_or(message);
+        let message = pre_leet_level.map(|l| l.leetify(&message)).unwrap_or(message);
         let message = yeet_upper_bytes(&message).collect::<Vec<u8>>();
-        let hash = H::MD5::hash(&message);
-        let grapheme_indices = hash.convert_to_base(characters.len());
-        GetGraphemesIterator { graphemes : characters, inner: GetGraphemesIteratorInner::V06(grapheme_indices)}
+        observer.hasher_input(None, message.iter().copied());
+        PasswordPartHash::V06(digest_bytes::<H::MD5,16>(&H::MD5::hash(&message)))
     }
 
     
-    fn generate_password_part_v06_hmac<'a>(
+    fn hash_password_part_v06_hmac<O : PasswordPartObserver>(
         data : &str,
         key : String,
-        pre_leet_level: &Option<LeetReplacementTable>,
-        characters : &'a Vec<Grapheme<'a>>,
-    ) -> GetGraphemesIterator<'a>  {
-        let key = pre_leet_level.as_ref().map(|l| l.leetify(&key)).unwrap_or(key);
-        let leetified_data = pre_leet_level.as_ref().map(|l| l.leetify(data));
+        pre_leet_level: Option<&LeetReplacementTable>,
+        observer : &O,
+    ) -> PasswordPartHash {
+        let key = pre_leet_level.map(|l| l.leetify(&key)).unwrap_or(key);
+        let leetified_data = pre_leet_level.map(|l| l.leetify(data));
         let data = leetified_data.as_deref().unwrap_or(data);
-        let key = yeet_upper_bytes(&key);
-        let data = yeet_upper_bytes(data);
-        let hash = hmac::hmac::<H::MD5,_>(&key.collect::<Vec<_>>(), data);
-        let grapheme_indices = hash.convert_to_base(characters.len());
-        GetGraphemesIterator { graphemes : characters, inner: GetGraphemesIteratorInner::V06(grapheme_indices)}
+        let key = yeet_upper_bytes(&key).collect::<Vec<_>>();
+        let data = yeet_upper_bytes(data).collect::<Vec<_>>();
+        observer.hasher_input(Some(&key), data.iter().copied());
+        PasswordPartHash::V06(digest_bytes::<H::MD5,16>(&hmac::hmac::<H::MD5>(&key, &data)))
     }
     
-    fn generate_password_part_modern_hmac<'a>(
+    fn hash_password_part_modern_hmac<O : PasswordPartObserver>(
         data : &str,
         key : String,
         algo : &Algorithm,
-        pre_leet_level: &Option<LeetReplacementTable>,
-        characters : &'a Vec<Grapheme<'a>>,
-    ) -> GetGraphemesIterator<'a>  {
-        let key = pre_leet_level.as_ref().map(|l| l.leetify(&key)).unwrap_or(key);
-        let leetified_data = pre_leet_level.as_ref().map(|l| l.leetify(data));
+        pre_leet_level: Option<&LeetReplacementTable>,
+        observer : &O,
+    ) -> PasswordPartHash {
+        let key = pre_leet_level.map(|l| l.leetify(&key)).unwrap_or(key);
+        let leetified_data = pre_leet_level.map(|l| l.leetify(data));
         let data = leetified_data.as_deref().unwrap_or(data);
-        let grapheme_indices = match algo {
-            Algorithm::Md4 => 
-                GetGraphemesIteratorInner::Modern16(modern_hmac_to_grapheme_indices::<H::MD4>(&key, data, characters.len()).skip_while(is_zero)),
-            Algorithm::Md5 => 
-                GetGraphemesIteratorInner::Modern16(modern_hmac_to_grapheme_indices::<H::MD5>(&key, data, characters.len()).skip_while(is_zero)),
-            Algorithm::Sha1 => 
-                GetGraphemesIteratorInner::Modern20(modern_hmac_to_grapheme_indices::<H::SHA1>(&key, data, characters.len()).skip_while(is_zero)),
-            Algorithm::Sha256 => 
-                GetGraphemesIteratorInner::Modern32(modern_hmac_to_grapheme_indices::<H::SHA256>(&key, data, characters.len()).skip_while(is_zero)),
-            Algorithm::Ripemd160 => 
-                GetGraphemesIteratorInner::Modern20(modern_hmac_to_grapheme_indices::<H::RIPEMD160>(&key, data, characters.len()).skip_while(is_zero)),
-        };
-        GetGraphemesIterator { graphemes : characters, inner: grapheme_indices}
+        match algo {
+            Algorithm::Md4 => PasswordPartHash::Modern16(modern_hmac::<H::MD4,_,_>(&key, data, observer)),
+            Algorithm::Md5 => PasswordPartHash::Modern16(modern_hmac::<H::MD5,_,_>(&key, data, observer)),
+            Algorithm::Sha1 => PasswordPartHash::Modern20(modern_hmac::<H::SHA1,_,_>(&key, data, observer)),
+            Algorithm::Sha256 => PasswordPartHash::Modern32(modern_hmac::<H::SHA256,_,_>(&key, data, observer)),
+            Algorithm::Ripemd160 => PasswordPartHash::Modern20(modern_hmac::<H::RIPEMD160,_,_>(&key, data, observer)),
+            Algorithm::Ripemd128 => PasswordPartHash::Modern16(modern_hmac::<H::RIPEMD128,_,_>(&key, data, observer)),
+            Algorithm::Tiger => PasswordPartHash::Modern24(modern_hmac::<H::TIGER,_,_>(&key, data, observer)),
+        }
     }
     
-    fn generate_password_part_modern<'a>(
+    fn hash_password_part_modern<O : PasswordPartObserver>(
         second_part : &str,
         message : String,
         algo : &Algorithm,
-        pre_leet_level: &Option<LeetReplacementTable>,
-        characters : &'a Vec<Grapheme<'a>>,
-    ) -> GetGraphemesIterator<'a>  {
+        input_encoding : InputEncoding,
+        pre_leet_level: Option<&LeetReplacementTable>,
+        observer : &O,
+    ) -> PasswordPartHash {
         let message = message + second_part;
-        let message = pre_leet_level.as_ref().map(|l| l.leetify(&message)).unwrap_or(message);
-        let grapheme_indices = match algo {
-            Algorithm::Md4 => 
-                GetGraphemesIteratorInner::Modern16(modern_message_to_grapheme_indices::<H::MD4>(&message, characters.len()).skip_while(is_zero)),
-            Algorithm::Md5 => 
-                GetGraphemesIteratorInner::Modern16(modern_message_to_grapheme_indices::<H::MD5>(&message,characters.len()).skip_while(is_zero)),
-            Algorithm::Sha1 => 
-                GetGraphemesIteratorInner::Modern20(modern_message_to_grapheme_indices::<H::SHA1>(&message,characters.len()).skip_while(is_zero)),
-            Algorithm::Sha256 => 
-                GetGraphemesIteratorInner::Modern32(modern_message_to_grapheme_indices::<H::SHA256>(&message,characters.len()).skip_while(is_zero)),
-            Algorithm::Ripemd160 => 
-                GetGraphemesIteratorInner::Modern20(modern_message_to_grapheme_indices::<H::RIPEMD160>(&message,characters.len()).skip_while(is_zero)),
+        let message = pre_leet_level.map(|l| l.leetify(&message)).unwrap_or(message);
+        let message = match input_encoding {
+            InputEncoding::Utf8 => message.into_bytes(),
+            InputEncoding::Utf16DiscardHighByte => yeet_upper_bytes(&message).collect(),
         };
-        GetGraphemesIterator { graphemes : characters, inner: grapheme_indices}
+        match algo {
+            Algorithm::Md4 => PasswordPartHash::Modern16(modern_hash::<H::MD4,_,_>(&message, observer)),
+            Algorithm::Md5 => PasswordPartHash::Modern16(modern_hash::<H::MD5,_,_>(&message, observer)),
+            Algorithm::Sha1 => PasswordPartHash::Modern20(modern_hash::<H::SHA1,_,_>(&message, observer)),
+            Algorithm::Sha256 => PasswordPartHash::Modern32(modern_hash::<H::SHA256,_,_>(&message, observer)),
+            Algorithm::Ripemd160 => PasswordPartHash::Modern20(modern_hash::<H::RIPEMD160,_,_>(&message, observer)),
+            Algorithm::Ripemd128 => PasswordPartHash::Modern16(modern_hash::<H::RIPEMD128,_,_>(&message, observer)),
+            Algorithm::Tiger => PasswordPartHash::Modern24(modern_hash::<H::TIGER,_,_>(&message, observer)),
+        }
     }
 }
 
+/// The inputs of the hash function for each password part. Starting with the second part, a counter is appended to one of them.
+struct PartInputs<'a> {
+    /// data + username + modifier + context tag
+    data : String,
+    /// The length of the data part of `data`, in bytes.
+    data_length : usize,
+    /// key + revision
+    key : String,
+    placement : PartCounterPlacement,
+    /// Put in front of the part counter. Already leeted if `leet_applied` is set.
+    separator : Cow<'a, str>,
+    /// True if leet that is applied before generation has already been applied to data and key.
+    leet_applied : bool,
+}
+
+impl PartInputs<'_> {
+    /// The leet that still needs to be applied to the inputs returned by [`get`][PartInputs::get].
+    fn pre_leet_level<'p>(&self, parameters : &'p PasswordPartParameters) -> Option<&'p LeetReplacementTable> {
+        if self.leet_applied { None } else { parameters.pre_leet_level.as_ref() }
+    }
+
+    /// Returns data and key for the password part with index `i`.
+    /// For HMAC with keys longer than the block size, placing the counter in the key means that the key needs to be hashed anew for each part.
+    fn get(&self, i : usize) -> (Cow<'_, str>, Cow<'_, str>) {
+        if i == 0 {
+            return (Cow::Borrowed(&self.data), Cow::Borrowed(&self.key));
+        }
+        let counter = self.separator.clone().into_owned() + &i.to_string();
+        match self.placement {
+            PartCounterPlacement::Key => (Cow::Borrowed(&self.data), Cow::Owned(self.key.clone() + &counter)),
+            PartCounterPlacement::Data => {
+                let (data, username_modifier_and_context) = self.data.split_at(self.data_length);
+                (Cow::Owned(data.to_owned() + &counter + username_modifier_and_context), Cow::Borrowed(&self.key))
+            },
+            PartCounterPlacement::Modifier => (Cow::Owned(self.data.clone() + &counter), Cow::Borrowed(&self.key)),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub(super) struct PasswordAssemblyParameters<'a> {
-    suffix : &'a str,
-    prefix : &'a str,
+    suffix : Cow<'a, str>,
+    prefix : Cow<'a, str>,
     password_length : usize,
+    /// Capped at `password_length`, as only "does it fit" matters.
     suffix_length : usize,
+    /// Capped at `password_length`, as only "does it fit" matters.
     prefix_length : usize,
+    /// The unit of all lengths above. The password itself is generated in grapheme clusters, which are at least one unit long.
+    length_unit : LengthUnit,
 }
 impl<'a> PasswordAssemblyParameters<'a> {
-    pub(super) fn from_public_parameters(prefix : &'a str, suffix : &'a str, password_length : usize) -> Self{
+    pub(super) fn from_public_parameters(prefix : Cow<'a, str>, suffix : Cow<'a, str>, password_length : usize) -> Self{
+        Self::with_affix_lengths(prefix, suffix, password_length, LengthUnit::default())
+    }
+
+    pub(super) fn with_length_unit(self, length_unit : LengthUnit) -> Self {
+        Self::with_affix_lengths(self.prefix, self.suffix, self.password_length, length_unit)
+    }
+
+    fn with_affix_lengths(prefix : Cow<'a, str>, suffix : Cow<'a, str>, password_length : usize, length_unit : LengthUnit) -> Self {
+        //Lengths are only ever subtracted (saturating) from password_length. Anything above it yields the same result, so there's
+        //no need to walk the rest of a long affix. Matters for batch generation, where this runs for every password.
+        let affix_length = |affix : &str| Grapheme::iter_from_str(affix)
+            .flat_map(|grapheme| grapheme.length_units(length_unit))
+            .scan(0, |length, (_, units)| if *length < password_length { *length += units; Some(*length) } else { None })
+            .last()
+            .map_or(0, |length| length.min(password_length));
         PasswordAssemblyParameters {
+            suffix_length: affix_length(&suffix),
+            prefix_length: affix_length(&prefix),
             suffix,
             prefix,
             password_length,
-            suffix_length: Grapheme::iter_from_str(suffix).count(),
-            prefix_length: Grapheme::iter_from_str(prefix).count(),
+            length_unit,
+        }
+    }
+
+    pub(super) fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub(super) fn suffix(&self) -> &str {
+        &self.suffix
+    }
+
+    #[cfg(feature = "tracing")]
+    pub(super) fn password_length(&self) -> usize {
+        self.password_length
+    }
+
+    /// The number of graphemes that need to be generated, as prefix and suffix take up some of the password length.
+    fn needed_password_length(&self) -> usize {
+        self.password_length.saturating_sub(self.suffix_length).saturating_sub(self.prefix_length)
+    }
+
+    /// Mirrors the truncation in [`prefix_password_suffix`]: The suffix is cut to `password_length`, the prefix to what's left.
+    pub(super) fn affix_budget(&self) -> super::AffixBudget {
+        let full_length = |affix : &str| Grapheme::iter_from_str(affix)
+            .flat_map(|grapheme| grapheme.length_units(self.length_unit))
+            .map(|(_, units)| units)
+            .sum::<usize>();
+        super::AffixBudget {
+            prefix_length : full_length(&self.prefix),
+            prefix_kept : self.prefix_length.min(self.password_length - self.suffix_length),
+            suffix_length : full_length(&self.suffix),
+            suffix_kept : self.suffix_length,
+            generated_length : self.needed_password_length(),
         }
     }
 }
 
-fn combine_prefix_password_suffix<'a, T : Iterator<Item=Grapheme<'a>>>(password: T, assembly_settings : &PasswordAssemblyParameters<'a>) -> String {
+/// `max_password_bytes` is an upper bound for the number of bytes taken from `password`.
+/// Fails with [`OutputTooLarge`][super::GenerationError::OutputTooLarge] as soon as the output would exceed `max_output_bytes`.
+fn combine_prefix_password_suffix<'a, T : Iterator<Item=Grapheme<'a>>>(password: T, assembly_settings : &'a PasswordAssemblyParameters<'_>, max_password_bytes : usize, max_output_bytes : Option<usize>) -> Result<String, super::GenerationError> {
     //Rust's collect only uses the lower hint for pre-allocation. UnicodeSegmentation is giving correct hints,
     //meaning that the lower bound is 1 (or 0 for empty strings).
-    //We know however, that assembly_settings.password_length is a much better lower bound. Still too low for
-    //passwords that contain characters that take more than 1 byte though. Still, this value should reduce the number of needed re-allocations drastically.
-    let mut result = String::with_capacity(assembly_settings.password_length);
-    result.extend(Grapheme::iter_from_str(assembly_settings.prefix)
+    //We know however an upper bound for the password's length in bytes, so we can allocate once. This might over-allocate a bit if the
+    //characters have different lengths, but for the typical single-byte characters it is exact, and for emoji it avoids many re-allocations.
+    let capacity = assembly_settings.prefix.len().saturating_add(max_password_bytes).saturating_add(assembly_settings.suffix.len());
+    let max_output_bytes = max_output_bytes.unwrap_or(usize::MAX);
+    let mut result = String::with_capacity(capacity.min(max_output_bytes));
+    for grapheme in prefix_password_suffix(password, assembly_settings) {
+        if result.len() + grapheme.get().len() > max_output_bytes {
+            return Err(super::GenerationError::OutputTooLarge);
+        }
+        result.push_str(grapheme.get());
+    }
+    Ok(result)
+}
+
+fn prefix_password_suffix<'a, T : Iterator<Item=Grapheme<'a>>>(password: T, assembly_settings : &'a PasswordAssemblyParameters<'_>) -> impl Iterator<Item=Grapheme<'a>> {
+    let length_unit = assembly_settings.length_unit;
+    if length_unit != LengthUnit::GraphemeClusters {
+        return AssembledPassword::LengthUnits(prefix_password_suffix_in_units(password, assembly_settings, length_unit));
+    }
+    AssembledPassword::Graphemes(Grapheme::iter_from_str(&assembly_settings.prefix)
         .chain(password)
         .take(assembly_settings.password_length.saturating_sub(assembly_settings.suffix_length))
-        .chain(Grapheme::iter_from_str(assembly_settings.suffix))
-        .take(assembly_settings.password_length)//cut end if suffix_length is larger than password_length...
-        .map(|g| g.get()));
-    result
+        .chain(Grapheme::iter_from_str(&assembly_settings.suffix))
+        .take(assembly_settings.password_length))//cut end if suffix_length is larger than password_length...
+}
+
+/// Same as the grapheme cluster case of [`prefix_password_suffix`], but splits the graphemes into the pieces of `length_unit` first.
+/// Kept separate, as splitting makes the common case a lot slower.
+fn prefix_password_suffix_in_units<'a, T : Iterator<Item=Grapheme<'a>>>(password: T, assembly_settings : &'a PasswordAssemblyParameters<'_>, length_unit : LengthUnit) -> impl Iterator<Item=Grapheme<'a>> {
+    let split = move |grapheme : Grapheme<'a>| grapheme.length_units(length_unit);
+    let prefix_and_password = Grapheme::iter_from_str(&assembly_settings.prefix).chain(password).flat_map(split);
+    let suffix = Grapheme::iter_from_str(&assembly_settings.suffix).flat_map(split);
+    take_units(take_units(prefix_and_password, assembly_settings.password_length.saturating_sub(assembly_settings.suffix_length)).chain(suffix),
+        assembly_settings.password_length)
+        .map(|(piece, _)| piece)
+}
+
+enum AssembledPassword<G, U> {
+    Graphemes(G),
+    LengthUnits(U),
+}
+
+impl<'a, G : Iterator<Item=Grapheme<'a>>, U : Iterator<Item=Grapheme<'a>>> Iterator for AssembledPassword<G, U> {
+    type Item = Grapheme<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            AssembledPassword::Graphemes(graphemes) => graphemes.next(),
+            AssembledPassword::LengthUnits(pieces) => pieces.next(),
+        }
+    }
+}
+
+/// Takes pieces as long as their total length stays within `limit`. The first piece that doesn't fit ends the iteration.
+fn take_units<'a, T : Iterator<Item=(Grapheme<'a>, usize)>>(pieces : T, limit : usize) -> TakeUnits<T> {
+    TakeUnits { pieces, remaining : limit }
+}
+
+/// Unlike `scan`, this does not pull another piece once the limit is reached. The password is generated lazily, and pulling one
+/// grapheme too many could compute an extra password part.
+struct TakeUnits<T> {
+    pieces : T,
+    remaining : usize,
+}
+
+impl<'a, T : Iterator<Item=(Grapheme<'a>, usize)>> Iterator for TakeUnits<T> {
+    type Item = (Grapheme<'a>, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (piece, units) = self.pieces.next()?;
+        if let Some(remaining) = self.remaining.checked_sub(units) {
+            self.remaining = remaining;
+            Some((piece, units))
+        } else {
+            self.remaining = 0;
+            None
+        }
+    }
 }
 
 #[allow(clippy::trivially_copy_pass_by_ref)] //signature is actually determined by Iterator::skip_while(). There's simply no choice.
@@ -206,68 +616,216 @@ fn is_zero(i : &usize) -> bool {
     *i == 0
 }
 
-type BaseConversion16 = IterativeBaseConversion<SixteenBytes,usize>;
+//Named via the BaseConversion trait, so the conversion backend can change its iterator types without touching this file.
+type BaseConversion16 = <[u8;16] as BaseConversion>::Output;
 type BaseConversion16Modern = SkipWhile<BaseConversion16,fn(&usize)->bool>;
 
-type BaseConversion20 = IterativeBaseConversion<ArbitraryBytes<5>,usize>;
+type BaseConversion20 = <[u8;20] as BaseConversion>::Output;
 type BaseConversion20Modern = SkipWhile<BaseConversion20,fn(&usize)->bool>;
 
-type BaseConversion32 = IterativeBaseConversion<ArbitraryBytes<8>,usize>;
+type BaseConversion24 = <[u8;24] as BaseConversion>::Output;
+type BaseConversion24Modern = SkipWhile<BaseConversion24,fn(&usize)->bool>;
+
+type BaseConversion32 = <[u8;32] as BaseConversion>::Output;
 type BaseConversion32Modern = SkipWhile<BaseConversion32,fn(&usize)->bool>;
 
+type Base58Digits<T> = Chain<Take<Repeat<usize>>, SkipWhile<T,fn(&usize)->bool>>;
+
+/// The `Modern` variants skip leading zeros, the `Padded` variants keep them (V0.6 and [`OutputEncoding::Hex`]).
 enum GetGraphemesIteratorInner {
     Modern16(BaseConversion16Modern),
     Modern20(BaseConversion20Modern),
+    Modern24(BaseConversion24Modern),
     Modern32(BaseConversion32Modern),
-    V06(BaseConversion16)
+    Padded16(BaseConversion16),
+    Padded20(BaseConversion20),
+    Padded24(BaseConversion24),
+    Padded32(BaseConversion32),
+    Base58_16(Base58Digits<BaseConversion16>),
+    Base58_20(Base58Digits<BaseConversion20>),
+    Base58_24(Base58Digits<BaseConversion24>),
+    Base58_32(Base58Digits<BaseConversion32>),
+    Base64(Base64Digits),
 }
 struct GetGraphemesIterator<'a> {
-    graphemes : &'a Vec<Grapheme<'a>>,
+    graphemes : &'a GraphemeList<'a>,
     inner : GetGraphemesIteratorInner
 }
 
+impl GetGraphemesIterator<'_> {
+    fn into_indices(self) -> impl Iterator<Item=usize> {
+        self.inner
+    }
+}
+
 impl<'a> Iterator for GetGraphemesIterator<'a> {
     type Item = Grapheme<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let idx = match &mut self.inner {
+        self.inner.next().and_then(|idx| self.graphemes.get(idx))
+    }
+}
+
+impl Iterator for GetGraphemesIteratorInner {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
             GetGraphemesIteratorInner::Modern16(i) => i.next(),
             GetGraphemesIteratorInner::Modern20(i) => i.next(),
+            GetGraphemesIteratorInner::Modern24(i) => i.next(),
             GetGraphemesIteratorInner::Modern32(i) => i.next(),
-            GetGraphemesIteratorInner::V06(i) => i.next(),
+            GetGraphemesIteratorInner::Padded16(i) => i.next(),
+            GetGraphemesIteratorInner::Padded20(i) => i.next(),
+            GetGraphemesIteratorInner::Padded24(i) => i.next(),
+            GetGraphemesIteratorInner::Padded32(i) => i.next(),
+            GetGraphemesIteratorInner::Base58_16(i) => i.next(),
+            GetGraphemesIteratorInner::Base58_20(i) => i.next(),
+            GetGraphemesIteratorInner::Base58_24(i) => i.next(),
+            GetGraphemesIteratorInner::Base58_32(i) => i.next(),
+            GetGraphemesIteratorInner::Base64(i) => i.next(),
+        }
+    }
+}
+
+/// The raw hash of a single password part. It does not depend on the output characters, which is what allows
+/// [`generate_batch`][super::PasswordMaker::generate_batch] to reuse it for several outputs.
+#[derive(Clone)]
+enum PasswordPartHash {
+    V06([u8;16]),
+    Modern16([u8;16]),
+    Modern20([u8;20]),
+    Modern24([u8;24]),
+    Modern32([u8;32]),
+}
+
+impl PasswordPartHash {
+    /// `characters` must match `encoding`, see [`PasswordPartParameters::output_characters`].
+    fn to_graphemes<'a, O : PasswordPartObserver>(&self, characters : &'a GraphemeList<'a>, encoding : OutputEncoding, padding : OutputPadding, observer : &O) -> GetGraphemesIterator<'a> {
+        let base = characters.len();
+        observer.hash(self.bytes(), || self.digits(base, encoding, padding));
+        GetGraphemesIterator { graphemes : characters, inner : self.digits(base, encoding, padding) }
+    }
+
+    /// `padding` only matters for modern algorithms with [`OutputEncoding::Characters`].
+    fn digits(&self, base : usize, encoding : OutputEncoding, padding : OutputPadding) -> GetGraphemesIteratorInner {
+        //No padding needed for V0.6, hex output, or OutputPadding::LeadingZeros: The base conversion always yields as many digits
+        //as the largest value of the hash type needs. For hexadecimal output that's 2 digits per byte of hash output, leading zeros
+        //included. 32 for MD5. Only the Modern variants skip them.
+        let padded = encoding == OutputEncoding::Hex || padding == OutputPadding::LeadingZeros;
+        match (self, encoding) {
+            (_, OutputEncoding::Base64) => GetGraphemesIteratorInner::Base64(Base64Digits { hash : self.clone(), bit : 0 }),
+            (PasswordPartHash::V06(hash), OutputEncoding::Base58) | (PasswordPartHash::Modern16(hash), OutputEncoding::Base58) => 
+                GetGraphemesIteratorInner::Base58_16(base58_digits(hash)),
+            (PasswordPartHash::Modern20(hash), OutputEncoding::Base58) => GetGraphemesIteratorInner::Base58_20(base58_digits(hash)),
+            (PasswordPartHash::Modern24(hash), OutputEncoding::Base58) => GetGraphemesIteratorInner::Base58_24(base58_digits(hash)),
+            (PasswordPartHash::Modern32(hash), OutputEncoding::Base58) => GetGraphemesIteratorInner::Base58_32(base58_digits(hash)),
+            (PasswordPartHash::V06(hash), _) => GetGraphemesIteratorInner::Padded16(hash.convert_to_base(base)),
+            (PasswordPartHash::Modern16(hash), _) if padded => GetGraphemesIteratorInner::Padded16(hash.convert_to_base(base)),
+            (PasswordPartHash::Modern20(hash), _) if padded => GetGraphemesIteratorInner::Padded20(hash.convert_to_base(base)),
+            (PasswordPartHash::Modern24(hash), _) if padded => GetGraphemesIteratorInner::Padded24(hash.convert_to_base(base)),
+            (PasswordPartHash::Modern32(hash), _) if padded => GetGraphemesIteratorInner::Padded32(hash.convert_to_base(base)),
+            (PasswordPartHash::Modern16(hash), _) => GetGraphemesIteratorInner::Modern16(hash.convert_to_base(base).skip_while(is_zero)),
+            (PasswordPartHash::Modern20(hash), _) => GetGraphemesIteratorInner::Modern20(hash.convert_to_base(base).skip_while(is_zero)),
+            (PasswordPartHash::Modern24(hash), _) => GetGraphemesIteratorInner::Modern24(hash.convert_to_base(base).skip_while(is_zero)),
+            (PasswordPartHash::Modern32(hash), _) => GetGraphemesIteratorInner::Modern32(hash.convert_to_base(base).skip_while(is_zero)),
+        }
+    }
+
+    /// The first `max_count` digits of [`digits`][PasswordPartHash::digits], but computed up front with [`u128_leading_digits`], which
+    /// is faster. `None` for hashes longer than 16 bytes, and for Base58 and Base64 encodings.
+    fn leading_digits(&self, base : usize, encoding : OutputEncoding, padding : OutputPadding, max_count : usize) -> Option<impl Iterator<Item=usize>> {
+        let padded = encoding == OutputEncoding::Hex || padding == OutputPadding::LeadingZeros;
+        let (hash, padded) = match (self, encoding) {
+            (_, OutputEncoding::Base64) | (_, OutputEncoding::Base58) => return None,
+            (PasswordPartHash::V06(hash), _) => (hash, true),
+            (PasswordPartHash::Modern16(hash), _) => (hash, padded),
+            _ => return None,
         };
-        idx.and_then(|idx| self.graphemes.get(idx).cloned())
+        let base_u32 = u32::try_from(base).ok()?;
+        let min_digits = if padded { [u8::MAX;16].convert_to_base(base).len() } else { 0 };
+        let mut digits = [0;128];
+        let count = u128_leading_digits(u128::from_be_bytes(*hash), base_u32, min_digits, max_count, &mut digits);
+        Some((0..count).map(move |index| digits[index] as usize))
     }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            PasswordPartHash::V06(hash) | PasswordPartHash::Modern16(hash) => hash,
+            PasswordPartHash::Modern20(hash) => hash,
+            PasswordPartHash::Modern24(hash) => hash,
+            PasswordPartHash::Modern32(hash) => hash,
+        }
+    }
+}
+
+/// Like the usual Base58 encoding, each leading zero byte becomes a zero digit. The rest of the hash is converted without leading zeros.
+fn base58_digits<T : BaseConversion + AsRef<[u8]> + Copy>(hash : &T) -> Base58Digits<T::Output> {
+    let leading_zero_bytes = hash.as_ref().iter().take_while(|b| **b == 0).count();
+    std::iter::repeat(0).take(leading_zero_bytes).chain(hash.convert_to_base(58).skip_while(is_zero as fn(&usize)->bool))
+}
+
+/// Splits the hash into groups of 6 bits, most significant first. Like in Base64, the last group is padded with zero bits.
+struct Base64Digits {
+    hash : PasswordPartHash,
+    bit : usize,
 }
 
-fn modern_hmac_to_grapheme_indices<T>(key : &str, data: &str, divisor : usize) -> <<T as Hasher>::Output as BaseConversion>::Output
+impl Iterator for Base64Digits {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.hash.bytes();
+        let byte_index = self.bit / 8;
+        let window = u16::from(*bytes.get(byte_index)?) << 8 | u16::from(bytes.get(byte_index + 1).copied().unwrap_or(0));
+        let digit = (window >> (10 - self.bit % 8)) & 0x3f;
+        self.bit += 6;
+        Some(usize::from(digit))
+    }
+}
+
+fn modern_hmac<T, O, const N : usize>(key : &str, data: &str, observer : &O) -> [u8;N]
     where T:Hasher,
-    <T as Hasher>::Output: BaseConversion + AsRef<[u8]>
+    O : PasswordPartObserver,
 {
-    hmac::hmac::<T,_>(key.as_bytes(), data.bytes()).convert_to_base(divisor)
+    observer.hasher_input(Some(key.as_bytes()), data.bytes());
+    digest_bytes::<T,N>(&hmac::hmac::<T>(key.as_bytes(), data.as_bytes()))
 }
 
-fn modern_message_to_grapheme_indices<T>(data: &str, divisor : usize) -> <<T as Hasher>::Output as BaseConversion>::Output
+fn modern_hash<T, O, const N : usize>(data: &[u8], observer : &O) -> [u8;N]
     where T:Hasher,
-    <T as Hasher>::Output: BaseConversion
+    O : PasswordPartObserver,
 {
-    T::hash(data.as_bytes()).convert_to_base(divisor)
+    observer.hasher_input(None, data.iter().copied());
+    digest_bytes::<T,N>(&T::hash(data))
 }
 
+/// Copies the output of a [`Hasher`] into an array of the digest length of the algorithm, so the base conversion can pick the matching
+/// implementation. A hasher with the wrong output length cannot produce a meaningful password, so this panics. The generation
+/// functions state this in their `# Panics` sections.
+fn digest_bytes<T : Hasher, const N : usize>(output : &T::Output) -> [u8;N] {
+    let bytes = output.as_ref();
+    <[u8;N]>::try_from(bytes).unwrap_or_else(|_| panic!("The hash function returned {} bytes, but the algorithm needs {} bytes.", bytes.len(), N))
+}
+
+#[derive(Clone)]
 pub(super) struct PasswordPartParameters<'a>{
     hash_algorithm : AlgoSelection,
     pre_leet_level : Option<LeetReplacementTable>,
-    characters : Vec<Grapheme<'a>>,
+    characters : GraphemeList<'a>,
+    output_encoding : OutputEncoding,
+    output_padding : OutputPadding,
+    input_encoding : InputEncoding,
 }
 
 impl<'a> PasswordPartParameters<'a>{
-    pub(super) fn from_public_parameters(hash_algorithm : super::HashAlgorithm, leet : super::UseLeetWhenGenerating, characters : &'a str) -> Self {
+    pub(super) fn from_public_parameters(hash_algorithm : super::HashAlgorithm, leet : super::UseLeetWhenGenerating, characters : GraphemeList<'a>) -> Self {
         use super::UseLeetWhenGenerating;
         let hash_algorithm = AlgoSelection::from_public_parameters(hash_algorithm);
         PasswordPartParameters{
             characters: match &hash_algorithm {
-                AlgoSelection::V06(_) => Grapheme::iter_from_str("0123456789abcdef").collect(),
-                AlgoSelection::Modern(_) => Grapheme::iter_from_str(characters).collect(),
+                AlgoSelection::V06(_) => HEX_DIGITS.borrow(),
+                AlgoSelection::Modern(_) => characters,
             },
             pre_leet_level: match leet {
                 UseLeetWhenGenerating::NotAtAll
@@ -276,28 +834,111 @@ impl<'a> PasswordPartParameters<'a>{
                  | UseLeetWhenGenerating::BeforeAndAfter { level } => Some(LeetReplacementTable::get(level)),
             },
             hash_algorithm,
+            output_encoding : OutputEncoding::default(),
+            output_padding : OutputPadding::default(),
+            input_encoding : InputEncoding::default(),
+        }
+    }
+
+    pub(super) fn with_output_encoding(self, output_encoding : OutputEncoding) -> Self {
+        PasswordPartParameters { output_encoding, ..self }
+    }
+
+    pub(super) fn with_output_padding(self, output_padding : OutputPadding) -> Self {
+        PasswordPartParameters { output_padding, ..self }
+    }
+
+    pub(super) fn with_input_encoding(self, input_encoding : InputEncoding) -> Self {
+        PasswordPartParameters { input_encoding, ..self }
+    }
+
+    pub(super) fn hash_algorithm(&self) -> super::HashAlgorithm {
+        self.hash_algorithm.to_public_parameters()
+    }
+
+    /// The index of the first entry of the user-supplied characters that contains a control character, if they are used.
+    /// If given, `characters` are checked instead, like the characters of an `OutputSpec`, which replace the user-supplied ones.
+    pub(super) fn find_control_character(&self, characters : Option<&GraphemeList>) -> Option<usize> {
+        if self.uses_characters() {
+            characters.unwrap_or(&self.characters).iter().position(|grapheme| grapheme.get().chars().any(char::is_control))
+        } else {
+            None
+        }
+    }
+
+    /// False if the user-supplied characters are ignored, because a V0.6 algorithm or an output encoding other than `Characters` is used.
+    pub(super) fn uses_characters(&self) -> bool {
+        matches!((&self.hash_algorithm, self.output_encoding), (AlgoSelection::Modern(_), OutputEncoding::Characters))
+    }
+
+    /// The characters the digits of the hash select from. Only for [`OutputEncoding::Characters`] these are the user-supplied ones.
+    fn output_characters(&self) -> &GraphemeList<'a> {
+        match self.output_encoding {
+            OutputEncoding::Characters => &self.characters,
+            OutputEncoding::Hex => &HEX_DIGITS,
+            OutputEncoding::Base64 => &BASE64_URL_DIGITS,
+            OutputEncoding::Base58 => &BASE58_DIGITS,
         }
     }
+
+    /// The number of output characters, which is the base the hash is converted to.
+    pub(super) fn base(&self) -> usize {
+        self.output_characters().len()
+    }
+
+    /// Same as [`base`][Self::base], but with `characters` instead of the user-supplied ones, like for an `OutputSpec`.
+    pub(super) fn base_with(&self, characters : &GraphemeList) -> usize {
+        if self.uses_characters() { characters.len() } else { self.base() }
+    }
+
+    /// The number of digits the largest possible hash yields. Modern algorithms skip leading zeros, so they might yield fewer.
+    pub(super) fn max_digits_per_part(&self) -> usize {
+        let base = self.base();
+        let algorithm = match &self.hash_algorithm {
+            AlgoSelection::V06(_) => return [u8::MAX;16].convert_to_base(base).len(),
+            AlgoSelection::Modern(HmacOrNot::Hmac(a)) | AlgoSelection::Modern(HmacOrNot::NonHmac(a)) => a,
+        };
+        match algorithm {
+            Algorithm::Md4 | Algorithm::Md5 | Algorithm::Ripemd128 => [u8::MAX;16].convert_to_base(base).len(),
+            Algorithm::Sha1 | Algorithm::Ripemd160 => [u8::MAX;20].convert_to_base(base).len(),
+            Algorithm::Tiger => [u8::MAX;24].convert_to_base(base).len(),
+            Algorithm::Sha256 => [u8::MAX;32].convert_to_base(base).len(),
+        }
+    }
+
+    /// True if converting the hashes to `base` digits needs to find the highest fitting power at runtime. That's only slow for
+    /// hashes longer than 16 bytes. Base58 and Base64 output doesn't use the base conversion at all.
+    pub(super) fn needs_slow_base_conversion(&self, base : usize) -> bool {
+        let hash_bytes = self.hash_algorithm().output_bytes();
+        let converts_base = matches!(self.output_encoding, OutputEncoding::Characters | OutputEncoding::Hex);
+        converts_base && hash_bytes > 16 && !is_base_precomputed(base, hash_bytes)
+    }
 }
 
+#[derive(Clone)]
 enum Algorithm {
     Md4,
     Md5,
     Sha1,
     Sha256,
     Ripemd160,
+    Ripemd128,
+    Tiger,
 }
 
+#[derive(Clone)]
 enum HmacOrNot{
     Hmac(Algorithm),
     NonHmac(Algorithm),
 }
 
+#[derive(Clone)]
 enum V06HmacOrNot{
     Hmac,
     NonHmac,
 }
 
+#[derive(Clone)]
 enum AlgoSelection{
     V06(V06HmacOrNot),
     Modern(HmacOrNot),
@@ -319,6 +960,33 @@ impl AlgoSelection {
             HashAlgorithm::HmacSha256 => AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Sha256)),
             HashAlgorithm::Ripemd160 => AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Ripemd160)),
             HashAlgorithm::HmacRipemd160 => AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Ripemd160)),
+            HashAlgorithm::Ripemd128 => AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Ripemd128)),
+            HashAlgorithm::HmacRipemd128 => AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Ripemd128)),
+            HashAlgorithm::Tiger => AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Tiger)),
+            HashAlgorithm::HmacTiger => AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Tiger)),
+        }
+    }
+
+    /// Inverse of [`from_public_parameters`][AlgoSelection::from_public_parameters].
+    fn to_public_parameters(&self) -> super::HashAlgorithm {
+        use super::HashAlgorithm;
+        match self {
+            AlgoSelection::V06(V06HmacOrNot::NonHmac) => HashAlgorithm::Md5Version06,
+            AlgoSelection::V06(V06HmacOrNot::Hmac) => HashAlgorithm::HmacMd5Version06,
+            AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Md4)) => HashAlgorithm::Md4,
+            AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Md4)) => HashAlgorithm::HmacMd4,
+            AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Md5)) => HashAlgorithm::Md5,
+            AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Md5)) => HashAlgorithm::HmacMd5,
+            AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Sha1)) => HashAlgorithm::Sha1,
+            AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Sha1)) => HashAlgorithm::HmacSha1,
+            AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Sha256)) => HashAlgorithm::Sha256,
+            AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Sha256)) => HashAlgorithm::HmacSha256,
+            AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Ripemd160)) => HashAlgorithm::Ripemd160,
+            AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Ripemd160)) => HashAlgorithm::HmacRipemd160,
+            AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Ripemd128)) => HashAlgorithm::Ripemd128,
+            AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Ripemd128)) => HashAlgorithm::HmacRipemd128,
+            AlgoSelection::Modern(HmacOrNot::NonHmac(Algorithm::Tiger)) => HashAlgorithm::Tiger,
+            AlgoSelection::Modern(HmacOrNot::Hmac(Algorithm::Tiger)) => HashAlgorithm::HmacTiger,
         }
     }
 }
@@ -336,16 +1004,93 @@ mod passwordmaker_tests {
 
     #[test]
     fn test_combine_prefix_password_suffix(){
-        let parameters = PasswordAssemblyParameters::from_public_parameters("prefi", "suffi", 15);
-        let result = combine_prefix_password_suffix(Grapheme::iter_from_str("passwo"), &parameters);
+        let parameters = PasswordAssemblyParameters::from_public_parameters("prefi".into(), "suffi".into(), 15);
+        let result = combine_prefix_password_suffix(Grapheme::iter_from_str("passwo"), &parameters, 6, None).unwrap();
         assert_eq!(&result, "prefipasswsuffi");
     }
     #[test]
     fn test_combine_prefix_password_suffix_too_short(){
-        let parameters = PasswordAssemblyParameters::from_public_parameters("prefi", "suffi", 8);
-        let result = combine_prefix_password_suffix(Grapheme::iter_from_str("passwo"), &parameters);
+        let parameters = PasswordAssemblyParameters::from_public_parameters("prefi".into(), "suffi".into(), 8);
+        let result = combine_prefix_password_suffix(Grapheme::iter_from_str("passwo"), &parameters, 6, None).unwrap();
         assert_eq!(&result, "presuffi");
     }
+    #[test]
+    fn test_combine_prefix_password_suffix_emoji_allocates_once(){
+        let characters = GraphemeList::new(Cow::Borrowed("😀😁😂🤣😃😄😅😆"));
+        assert_eq!(characters.max_grapheme_len(), 4);
+        let parameters = PasswordAssemblyParameters::from_public_parameters("".into(), "".into(), 100);
+        let password = (0..).map(|i| characters.get(i % characters.len()).unwrap());
+        let result = combine_prefix_password_suffix(password, &parameters, parameters.needed_password_length() * characters.max_grapheme_len(), None).unwrap();
+        assert_eq!(result.chars().count(), 100);
+        assert_eq!(result.len(), 400);
+        assert_eq!(result.capacity(), 400);
+    }
+    #[test]
+    fn test_affix_longer_than_password(){
+        let long = "0123456789".repeat(1000);
+        let parameters = PasswordAssemblyParameters::from_public_parameters("".into(), long.as_str().into(), 8);
+        assert_eq!(parameters.suffix_length, 8);
+        assert_eq!(parameters.needed_password_length(), 0);
+        let result = combine_prefix_password_suffix(Grapheme::iter_from_str("abcdefgh"), &parameters, 8, None).unwrap();
+        assert_eq!(result, "01234567");
+        let parameters = PasswordAssemblyParameters::from_public_parameters(long.as_str().into(), "".into(), 8);
+        assert_eq!(parameters.prefix_length, 8);
+        let result = combine_prefix_password_suffix(Grapheme::iter_from_str("abcdefgh"), &parameters, 8, None).unwrap();
+        assert_eq!(result, "01234567");
+    }
+    #[test]
+    fn test_combine_prefix_password_suffix_length_units(){
+        //The thumbs up with skin tone modifier is one grapheme cluster, two code points, and four UTF-16 code units.
+        let combine = |prefix : &str, suffix : &str, length_unit : LengthUnit| {
+            let parameters = PasswordAssemblyParameters::from_public_parameters(prefix.to_owned().into(), suffix.to_owned().into(), 4).with_length_unit(length_unit);
+            combine_prefix_password_suffix(Grapheme::iter_from_str("abcd"), &parameters, 4, None).unwrap()
+        };
+        assert_eq!(combine("", "x👍🏽", LengthUnit::GraphemeClusters), "abx👍🏽");
+        assert_eq!(combine("", "x👍🏽", LengthUnit::CodePoints), "ax👍🏽");
+        //The skin tone modifier would exceed the length, and is cut.
+        assert_eq!(combine("", "x👍🏽", LengthUnit::Utf16CodeUnits), "x👍");
+        assert_eq!(combine("👍🏽ab", "", LengthUnit::GraphemeClusters), "👍🏽aba");
+        assert_eq!(combine("👍🏽ab", "", LengthUnit::CodePoints), "👍🏽ab");
+        assert_eq!(combine("👍🏽ab", "", LengthUnit::Utf16CodeUnits), "👍🏽");
+        //Half a code point is never emitted.
+        assert_eq!(combine("a👍🏽", "", LengthUnit::Utf16CodeUnits), "a👍");
+        //Password characters are counted the same way.
+        let characters = GraphemeList::new(Cow::Borrowed("👍🏽"));
+        let parameters = PasswordAssemblyParameters::from_public_parameters("".into(), "".into(), 3).with_length_unit(LengthUnit::CodePoints);
+        let password = (0..).map(|_| characters.get(0).unwrap());
+        assert_eq!(combine_prefix_password_suffix(password, &parameters, 24, None).unwrap(), "👍🏽👍");
+    }
+    #[test]
+    fn test_affix_length_units(){
+        let parameters = |length_unit| PasswordAssemblyParameters::from_public_parameters("e\u{301}".into(), "x👍🏽".into(), 8).with_length_unit(length_unit);
+        assert_eq!(parameters(LengthUnit::GraphemeClusters).prefix_length, 1);
+        assert_eq!(parameters(LengthUnit::GraphemeClusters).suffix_length, 2);
+        assert_eq!(parameters(LengthUnit::CodePoints).prefix_length, 2);
+        assert_eq!(parameters(LengthUnit::CodePoints).suffix_length, 3);
+        assert_eq!(parameters(LengthUnit::Utf16CodeUnits).prefix_length, 2);
+        assert_eq!(parameters(LengthUnit::Utf16CodeUnits).suffix_length, 5);
+        assert_eq!(parameters(LengthUnit::Utf16CodeUnits).needed_password_length(), 1);
+        //Capped at the password length.
+        let parameters = PasswordAssemblyParameters::from_public_parameters("".into(), "👍🏽👍🏽".into(), 6).with_length_unit(LengthUnit::Utf16CodeUnits);
+        assert_eq!(parameters.suffix_length, 6);
+    }
+    #[test]
+    fn test_combine_prefix_password_suffix_byte_limit(){
+        let parameters = PasswordAssemblyParameters::from_public_parameters("pre".into(), "€".into(), 8);
+        //3 + 4 + 3 bytes
+        let result = combine_prefix_password_suffix(Grapheme::iter_from_str("abcdefgh"), &parameters, 8, Some(10));
+        assert_eq!(result.unwrap(), "preabcd€");
+        let result = combine_prefix_password_suffix(Grapheme::iter_from_str("abcdefgh"), &parameters, 8, Some(9));
+        assert!(matches!(result, Err(crate::GenerationError::OutputTooLarge)));
+    }
+    #[test]
+    fn test_max_grapheme_len(){
+        assert_eq!(GraphemeList::new(Cow::Borrowed("")).max_grapheme_len(), 0);
+        assert_eq!(GraphemeList::new(Cow::Borrowed("abc")).max_grapheme_len(), 1);
+        //e + combining acute accent is a single grapheme of 3 bytes.
+        assert_eq!(GraphemeList::new(Cow::Borrowed("ae\u{301}€b")).max_grapheme_len(), 3);
+        assert_eq!(GraphemeList::new(Cow::Borrowed("a👍🏽b")).borrow().max_grapheme_len(), 8);
+    }
 
     #[test]
     fn test_yeet_upper_bytes(){
@@ -353,4 +1098,27 @@ mod passwordmaker_tests {
         let result = yeet_upper_bytes(testinput).collect::<Vec<_>>();
         assert_eq!(result, vec![0xac,0xa9,0x26,0xc6,0xc6]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn v06_hash_keeps_leading_zeros(){
+        let characters = GraphemeList::new(Cow::Borrowed("0123456789abcdef"));
+        let mut hash = [0u8;16];
+        hash[3] = 0x0a;
+        hash[15] = 0xff;
+        let result = PasswordPartHash::V06(hash).to_graphemes(&characters, OutputEncoding::Characters, OutputPadding::None, &NoTrace).map(|g| g.get()).collect::<String>();
+        assert_eq!(result.len(), 2 * hash.len());
+        assert_eq!(result, "0000000a0000000000000000000000ff");
+    }
+
+    #[test]
+    fn hex_digit_count_is_twice_the_hash_size(){
+        let mut hash = [0u8;20];
+        hash[19] = 1;
+        let digits = hash.convert_to_base(16).collect::<Vec<_>>();
+        assert_eq!(digits.len(), 2 * hash.len());
+        assert!(digits[..39].iter().all(|d| *d == 0));
+        assert_eq!(digits[39], 1);
+        assert_eq!([0u8;24].convert_to_base(16).len(), 48);
+        assert_eq!([0u8;32].convert_to_base(16).len(), 64);
+    }
+}
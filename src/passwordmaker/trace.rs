@@ -0,0 +1,47 @@
+use std::cell::RefCell;
+
+use crate::{GenerationTrace, PasswordPartTrace};
+
+/// Gets notified about the intermediate values while a password part is computed.
+/// The normal generation path uses [`NoTrace`], which the compiler should be able to optimize away completely.
+pub(super) trait PasswordPartObserver {
+    /// Called with the exact input to the hash function. `key` is only `Some` for HMAC algorithms.
+    fn hasher_input<M : Iterator<Item=u8>>(&self, key : Option<&[u8]>, message : M);
+    /// Called with the raw hash. `digits` yields the digits the password part is made of.
+    fn hash<D : FnOnce() -> I, I : Iterator<Item=usize>>(&self, hash : &[u8], digits : D);
+}
+
+pub(super) struct NoTrace;
+
+impl PasswordPartObserver for NoTrace {
+    fn hasher_input<M : Iterator<Item=u8>>(&self, _key : Option<&[u8]>, _message : M) {}
+    fn hash<D : FnOnce() -> I, I : Iterator<Item=usize>>(&self, _hash : &[u8], _digits : D) {}
+}
+
+//Password parts are generated from within Fn closures, so interior mutability it is.
+#[derive(Default)]
+pub(super) struct Tracer(RefCell<Vec<PasswordPartTrace>>);
+
+impl Tracer {
+    pub(super) fn into_trace(self) -> GenerationTrace {
+        GenerationTrace { parts : self.0.into_inner() }
+    }
+}
+
+impl PasswordPartObserver for Tracer {
+    fn hasher_input<M : Iterator<Item=u8>>(&self, key : Option<&[u8]>, message : M) {
+        self.0.borrow_mut().push(PasswordPartTrace {
+            hmac_key : key.map(<[u8]>::to_vec),
+            hasher_input : message.collect(),
+            hash : Vec::new(),
+            digits : Vec::new(),
+        });
+    }
+
+    fn hash<D : FnOnce() -> I, I : Iterator<Item=usize>>(&self, hash : &[u8], digits : D) {
+        if let Some(part) = self.0.borrow_mut().last_mut() {
+            part.hash = hash.to_vec();
+            part.digits = digits().collect();
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use super::{HashAlgorithm, LeetLevel, ParseUseLeetError, PreparedCharset, ProtocolUsageMode, Settings, SettingsError, UrlParsing, UseLeetWhenGenerating};
+
+/// PasswordMaker Pro's "Alphanumerics + Symbols" character set, which is its default.
+const DEFAULT_CHARACTERS : &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+
+pub(super) fn factory_defaults() -> Settings {
+    Settings {
+        hash_algorithm : HashAlgorithm::Md5,
+        use_leet : UseLeetWhenGenerating::NotAtAll,
+        characters : PreparedCharset::new(DEFAULT_CHARACTERS).expect("The default characters are more than a single grapheme cluster."),
+        username : String::new(),
+        modifier : String::new(),
+        password_length : 8,
+        prefix : String::new(),
+        suffix : String::new(),
+        url_parsing : UrlParsing::default(),
+    }
+}
+
+pub(super) fn from_kv(map : &BTreeMap<String, String>) -> Result<Settings, SettingsError> {
+    let get = |key : &str| map.get(key).map(String::as_str);
+    let text = |key : &str, default : String| get(key).map_or(default, str::to_owned);
+
+    let defaults = factory_defaults();
+    let url_defaults = &defaults.url_parsing;
+    let url_parsing = UrlParsing::new(
+        parse_or(get("protocol"), "protocol", url_defaults.use_protocol, parse_protocol_usage_mode)?,
+        parse_or(get("userinfo"), "userinfo", url_defaults.use_userinfo, from_str)?,
+        parse_or(get("subdomains"), "subdomains", url_defaults.use_subdomains, from_str)?,
+        parse_or(get("domain"), "domain", url_defaults.use_domain, from_str)?,
+        parse_or(get("port_path"), "port_path", url_defaults.use_port_path, from_str)?,
+    );
+
+    Ok(Settings {
+        hash_algorithm : parse_or(get("algorithm"), "algorithm", defaults.hash_algorithm, parse_hash_algorithm)?,
+        use_leet : parse_or(get("leet"), "leet", defaults.use_leet, from_str)?,
+        characters : get("characters").map_or(Ok(defaults.characters), PreparedCharset::new)?,
+        username : text("username", defaults.username),
+        modifier : text("modifier", defaults.modifier),
+        password_length : parse_or(get("length"), "length", defaults.password_length, from_str)?,
+        prefix : text("prefix", defaults.prefix),
+        suffix : text("suffix", defaults.suffix),
+        url_parsing,
+    })
+}
+
+/// Returns `default` if `value` is `None`, and fails with [`SettingsError::InvalidValue`] for `key` if `parse` does not accept it.
+fn parse_or<T, P : Fn(&str) -> Option<T>>(value : Option<&str>, key : &'static str, default : T, parse : P) -> Result<T, SettingsError> {
+    value.map_or(Ok(default), |value| parse(value).ok_or(SettingsError::InvalidValue { key }))
+}
+
+fn from_str<T : FromStr>(value : &str) -> Option<T> {
+    value.parse().ok()
+}
+
+//Not using the strum derives, so this also works without the strum feature, and is case-insensitive.
+fn parse_hash_algorithm(value : &str) -> Option<HashAlgorithm> {
+    HashAlgorithm::all().iter().copied().find(|algorithm| algorithm.to_string().eq_ignore_ascii_case(value))
+}
+
+pub(super) fn parse_protocol_usage_mode(value : &str) -> Option<ProtocolUsageMode> {
+    [ProtocolUsageMode::Ignored, ProtocolUsageMode::Used, ProtocolUsageMode::UsedWithUndefinedIfEmpty].iter().copied()
+        .find(|mode| format!("{:?}", mode).eq_ignore_ascii_case(value))
+}
+
+impl FromStr for UseLeetWhenGenerating {
+    type Err = ParseUseLeetError;
+    fn from_str(value : &str) -> Result<Self, Self::Err> {
+        let mut split = value.splitn(2, ':');
+        let keyword = split.next().unwrap_or_default();
+        let level = split.next();
+        if keyword.eq_ignore_ascii_case("none") {
+            return level.map_or(Ok(UseLeetWhenGenerating::NotAtAll), |_| Err(ParseUseLeetError::InvalidLevel));
+        }
+        let variant = if keyword.eq_ignore_ascii_case("before") {
+            |level| UseLeetWhenGenerating::Before { level }
+        } else if keyword.eq_ignore_ascii_case("after") {
+            |level| UseLeetWhenGenerating::After { level }
+        } else if keyword.eq_ignore_ascii_case("both") {
+            |level| UseLeetWhenGenerating::BeforeAndAfter { level }
+        } else {
+            return Err(ParseUseLeetError::UnknownKeyword);
+        };
+        let level = level.ok_or(ParseUseLeetError::MissingLevel)?;
+        parse_leet_level(level).map(variant).ok_or(ParseUseLeetError::InvalidLevel)
+    }
+}
+
+impl TryFrom<&str> for UseLeetWhenGenerating {
+    type Error = ParseUseLeetError;
+    fn try_from(value : &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+fn parse_leet_level(value : &str) -> Option<LeetLevel> {
+    match value {
+        "0" => Some(LeetLevel::Zero),
+        "1" => Some(LeetLevel::One),
+        "2" => Some(LeetLevel::Two),
+        "3" => Some(LeetLevel::Three),
+        "4" => Some(LeetLevel::Four),
+        "5" => Some(LeetLevel::Five),
+        "6" => Some(LeetLevel::Six),
+        "7" => Some(LeetLevel::Seven),
+        "8" => Some(LeetLevel::Eight),
+        "9" => Some(LeetLevel::Nine),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod settings_tests {
+    use super::*;
+
+    fn map(entries : &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries.iter().map(|(key, value)| ((*key).to_owned(), (*value).to_owned())).collect()
+    }
+
+    #[test]
+    fn complete_map(){
+        let settings = from_kv(&map(&[
+            ("algorithm", "hmacsha256"),
+            ("leet", "after:6"),
+            ("characters", "abc"),
+            ("username", "user"),
+            ("modifier", "mod"),
+            ("length", "16"),
+            ("prefix", "pre"),
+            ("suffix", "suf"),
+            ("protocol", "Used"),
+            ("userinfo", "true"),
+            ("subdomains", "true"),
+            ("domain", "false"),
+            ("port_path", "true"),
+            ("unrelated", "ignored"),
+        ])).unwrap();
+        assert_eq!(settings.hash_algorithm, HashAlgorithm::HmacSha256);
+        assert_eq!(settings.use_leet, UseLeetWhenGenerating::After { level : LeetLevel::Six });
+        assert_eq!(settings.characters.as_str(), "abc");
+        assert_eq!(settings.username, "user");
+        assert_eq!(settings.modifier, "mod");
+        assert_eq!(settings.password_length, 16);
+        assert_eq!(settings.prefix, "pre");
+        assert_eq!(settings.suffix, "suf");
+        assert_eq!(settings.url_parsing.parse("https://me@www.example.com:8080/path"), "https://me@www:8080/path");
+    }
+
+    #[test]
+    fn minimal_map_uses_defaults(){
+        let settings = from_kv(&BTreeMap::new()).unwrap();
+        assert_eq!(settings.hash_algorithm, HashAlgorithm::Md5);
+        assert_eq!(settings.use_leet, UseLeetWhenGenerating::NotAtAll);
+        assert_eq!(settings.characters.as_str(), DEFAULT_CHARACTERS);
+        assert_eq!(settings.username, "");
+        assert_eq!(settings.password_length, 8);
+        assert_eq!(settings.url_parsing.parse("https://me@www.example.com:8080/path"), "example.com");
+    }
+
+    #[test]
+    fn default_is_factory_configuration(){
+        let settings = Settings::default();
+        assert_eq!(settings.characters.as_str(), "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./");
+        assert_eq!(crate::graphemes(settings.characters.as_str()).count(), 94);
+        assert_eq!(settings.password_length, 8);
+        assert_eq!(settings.hash_algorithm, HashAlgorithm::Md5);
+        assert_eq!(settings.use_leet, UseLeetWhenGenerating::NotAtAll);
+        assert_eq!((settings.username.as_str(), settings.modifier.as_str(), settings.prefix.as_str(), settings.suffix.as_str()), ("", "", "", ""));
+        assert_eq!(settings.url_parsing, UrlParsing::default());
+    }
+
+    #[test]
+    fn validate_all_reports_missing_data_and_key(){
+        let settings = Settings::default();
+        let errors = settings.validate_all("", "").unwrap_err();
+        assert!(matches!(errors.as_slice(), [crate::GenerationError::MissingTextToUse, crate::GenerationError::MissingMasterPassword]));
+        assert!(matches!(settings.validate_all("example.com", "").unwrap_err().as_slice(), [crate::GenerationError::MissingMasterPassword]));
+        assert!(settings.validate_all("example.com", "x").is_ok());
+    }
+
+    #[test]
+    fn parse_use_leet(){
+        assert_eq!("none".parse(), Ok(UseLeetWhenGenerating::NotAtAll));
+        assert_eq!("before:3".parse(), Ok(UseLeetWhenGenerating::Before { level : LeetLevel::Three }));
+        assert_eq!("after:6".parse(), Ok(UseLeetWhenGenerating::After { level : LeetLevel::Six }));
+        assert_eq!("both:9".parse(), Ok(UseLeetWhenGenerating::BeforeAndAfter { level : LeetLevel::Nine }));
+        assert_eq!("Before:1".parse(), Ok(UseLeetWhenGenerating::Before { level : LeetLevel::One }));
+        assert_eq!(UseLeetWhenGenerating::try_from("after:2"), Ok(UseLeetWhenGenerating::After { level : LeetLevel::Two }));
+        assert_eq!("after".parse::<UseLeetWhenGenerating>(), Err(ParseUseLeetError::MissingLevel));
+        assert_eq!("after:".parse::<UseLeetWhenGenerating>(), Err(ParseUseLeetError::InvalidLevel));
+        assert_eq!("after:0".parse(), Ok(UseLeetWhenGenerating::After { level : LeetLevel::Zero }));
+        assert_eq!("after:10".parse::<UseLeetWhenGenerating>(), Err(ParseUseLeetError::InvalidLevel));
+        assert_eq!("none:1".parse::<UseLeetWhenGenerating>(), Err(ParseUseLeetError::InvalidLevel));
+        assert_eq!("none:0".parse::<UseLeetWhenGenerating>(), Err(ParseUseLeetError::InvalidLevel));
+        assert_eq!("during:1".parse::<UseLeetWhenGenerating>(), Err(ParseUseLeetError::UnknownKeyword));
+        assert_eq!("".parse::<UseLeetWhenGenerating>(), Err(ParseUseLeetError::UnknownKeyword));
+        assert_eq!(ParseUseLeetError::InvalidLevel.to_string(), "The leet level must be a number from 0 to 9.");
+    }
+
+    #[test]
+    fn invalid_values(){
+        let error = |entries : &[(&str, &str)]| from_kv(&map(entries)).unwrap_err();
+        assert!(matches!(error(&[("algorithm", "sha3")]), SettingsError::InvalidValue { key : "algorithm" }));
+        assert!(matches!(error(&[("length", "-1")]), SettingsError::InvalidValue { key : "length" }));
+        assert!(matches!(error(&[("leet", "after")]), SettingsError::InvalidValue { key : "leet" }));
+        assert!(matches!(error(&[("leet", "after:10")]), SettingsError::InvalidValue { key : "leet" }));
+        assert!(matches!(error(&[("leet", "during:1")]), SettingsError::InvalidValue { key : "leet" }));
+        assert!(matches!(error(&[("domain", "yes")]), SettingsError::InvalidValue { key : "domain" }));
+        assert!(matches!(error(&[("protocol", "always")]), SettingsError::InvalidValue { key : "protocol" }));
+        assert!(matches!(error(&[("characters", "a")]), SettingsError::InsufficientCharset { found : 1 }));
+        assert_eq!(error(&[("length", "eight")]).to_string(), "The value of setting \"length\" is invalid.");
+    }
+}
@@ -0,0 +1,139 @@
+//! Tests for hash functions that can fail, see `TryHasherList`.
+
+use std::cell::Cell;
+use passwordmaker_rs::{Fallible, GenerationError, HashAlgorithm, Hasher, HasherList, PasswordMaker, TryGenerationError, TryHasher, TryHasherList, UseLeetWhenGenerating};
+use digest::Digest;
+
+#[derive(Debug, PartialEq, Eq)]
+struct EnclaveError(usize);
+
+impl std::fmt::Display for EnclaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "enclave unavailable at call {}", self.0)
+    }
+}
+
+thread_local! {
+    /// Number of calls after which the hash functions start failing.
+    static CALLS_UNTIL_FAILURE : Cell<usize> = Cell::new(usize::MAX);
+    static CALLS : Cell<usize> = Cell::new(0);
+}
+
+fn fail_after(calls : usize) {
+    CALLS_UNTIL_FAILURE.with(|c| c.set(calls));
+    CALLS.with(|c| c.set(0));
+}
+
+fn count_call() -> Result<(), EnclaveError> {
+    let call = CALLS.with(|c| { c.set(c.get() + 1); c.get() });
+    if call > CALLS_UNTIL_FAILURE.with(Cell::get) { Err(EnclaveError(call)) } else { Ok(()) }
+}
+
+struct EnclaveMd5;
+struct EnclaveSha256;
+struct Unused16;
+struct Unused20;
+
+impl TryHasher for EnclaveMd5 {
+    type Output = [u8;16];
+    type Error = EnclaveError;
+    fn try_hash(input : &[u8]) -> Result<Self::Output, Self::Error> {
+        count_call().map(|_| md5::Md5::digest(input).into())
+    }
+}
+impl TryHasher for EnclaveSha256 {
+    type Output = [u8;32];
+    type Error = EnclaveError;
+    fn try_hash(input : &[u8]) -> Result<Self::Output, Self::Error> {
+        count_call().map(|_| sha2::Sha256::digest(input).into())
+    }
+}
+impl TryHasher for Unused16 {
+    type Output = [u8;16];
+    type Error = EnclaveError;
+    fn try_hash(_ : &[u8]) -> Result<Self::Output, Self::Error> { unimplemented!() }
+}
+impl TryHasher for Unused20 {
+    type Output = [u8;20];
+    type Error = EnclaveError;
+    fn try_hash(_ : &[u8]) -> Result<Self::Output, Self::Error> { unimplemented!() }
+}
+
+struct EnclaveHashers;
+impl TryHasherList for EnclaveHashers {
+    type Error = EnclaveError;
+    type MD4 = Unused16;
+    type MD5 = EnclaveMd5;
+    type SHA1 = Unused20;
+    type SHA256 = EnclaveSha256;
+    type RIPEMD160 = Unused20;
+}
+
+type FalliblePwm<'a> = PasswordMaker<'a, Fallible<EnclaveHashers>>;
+
+struct LocalMd5;
+struct LocalSha256;
+struct LocalUnused16;
+struct LocalUnused20;
+impl Hasher for LocalMd5 {
+    type Output = [u8;16];
+    fn hash(input : &[u8]) -> Self::Output { md5::Md5::digest(input).into() }
+}
+impl Hasher for LocalSha256 {
+    type Output = [u8;32];
+    fn hash(input : &[u8]) -> Self::Output { sha2::Sha256::digest(input).into() }
+}
+impl Hasher for LocalUnused16 {
+    type Output = [u8;16];
+    fn hash(_ : &[u8]) -> Self::Output { unimplemented!() }
+}
+impl Hasher for LocalUnused20 {
+    type Output = [u8;20];
+    fn hash(_ : &[u8]) -> Self::Output { unimplemented!() }
+}
+struct LocalHashers;
+impl HasherList for LocalHashers {
+    type MD4 = LocalUnused16;
+    type MD5 = LocalMd5;
+    type SHA1 = LocalUnused20;
+    type SHA256 = LocalSha256;
+    type RIPEMD160 = LocalUnused20;
+}
+
+const CHARACTERS : &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+
+#[test]
+fn try_generate_matches_generate(){
+    fail_after(usize::MAX);
+    for algorithm in &[HashAlgorithm::Md5, HashAlgorithm::HmacSha256, HashAlgorithm::Md5Version06, HashAlgorithm::HmacMd5Version06] {
+        let fallible = FalliblePwm::new(*algorithm, UseLeetWhenGenerating::NotAtAll, CHARACTERS, "user", "", 50, "", "").unwrap();
+        let local = PasswordMaker::<LocalHashers>::new(*algorithm, UseLeetWhenGenerating::NotAtAll, CHARACTERS, "user", "", 50, "", "").unwrap();
+        assert_eq!(fallible.try_generate("example.com", "password").unwrap(), local.generate("example.com", "password").unwrap());
+    }
+}
+
+#[test]
+fn try_generate_fails_on_second_hash(){
+    //MD5 with 94 characters yields at most 20 characters per hash, so 30 characters need two hashes.
+    let pwm = FalliblePwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, CHARACTERS, "", "", 30, "", "").unwrap();
+    fail_after(1);
+    assert!(matches!(pwm.try_generate("example.com", "password"), Err(TryGenerationError::Hasher(EnclaveError(2)))));
+    //No further calls after the failure.
+    assert_eq!(CALLS.with(Cell::get), 2);
+    //A single hash is enough for short passwords.
+    let short = FalliblePwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, CHARACTERS, "", "", 10, "", "").unwrap();
+    fail_after(1);
+    assert!(short.try_generate("example.com", "password").is_ok());
+    //The hasher works again.
+    fail_after(usize::MAX);
+    assert!(pwm.try_generate("example.com", "password").is_ok());
+}
+
+#[test]
+fn try_generate_reports_invalid_input(){
+    let pwm = FalliblePwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, CHARACTERS, "", "", 30, "", "").unwrap();
+    fail_after(0);
+    assert!(matches!(pwm.try_generate("example.com", ""), Err(TryGenerationError::Generation(GenerationError::MissingMasterPassword))));
+    assert_eq!(CALLS.with(Cell::get), 0);
+    assert_eq!(pwm.try_generate("example.com", "password").unwrap_err().to_string(), "Hashing failed: enclave unavailable at call 1");
+}
@@ -0,0 +1,126 @@
+//! Property based tests, that check invariants of password generation for randomized settings and inputs.
+//! The seed is fixed, so failures are reproducible. Proptest shrinks failing cases to a minimal example.
+
+use passwordmaker_rs::{PasswordMaker, Extended, ExtendedHasherList, Hasher, HasherList, HashAlgorithm, LeetLevel, UseLeetWhenGenerating};
+use digest::Digest;
+use proptest::prelude::*;
+use proptest::sample::select;
+use proptest::test_runner::{Config, RngSeed};
+use unicode_segmentation::UnicodeSegmentation;
+
+struct Md4;
+struct Md5;
+struct Sha1;
+struct Sha256;
+struct RipeMD160;
+struct RipeMD128;
+struct Tiger;
+impl Hasher for Md4{
+    type Output = [u8;16];
+    fn hash(data : &[u8]) -> Self::Output {
+        md4::Md4::digest(data).into()
+    }
+}
+impl Hasher for Md5{
+    type Output = [u8;16];
+    fn hash(data : &[u8]) -> Self::Output {
+        md5::Md5::digest(data).into()
+    }
+}
+impl Hasher for Sha1{
+    type Output = [u8;20];
+    fn hash(data : &[u8]) -> Self::Output {
+        sha1::Sha1::digest(data).into()
+    }
+}
+impl Hasher for Sha256{
+    type Output = [u8;32];
+    fn hash(data : &[u8]) -> Self::Output {
+        sha2::Sha256::digest(data).into()
+    }
+}
+impl Hasher for RipeMD160{
+    type Output = [u8;20];
+    fn hash(data : &[u8]) -> Self::Output {
+        ripemd::Ripemd160::digest(data).into()
+    }
+}
+impl Hasher for RipeMD128{
+    type Output = [u8;16];
+    fn hash(data : &[u8]) -> Self::Output {
+        ripemd::Ripemd128::digest(data).into()
+    }
+}
+impl Hasher for Tiger{
+    type Output = [u8;24];
+    fn hash(data : &[u8]) -> Self::Output {
+        tiger::Tiger::digest(data).into()
+    }
+}
+
+struct Hashes{}
+impl HasherList for Hashes {
+    type MD4 = Md4;
+    type MD5 = Md5;
+    type SHA1 = Sha1;
+    type SHA256 = Sha256;
+    type RIPEMD160 = RipeMD160;
+}
+impl ExtendedHasherList for Hashes {
+    type RIPEMD128 = RipeMD128;
+    type TIGER = Tiger;
+}
+
+type Pwm<'a> = PasswordMaker<'a, Extended<Hashes>>;
+
+/// Grapheme clusters that stay separate when concatenated in any order, and that leet's lower-casing doesn't merge or split.
+/// Counting the grapheme clusters of the output is only meaningful if this holds.
+const GRAPHEMES : &[&str] = &[
+    "a", "b", "c", "x", "y", "z", "A", "B", "M", "S", "W", "0", "1", "7", "9", "!", "#", "/", "\\", "|", "'", " ",
+    "é", "ß", "Σ", "€", "e\u{301}", "😀", "👍🏽",
+];
+
+fn graphemes(count : std::ops::Range<usize>) -> impl Strategy<Value = String> {
+    prop::collection::vec(select(GRAPHEMES), count).prop_map(|g| g.concat())
+}
+
+fn use_leet() -> impl Strategy<Value = UseLeetWhenGenerating> {
+    let level = select(&[
+        LeetLevel::One, LeetLevel::Two, LeetLevel::Three, LeetLevel::Four, LeetLevel::Five,
+        LeetLevel::Six, LeetLevel::Seven, LeetLevel::Eight, LeetLevel::Nine,
+    ][..]);
+    prop_oneof![
+        Just(UseLeetWhenGenerating::NotAtAll),
+        level.clone().prop_map(|level| UseLeetWhenGenerating::Before { level }),
+        level.clone().prop_map(|level| UseLeetWhenGenerating::After { level }),
+        level.prop_map(|level| UseLeetWhenGenerating::BeforeAndAfter { level }),
+    ]
+}
+
+//With a fixed seed there is no need to store failing cases in a file. Proptest prints the minimal failing input.
+fn config() -> Config {
+    Config { rng_seed : RngSeed::Fixed(0x5eed_5eed_5eed_5eed), failure_persistence : None, ..Config::default() }
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn password_has_requested_length(
+        algorithm in select(HashAlgorithm::all()),
+        use_leet in use_leet(),
+        characters in graphemes(2..20),
+        password_length in 0usize..150,
+        prefix in graphemes(0..10),
+        suffix in graphemes(0..10),
+        username in ".{0,10}",
+        modifier in ".{0,10}",
+        data in ".{1,20}",
+        key in ".{1,20}",
+    ) {
+        let pwm = Pwm::new(algorithm, use_leet, &characters, &username, &modifier, password_length, &prefix, &suffix).unwrap();
+        let password = pwm.generate(data, key).unwrap();
+        //Prefix and suffix are cut if they don't fit, so the length is always exactly the requested one.
+        prop_assert_eq!(password.graphemes(true).count(), password_length);
+    }
+}
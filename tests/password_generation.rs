@@ -1,16 +1,13 @@
-use passwordmaker_rs::{PasswordMaker, Hasher, HasherList, HashAlgorithm, LeetLevel};
+use passwordmaker_rs::{PasswordMaker, Extended, ExtendedHasherList, Hasher, HasherList, HashAlgorithm, LeetLevel, SettingsError};
 use digest::Digest;
-use md4;
-use md5;
-use sha1;
-use sha2;
-use ripemd;
 
 struct Md4;
 struct Md5;
 struct Sha1;
 struct Sha256;
 struct RipeMD160;
+struct RipeMD128;
+struct Tiger;
 impl Hasher for Md4{
     type Output = [u8;16];
     fn hash(data : &[u8]) -> Self::Output {
@@ -42,11 +39,19 @@ impl Hasher for RipeMD160{
     }
 }
 
-impl passwordmaker_rs::Md4 for Md4{}
-impl passwordmaker_rs::Md5 for Md5{}
-impl passwordmaker_rs::Sha1 for Sha1{}
-impl passwordmaker_rs::Sha256 for Sha256{}
-impl passwordmaker_rs::Ripemd160 for RipeMD160{}
+impl Hasher for RipeMD128{
+    type Output = [u8;16];
+    fn hash(data : &[u8]) -> Self::Output {
+        ripemd::Ripemd128::digest(data).into()
+    }
+}
+impl Hasher for Tiger{
+    type Output = [u8;24];
+    fn hash(data : &[u8]) -> Self::Output {
+        tiger::Tiger::digest(data).into()
+    }
+}
+
 
 struct Hashes{}
 impl HasherList for Hashes {
@@ -56,8 +61,12 @@ impl HasherList for Hashes {
     type SHA256 = Sha256;
     type RIPEMD160 = RipeMD160;
 }
+impl ExtendedHasherList for Hashes {
+    type RIPEMD128 = RipeMD128;
+    type TIGER = Tiger;
+}
 
-type Pwm<'a> = PasswordMaker<'a, Hashes>;
+type Pwm<'a> = PasswordMaker<'a, Extended<Hashes>>;
 
 #[test]
 fn default_settings() {
@@ -172,6 +181,23 @@ fn v06_yeet_bytes_hmac() {
     assert_eq!(result, "28e1392052364d34c7e42e2711ccdd62c67a0a30dbf568a");
 }
 
+#[test]
+fn v06_multiple_parts() {
+    for algo in &[HashAlgorithm::Md5Version06, HashAlgorithm::HmacMd5Version06] {
+        let pwm = |length| Pwm::new(*algo, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "notused", "", "", length, "", "").unwrap();
+        let single = pwm(32).generate("example.com", "password").unwrap();
+        let second = pwm(32).generate("example.com", "password\n1").unwrap();
+        assert_eq!(single.len(), 32);
+        assert_eq!(pwm(33).generate("example.com", "password").unwrap(), single.clone() + &second[..1]);
+        assert_eq!(pwm(64).generate("example.com", "password").unwrap(), single + &second);
+        assert_eq!(pwm(64).estimated_parts(), 2);
+        assert_eq!(pwm(65).estimated_parts(), 3);
+    }
+    //md5("passwordexample.com") followed by md5("password\n1example.com"), same as PasswordMaker Pro.
+    let pwm = Pwm::new(HashAlgorithm::Md5Version06, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "notused", "", "", 64, "", "").unwrap();
+    assert_eq!(pwm.generate("example.com", "password").unwrap(), "586e89abb63de1a585072e6ed434885d7ac60d389a1846a292cfcdcb95c61907");
+}
+
 #[test]
 fn test_each_algo_md4(){
     let pwm = Pwm::new(
@@ -440,4 +466,1749 @@ fn test_very_large_character_set(){
         ".0123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789öä@€Whatever".to_owned(), 
         "0123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789".to_owned()).unwrap();
     assert_eq!(result, r#"preF.º„ĸsj®³5⅜±←|ö←U1Fh~`€ſµ½ẞ5öi6:¯—#öŁ#Oö—ſkª“/[§Ŋ↓½`'Bu:″¯suf"#);
-}
\ No newline at end of file
+}
+#[test]
+fn trace_single_part(){
+    let pwm = Pwm::new(
+        HashAlgorithm::Md5, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./",
+        "",
+        "",
+        8,
+        "",
+        ""
+    ).unwrap();
+    let (result, trace) = pwm.generate_with_trace(".abcdefghij".to_owned(), "1".to_owned()).unwrap();
+    assert_eq!(result, "J3>'1F\"/");
+    assert_eq!(trace.parts().len(), 1);
+    let part = &trace.parts()[0];
+    assert_eq!(part.hmac_key(), None);
+    assert_eq!(part.hasher_input(), b"1.abcdefghij");
+    assert_eq!(part.hash(), &Md5::hash(b"1.abcdefghij"));
+    assert_eq!(&part.digits()[..3], &[9, 55, 89]);
+}
+
+#[test]
+fn trace_multiple_parts_hmac(){
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacSha1, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "0123456789",
+        "user",
+        "",
+        64,
+        "",
+        ""
+    ).unwrap();
+    let (result, trace) = pwm.generate_with_trace("example.com".to_owned(), "password".to_owned()).unwrap();
+    assert_eq!(result, pwm.generate("example.com".to_owned(), "password".to_owned()).unwrap());
+    assert_eq!(trace.parts().len(), 2);
+    assert_eq!(trace.parts()[0].hmac_key(), Some(&b"password"[..]));
+    assert_eq!(trace.parts()[1].hmac_key(), Some(&b"password\n1"[..]));
+    assert_eq!(trace.parts()[1].hasher_input(), b"example.comuser");
+    let digits = trace.parts().iter().flat_map(|p| p.digits()).map(|d| d.to_string()).collect::<String>();
+    assert!(digits.starts_with(&result));
+}
+
+#[test]
+fn ripemd_128_reference_vectors(){
+    assert_eq!(RipeMD128::hash(b""), [0xcd,0xf2,0x62,0x13,0xa1,0x50,0xdc,0x3e,0xcb,0x61,0x0f,0x18,0xf6,0xb3,0x8b,0x46]);
+    assert_eq!(RipeMD128::hash(b"abc"), [0xc1,0x4a,0x12,0x19,0x9c,0x66,0xe4,0xba,0x84,0x63,0x6b,0x0f,0x69,0x14,0x4c,0x77]);
+}
+
+#[test]
+fn tiger_reference_vectors(){
+    assert_eq!(Tiger::hash(b""), [0x32,0x93,0xac,0x63,0x0c,0x13,0xf0,0x24,0x5f,0x92,0xbb,0xb1,0x76,0x6e,0x16,0x16,0x7a,0x4e,0x58,0x49,0x2d,0xde,0x73,0xf3]);
+    assert_eq!(Tiger::hash(b"abc"), [0x2a,0xab,0x14,0x84,0xe8,0xc1,0x58,0xf2,0xbf,0xb8,0xc5,0xff,0x41,0xb5,0x7a,0x52,0x51,0x29,0x13,0x1c,0x95,0x7b,0x5f,0x93]);
+}
+
+/// HMAC as in RFC 2104, with the 64 byte block size all supported hash functions share.
+fn reference_hmac<D : Digest>(key : &[u8], data : &[u8]) -> Vec<u8> {
+    let mut block = [0u8;64];
+    if key.len() > block.len() {
+        let digest = D::digest(key);
+        block[..digest.len()].copy_from_slice(&digest);
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |value : u8| block.iter().map(|byte| byte ^ value).collect::<Vec<u8>>();
+    let inner = D::new().chain_update(pad(0x36)).chain_update(data).finalize();
+    D::new().chain_update(pad(0x5c)).chain_update(inner).finalize().to_vec()
+}
+
+/// The hash of `key` followed by `data`, what PasswordMaker Pro uses for the non-HMAC algorithms.
+fn reference_hash<D : Digest>(key : &[u8], data : &[u8]) -> Vec<u8> {
+    D::new().chain_update(key).chain_update(data).finalize().to_vec()
+}
+
+/// PasswordMaker Pro's password generation, written down as plainly as possible: Each hash is converted to base
+/// `characters.len()` without leading zeros, starting with the second part `"\n"` and the part number are appended to the key.
+fn reference_password<F : Fn(&[u8], &[u8]) -> Vec<u8>>(hash : F, data : &str, key : &str, characters : &str, length : usize) -> String {
+    use num_bigint::BigUint;
+    let characters : Vec<char> = characters.chars().collect();
+    let base = BigUint::from(characters.len());
+    let mut password : Vec<char> = Vec::new();
+    for part in 0.. {
+        if password.len() >= length {
+            break;
+        }
+        let key = if part == 0 { key.to_owned() } else { format!("{}\n{}", key, part) };
+        let mut value = BigUint::from_bytes_be(&hash(key.as_bytes(), data.as_bytes()));
+        let mut digits = Vec::new();
+        while value.bits() != 0 {
+            digits.push(characters[(&value % &base).to_u32_digits().first().copied().unwrap_or(0) as usize]);
+            value /= &base;
+        }
+        password.extend(digits.iter().rev());
+    }
+    password.iter().take(length).collect()
+}
+
+#[test]
+fn tiger_matches_reference_generation(){
+    use passwordmaker_rs::UseLeetWhenGenerating;
+    let characters = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+    let long_key = "0123456789".repeat(10);
+    for key in &["password", long_key.as_str()] {
+        let tiger = Pwm::new(HashAlgorithm::Tiger, UseLeetWhenGenerating::NotAtAll, characters, "", "", 70, "", "").unwrap();
+        let expected = reference_password(reference_hash::<tiger::Tiger>, "example.com", key, characters, 70);
+        assert_eq!(tiger.generate("example.com", *key).unwrap(), expected);
+        let hmac_tiger = Pwm::new(HashAlgorithm::HmacTiger, UseLeetWhenGenerating::NotAtAll, characters, "", "", 70, "", "").unwrap();
+        let expected = reference_password(reference_hmac::<tiger::Tiger>, "example.com", key, characters, 70);
+        assert_eq!(hmac_tiger.generate("example.com", *key).unwrap(), expected);
+    }
+}
+
+#[test]
+fn plain_hasher_list_rejects_extended_algorithms(){
+    use passwordmaker_rs::{GenerationError, UseLeetWhenGenerating};
+    for algorithm in &[HashAlgorithm::Ripemd128, HashAlgorithm::HmacRipemd128, HashAlgorithm::Tiger, HashAlgorithm::HmacTiger] {
+        let pwm = PasswordMaker::<Hashes>::new(*algorithm, UseLeetWhenGenerating::NotAtAll, "abcdefghij", "", "", 8, "", "").unwrap();
+        assert!(matches!(pwm.generate("example.com", "password"), Err(GenerationError::UnavailableAlgorithm)));
+        assert!(matches!(pwm.validate_all("example.com", "password").unwrap_err().as_slice(), [GenerationError::UnavailableAlgorithm]));
+    }
+    let md5 = PasswordMaker::<Hashes>::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdefghij", "", "", 8, "", "").unwrap();
+    assert!(md5.generate("example.com", "password").is_ok());
+}
+
+#[test]
+fn test_each_algo_ripemd_128(){
+    let pwm = Pwm::new(
+        HashAlgorithm::Ripemd128, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "0123456789abcdef",
+        "",
+        "",
+        32,
+        "",
+        ""
+    ).unwrap();
+    let result = pwm.generate("bc".to_owned(), "a".to_owned()).unwrap();
+    assert_eq!(result, "c14a12199c66e4ba84636b0f69144c77");
+}
+
+#[test]
+fn test_each_algo_hmac_ripemd_128(){
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacRipemd128, 
+        passwordmaker_rs::UseLeetWhenGenerating::Before { level: LeetLevel::Nine },
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "max_mustermann",
+        "modification",
+        64,
+        "pre",
+        "suf"
+    ).unwrap();
+    let result = pwm.generate(
+        ".0123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789öä@€Whatever".to_owned(), 
+        "0123456789012345678901234567890123456789012345678901234567890123456789012345678901234567890123456789".to_owned()).unwrap();
+    assert_eq!(result, "preCSAJLqdWSKaYbMW4aE9x2sFP40EbWzslbUybe6bp9UTmGAFJx7Y0Xt9iG7suf");
+}
+
+#[test]
+fn test_each_algo_tiger(){
+    let pwm = Pwm::new(
+        HashAlgorithm::Tiger, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./",
+        "",
+        "",
+        29,
+        "",
+        ""
+    ).unwrap();
+    let result = pwm.generate("bc".to_owned(), "a".to_owned()).unwrap();
+    assert_eq!(result, "7PXi$FEY2sSKe0stw,Sr7/#9#R\\D1");
+}
+
+#[test]
+fn generate_nth_zero_is_generate(){
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacSha256, 
+        passwordmaker_rs::UseLeetWhenGenerating::BeforeAndAfter { level: LeetLevel::Three },
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "max_mustermann",
+        "modification",
+        150,
+        "pre",
+        "suf"
+    ).unwrap();
+    let nth = pwm.generate_nth("example.com".to_owned(), "password".to_owned(), 0).unwrap();
+    let result = pwm.generate("example.com".to_owned(), "password".to_owned()).unwrap();
+    assert_eq!(nth, result);
+}
+
+/// V0.6 password parts always have 32 characters, what makes it easy to check which key was used for which part.
+#[test]
+fn generate_nth_three(){
+    let single_part = Pwm::new(
+        HashAlgorithm::Md5Version06, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "unused",
+        "",
+        "",
+        32,
+        "",
+        ""
+    ).unwrap();
+    let two_parts = Pwm::new(
+        HashAlgorithm::Md5Version06, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "unused",
+        "",
+        "",
+        64,
+        "",
+        ""
+    ).unwrap();
+    let result = two_parts.generate_nth("example.com".to_owned(), "password".to_owned(), 3).unwrap();
+    let third = single_part.generate("example.com".to_owned(), "password\n3".to_owned()).unwrap();
+    let fourth = single_part.generate("example.com".to_owned(), "password\n4".to_owned()).unwrap();
+    assert_eq!(result, third + &fourth);
+    assert_ne!(result, two_parts.generate("example.com".to_owned(), "password".to_owned()).unwrap());
+}
+
+#[test]
+fn insufficient_charset_reports_count(){
+    //e + combining acute accent is a single grapheme cluster.
+    let result = Pwm::new(
+        HashAlgorithm::Md5, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "e\u{301}",
+        "",
+        "",
+        8,
+        "",
+        ""
+    );
+    assert!(matches!(result, Err(SettingsError::InsufficientCharset { found : 1 })));
+    let result = Pwm::new(HashAlgorithm::Md5, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "", "", "", 8, "", "");
+    assert!(matches!(result, Err(SettingsError::InsufficientCharset { found : 0 })));
+}
+
+#[test]
+fn settings_fingerprint_changes_with_every_setting(){
+    use passwordmaker_rs::UseLeetWhenGenerating;
+    let charset = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    let make = |algo, leet, characters, username, modifier, length, prefix, suffix| 
+        Pwm::new(algo, leet, characters, username, modifier, length, prefix, suffix).unwrap().settings_fingerprint();
+    let base = make(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, charset, "user", "mod", 12, "pre", "suf");
+    assert_eq!(base, make(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, charset, "user", "mod", 12, "pre", "suf"));
+    let variants = [
+        make(HashAlgorithm::HmacMd5, UseLeetWhenGenerating::NotAtAll, charset, "user", "mod", 12, "pre", "suf"),
+        make(HashAlgorithm::Md5, UseLeetWhenGenerating::Before { level: LeetLevel::One }, charset, "user", "mod", 12, "pre", "suf"),
+        make(HashAlgorithm::Md5, UseLeetWhenGenerating::After { level: LeetLevel::One }, charset, "user", "mod", 12, "pre", "suf"),
+        make(HashAlgorithm::Md5, UseLeetWhenGenerating::After { level: LeetLevel::Two }, charset, "user", "mod", 12, "pre", "suf"),
+        make(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "0123456789", "user", "mod", 12, "pre", "suf"),
+        make(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, charset, "someone", "mod", 12, "pre", "suf"),
+        make(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, charset, "user", "", 12, "pre", "suf"),
+        make(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, charset, "user", "mod", 13, "pre", "suf"),
+        make(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, charset, "user", "mod", 12, "pres", "uf"),
+        make(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, charset, "user", "mod", 12, "pre", "su"),
+    ];
+    for (i, variant) in variants.iter().enumerate() {
+        assert_ne!(base, *variant, "Variant {} has the same fingerprint as the base settings.", i);
+    }
+}
+
+#[test]
+fn generate_batch_password_and_pin(){
+    use passwordmaker_rs::{UseLeetWhenGenerating, OutputSpec};
+    let charset = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+    let make = |characters, length, prefix, suffix| 
+        Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::After { level: LeetLevel::Three }, characters, "user", "mod", length, prefix, suffix).unwrap();
+    let pwm = make(charset, 8, "", "");
+    let specs = [
+        OutputSpec::new(charset, 80, "pre", "").unwrap(),
+        OutputSpec::new("0123456789", 6, "", "").unwrap(),
+    ];
+    let result = pwm.generate_batch("example.com".to_owned(), "password".to_owned(), &specs).unwrap();
+    let password = make(charset, 80, "pre", "").generate("example.com".to_owned(), "password".to_owned()).unwrap();
+    let pin = make("0123456789", 6, "", "").generate("example.com".to_owned(), "password".to_owned()).unwrap();
+    assert_eq!(result, vec![password, pin]);
+    let reversed = pwm.generate_batch("example.com".to_owned(), "password".to_owned(), &[specs[1], specs[0]]).unwrap();
+    assert_eq!(reversed, vec![result[1].clone(), result[0].clone()]);
+    assert!(matches!(OutputSpec::new("0", 6, "", ""), Err(SettingsError::InsufficientCharset { found : 1 })));
+}
+
+#[test]
+fn cloned_maker_generates_same_passwords(){
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacRipemd160, 
+        passwordmaker_rs::UseLeetWhenGenerating::BeforeAndAfter { level: LeetLevel::Five },
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "user",
+        "mod",
+        40,
+        "pre",
+        "suf"
+    ).unwrap();
+    let cloned = pwm.clone();
+    assert_eq!(pwm.settings_fingerprint(), cloned.settings_fingerprint());
+    for n in 0..3 {
+        assert_eq!(
+            pwm.generate_nth("example.com", "password", n).unwrap(),
+            cloned.generate_nth("example.com", "password", n).unwrap()
+        );
+    }
+}
+
+#[test]
+fn owned_maker_is_static(){
+    use passwordmaker_rs::UseLeetWhenGenerating;
+    fn make_owned() -> Pwm<'static> {
+        let characters = String::from("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789");
+        Pwm::new_owned(
+            HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, characters, "user".to_owned(), "mod".to_owned(), 20, "pre".to_owned(), "suf".to_owned()
+        ).unwrap()
+    }
+    let owned = make_owned();
+    let from_thread = std::thread::spawn(move || owned.generate("example.com", "password").unwrap()).join().unwrap();
+    let borrowed = Pwm::new(
+        HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789", "user", "mod", 20, "pre", "suf"
+    ).unwrap();
+    assert_eq!(from_thread, borrowed.generate("example.com", "password").unwrap());
+    assert_eq!(make_owned().settings_fingerprint(), borrowed.settings_fingerprint());
+}
+
+#[test]
+fn password_maker_is_send_and_sync(){
+    fn assert_send_sync<T : Send + Sync>() {}
+    assert_send_sync::<Pwm<'static>>();
+    assert_send_sync::<passwordmaker_rs::UrlParsing>();
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn generate_parallel_matches_generate(){
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacSha1, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "user",
+        "",
+        30,
+        "",
+        ""
+    ).unwrap();
+    let inputs : Vec<(String, String)> = (0..50).map(|i| (format!("site{}.example.com", i), "password".to_owned())).collect();
+    let mut inputs_with_error = inputs.clone();
+    inputs_with_error.push(("example.com".to_owned(), String::new()));
+    let result = pwm.generate_parallel(&inputs_with_error);
+    assert_eq!(result.len(), inputs_with_error.len());
+    for ((data, key), password) in inputs.iter().zip(&result) {
+        assert_eq!(password.as_ref().unwrap(), &pwm.generate(data.as_str(), key.as_str()).unwrap());
+    }
+    assert!(matches!(result.last(), Some(Err(passwordmaker_rs::GenerationError::MissingMasterPassword))));
+}
+
+#[test]
+fn generate_indices_map_to_generate(){
+    use unicode_segmentation::UnicodeSegmentation;
+    let characters = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789äöü";
+    let graphemes : Vec<&str> = characters.graphemes(true).collect();
+    for algo in &[HashAlgorithm::Md5, HashAlgorithm::HmacSha256, HashAlgorithm::Sha1] {
+        let pwm = Pwm::new(*algo, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, characters, "user", "mod", 70, "pre", "suf").unwrap();
+        let indices = pwm.generate_indices("abc", "abc").unwrap();
+        assert_eq!(indices.len(), 64);
+        let mapped : String = indices.iter().map(|i| graphemes[*i]).collect();
+        assert_eq!("pre".to_owned() + &mapped + "suf", pwm.generate("abc", "abc").unwrap());
+    }
+}
+
+#[test]
+fn map_indices_round_trip(){
+    use passwordmaker_rs::{map_indices, MapError, Settings};
+    let settings = Settings::default();
+    let pwm = settings.password_maker::<Hashes>();
+    let characters = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+    let indices = pwm.generate_indices("example.com", "password").unwrap();
+    assert_eq!(map_indices(&indices, characters).unwrap(), pwm.generate("example.com", "password").unwrap());
+    let mut out_of_range = indices.clone();
+    out_of_range[3] = 94;
+    assert_eq!(map_indices(&out_of_range, characters), Err(MapError::IndexOutOfRange { position : 3, index : 94, charset_length : 94 }));
+    assert_eq!(map_indices(&[], characters), Ok(String::new()));
+}
+
+#[test]
+#[cfg(feature = "unicode-normalization")]
+fn unicode_normalization_unifies_precomposed_and_decomposed(){
+    use passwordmaker_rs::NormalizationForm;
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacSha256, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "",
+        "",
+        20,
+        "",
+        ""
+    ).unwrap();
+    let precomposed = "caf\u{e9}";
+    let decomposed = "cafe\u{301}";
+    assert_ne!(pwm.generate("example.com", precomposed).unwrap(), pwm.generate("example.com", decomposed).unwrap());
+    let fingerprint = pwm.settings_fingerprint();
+    let pwm = pwm.with_unicode_normalization(Some(NormalizationForm::Nfc));
+    assert_ne!(fingerprint, pwm.settings_fingerprint());
+    let expected = pwm.generate("example.com", precomposed).unwrap();
+    assert_eq!(pwm.generate("example.com", decomposed).unwrap(), expected);
+    assert_eq!(pwm.generate("ex\u{e4}mple.com", "password").unwrap(), pwm.generate("exa\u{308}mple.com", "password").unwrap());
+}
+
+#[test]
+fn generate_from_url_equals_parse_then_generate(){
+    use passwordmaker_rs::{UrlParsing, ProtocolUsageMode};
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacMd5, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "user",
+        "",
+        16,
+        "",
+        ""
+    ).unwrap();
+    let url_settings = UrlParsing::new(ProtocolUsageMode::Used, false, true, true, true);
+    let url = "https://jane@www.example.com:8080/some/path?query";
+    let manual = pwm.generate(url_settings.parse(url), "password").unwrap();
+    assert_eq!(pwm.generate_from_url(&url_settings, url, "password").unwrap(), manual);
+    let domain_only = UrlParsing::new(ProtocolUsageMode::Ignored, false, false, true, false);
+    assert!(matches!(pwm.generate_from_url(&domain_only, "/just/a/path", "password"), Err(passwordmaker_rs::GenerationError::MissingTextToUse)));
+}
+
+#[test]
+fn generate_for_urls_matches_generate_from_url(){
+    use passwordmaker_rs::Settings;
+    use std::collections::BTreeMap;
+    let map : BTreeMap<String, String> = [("algorithm", "hmacsha256"), ("length", "20"), ("username", "user"), ("subdomains", "true")].iter()
+        .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+        .collect();
+    let settings = Settings::from_kv(&map).unwrap();
+    let pwm = settings.password_maker::<Hashes>();
+    let urls = [
+        "https://www.example.com/login",
+        "http://mail.example.org:8080/inbox?folder=1",
+        "example.net",
+        "ftp://files.example.com/",
+        "/just/a/path",
+    ];
+    let passwords = pwm.generate_for_urls(settings.url_parsing(), "password", &urls);
+    assert_eq!(passwords.len(), urls.len());
+    for (url, password) in urls.iter().zip(&passwords) {
+        assert_eq!(password.as_ref().ok(), pwm.generate_from_url(settings.url_parsing(), url, "password").as_ref().ok(), "{}", url);
+    }
+    assert!(matches!(passwords[4], Err(passwordmaker_rs::GenerationError::MissingTextToUse)));
+    assert_ne!(passwords[0].as_ref().unwrap(), passwords[1].as_ref().unwrap());
+}
+
+#[test]
+fn post_leet_fallback_only_if_leet_expands_a_lot(){
+    use passwordmaker_rs::UseLeetWhenGenerating;
+    let make = |characters, leet| Pwm::new(HashAlgorithm::Md5, leet, characters, "", "", 6, "", "").unwrap();
+    //level 9 replaces m by 4 and w by 3 characters.
+    let expanding = make("mw", UseLeetWhenGenerating::After { level: LeetLevel::Nine });
+    let leeted = expanding.generate("example.com", "password").unwrap();
+    assert!(leeted.contains('/') || leeted.contains('\\'));
+    let fallback = expanding.clone().with_post_leet_fallback(true).generate("example.com", "password").unwrap();
+    assert_eq!(fallback, make("mw", UseLeetWhenGenerating::NotAtAll).generate("example.com", "password").unwrap());
+    assert_ne!(expanding.settings_fingerprint(), expanding.clone().with_post_leet_fallback(true).settings_fingerprint());
+    //level 1 replaces a by 4, and keeps b. No expansion, so no fallback.
+    let not_expanding = make("ab", UseLeetWhenGenerating::After { level: LeetLevel::One });
+    assert_eq!(
+        not_expanding.clone().with_post_leet_fallback(true).generate("example.com", "password").unwrap(),
+        not_expanding.generate("example.com", "password").unwrap()
+    );
+}
+
+#[test]
+fn prepared_charset_equals_new(){
+    use passwordmaker_rs::{PreparedCharset, UseLeetWhenGenerating};
+    let characters = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789e\u{301}";
+    let prepared = PreparedCharset::new(characters).unwrap();
+    assert_eq!(prepared.as_str(), characters);
+    for site in &["example.com", "example.org"] {
+        let from_prepared = Pwm::new_with_prepared_charset(HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, &prepared, "user", "", 30, "", "");
+        let from_str = Pwm::new(HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, characters, "user", "", 30, "", "").unwrap();
+        assert_eq!(from_prepared.generate(*site, "password").unwrap(), from_str.generate(*site, "password").unwrap());
+        assert_eq!(from_prepared.settings_fingerprint(), from_str.settings_fingerprint());
+    }
+    assert!(matches!(PreparedCharset::new("e\u{301}"), Err(SettingsError::InsufficientCharset { found : 1 })));
+}
+
+#[test]
+fn prepared_charset_from_symbols(){
+    use passwordmaker_rs::{PreparedCharset, UseLeetWhenGenerating};
+    //As a string, e followed by a combining accent is a single grapheme cluster. As symbols, they are separate output characters.
+    let symbols = ["a", "e", "\u{301}", "x"];
+    let from_symbols = PreparedCharset::from_symbols(&symbols).unwrap();
+    let from_string = PreparedCharset::new(symbols.concat()).unwrap();
+    assert_eq!(from_symbols.as_str(), from_string.as_str());
+    let symbol_pwm = Pwm::new_with_prepared_charset(HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, &from_symbols, "", "", 20, "", "");
+    let string_pwm = Pwm::new_with_prepared_charset(HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, &from_string, "", "", 20, "", "");
+    assert_ne!(symbol_pwm.generate("example.com", "password").unwrap(), string_pwm.generate("example.com", "password").unwrap());
+    assert_ne!(symbol_pwm.settings_fingerprint(), string_pwm.settings_fingerprint());
+    //Each symbol is a single char here, so the password has exactly 20 of them, even if a combining accent follows an e.
+    assert_eq!(symbol_pwm.generate("example.com", "password").unwrap().chars().count(), 20);
+
+    //For symbols that are single grapheme clusters anyhow, there is no difference. Also works with owned strings.
+    let owned_symbols : Vec<String> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789".chars().map(String::from).collect();
+    let from_owned = PreparedCharset::from_symbols(&owned_symbols).unwrap();
+    let symbol_pwm = Pwm::new_with_prepared_charset(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, &from_owned, "", "", 20, "", "");
+    let string_pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789", "", "", 20, "", "").unwrap();
+    assert_eq!(symbol_pwm.generate("example.com", "password").unwrap(), string_pwm.generate("example.com", "password").unwrap());
+    assert_eq!(symbol_pwm.settings_fingerprint(), string_pwm.settings_fingerprint());
+
+    assert!(matches!(PreparedCharset::from_symbols(&["ab"]), Err(SettingsError::InsufficientCharset { found : 1 })));
+    assert!(matches!(PreparedCharset::from_symbols(&["", "a", ""]), Err(SettingsError::InsufficientCharset { found : 1 })));
+    assert!(matches!(PreparedCharset::from_symbols::<&str>(&[]), Err(SettingsError::InsufficientCharset { found : 0 })));
+}
+
+#[test]
+fn prepared_charset_symbols(){
+    use passwordmaker_rs::{map_indices, PreparedCharset, UseLeetWhenGenerating};
+    let from_str = PreparedCharset::new("ab👍🏽e\u{301}").unwrap();
+    assert_eq!(from_str.symbols().collect::<Vec<_>>(), ["a", "b", "👍🏽", "e\u{301}"]);
+    assert_eq!(from_str.symbol_count(), 4);
+    let from_symbols = PreparedCharset::from_symbols(&["a", "", "b", "👍🏽", "e", "\u{301}"]).unwrap();
+    assert_eq!(from_symbols.symbols().collect::<Vec<_>>(), ["a", "b", "👍🏽", "e", "\u{301}"]);
+    assert_eq!(from_symbols.symbol_count(), 5);
+    //The symbols are what generation selects from.
+    for charset in &[from_str, from_symbols] {
+        let pwm = Pwm::new_with_prepared_charset(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, charset, "", "", 20, "", "");
+        assert_eq!(pwm.charset_len(), charset.symbol_count());
+        let password = pwm.generate("example.com", "password").unwrap();
+        let symbols : Vec<&str> = charset.symbols().collect();
+        let mapped : String = pwm.generate_indices("example.com", "password").unwrap().iter().map(|index| symbols[*index]).collect();
+        assert_eq!(mapped, password);
+    }
+    //For charsets made of grapheme clusters, the symbols match map_indices.
+    let charset = PreparedCharset::new("ab👍🏽e\u{301}").unwrap();
+    let pwm = Pwm::new_with_prepared_charset(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, &charset, "", "", 20, "", "");
+    assert_eq!(map_indices(&pwm.generate_indices("example.com", "password").unwrap(), charset.as_str()).unwrap(), pwm.generate("example.com", "password").unwrap());
+}
+
+#[test]
+fn settings_enums_compare_by_value(){
+    use passwordmaker_rs::{UseLeetWhenGenerating, ProtocolUsageMode};
+    use std::collections::HashSet;
+    assert_eq!(HashAlgorithm::HmacSha256, HashAlgorithm::HmacSha256);
+    assert_ne!(HashAlgorithm::Sha256, HashAlgorithm::HmacSha256);
+    assert_eq!(UseLeetWhenGenerating::After { level : LeetLevel::Three }, UseLeetWhenGenerating::After { level : LeetLevel::Three });
+    assert_ne!(UseLeetWhenGenerating::After { level : LeetLevel::Three }, UseLeetWhenGenerating::Before { level : LeetLevel::Three });
+    assert_ne!(UseLeetWhenGenerating::After { level : LeetLevel::Three }, UseLeetWhenGenerating::After { level : LeetLevel::Four });
+    assert_ne!(ProtocolUsageMode::Used, ProtocolUsageMode::UsedWithUndefinedIfEmpty);
+    let algorithms : HashSet<_> = HashAlgorithm::all().iter().chain(HashAlgorithm::all()).copied().collect();
+    assert_eq!(algorithms.len(), HashAlgorithm::all().len());
+}
+
+/// Minimal inputs: A single byte each for `data` and `key`. For each algorithm there are two vectors: `data = "a"`, `key = "b"`,
+/// and a pair of single characters for which the first hash starts with a zero byte. The latter yields fewer digits in the
+/// first password part (but zero-padded hex digits for the V0.6 algorithms).
+/// PasswordMaker Pro's default charset and a length of 30 are used. The other algorithms need more than one password part for
+/// that, the V0.6 algorithms fit it into the 32 hex digits of a single part.
+/// 
+/// These are not outputs of PasswordMaker Pro. The expected values were computed with `reference_password` and
+/// `reference_v06_password`, directly on the RustCrypto hash functions, and `minimal_inputs` checks them against those
+/// helpers again. This covers RIPEMD-128 and Tiger, which PasswordMaker Pro does not have.
+const MINIMAL_INPUT_VECTORS : &[(HashAlgorithm, &str, &str, &str)] = &[
+    (HashAlgorithm::Md4, "a", "b", "Ke0y`KI69CM-&[l&g3AGHY_VM`y{w,"),
+    (HashAlgorithm::Md4, "q", "0", "BYw?=.N?9/MJMX`.CJoFFNsOc.!~]n"),
+    (HashAlgorithm::HmacMd4, "a", "b", "J]1{^X9~)~p_dI9t@0b4HjC9WD@xKM"),
+    (HashAlgorithm::HmacMd4, "0", "x", "32hH,:~)?z]50CR)gKKJkz<fct\"(}-"),
+    (HashAlgorithm::Md5, "a", "b", "c!F\"`|2W>sNP;-M_{UkD[drz(-=#*}"),
+    (HashAlgorithm::Md5, "e", "y", "DM.%a[qi5(fgxb:U7zzDm?'XfZ\\+5Z"),
+    (HashAlgorithm::HmacMd5, "a", "b", "EGWnt7L9Q'KH:H7z?Qb2B7hcxdEwRW"),
+    (HashAlgorithm::HmacMd5, "4", "5", "C0z/z?2sIHc3?{rM^+gH+\"e|4l\"#?<"),
+    (HashAlgorithm::Sha1, "a", "b", "C$';2<4s>4(&[W)*~-\";gF\\XDFsl!8"),
+    (HashAlgorithm::Sha1, "a", "t", "BzEzF0s;]&P3S.4x\\,tYH!\"m:Eqcdr"),
+    (HashAlgorithm::HmacSha1, "a", "b", "Du$+q3N<{OlR4+:(CXE'XIKdAGD7mf"),
+    (HashAlgorithm::HmacSha1, "3", "4", "CEyz=*&m(6E6,U.LlH|U~@TaC^TN=Q"),
+    (HashAlgorithm::Sha256, "a", "b", "*^GMK*Rae505*J9\"Xr*aq][0}lFxGg"),
+    (HashAlgorithm::Sha256, "4", "c", "DXx[sc#oo~x7j3Tr^o*-sO8T|\"jg8i"),
+    (HashAlgorithm::HmacSha256, "a", "b", "BCxy\\M0fW]u0G_:'%YI/:oj{Td6G[-"),
+    (HashAlgorithm::HmacSha256, "7", "4", "F}*\\m2]F!mq_L~RGuO#x]u\\G/84Nu)"),
+    (HashAlgorithm::Ripemd160, "a", "b", "C!aLLZq~%z/Iv#]Wvmv2QqU/1DNb6S"),
+    (HashAlgorithm::Ripemd160, "0", "e", "BcYUJ1L#Yksl{JsH0zau+gisDW(36\\"),
+    (HashAlgorithm::HmacRipemd160, "a", "b", "CxPFp((1h#u*~wQMWd1r;`<9mGOG+b"),
+    (HashAlgorithm::HmacRipemd160, "1", "j", "CD?YZ',,'gy(8=++K=\"u7ueP&rNqh%"),
+    (HashAlgorithm::Ripemd128, "a", "b", "CR#[~TCC%oFi\"ow@\\^EQF#!|G<evt+"),
+    (HashAlgorithm::Ripemd128, "1", "f", "D#;X=Lik#qU.#-6zZb?C03R|f5C(7d"),
+    (HashAlgorithm::HmacRipemd128, "a", "b", "H0pnu{7~RqMg1?>nuyV,I5@9kJaN[{"),
+    (HashAlgorithm::HmacRipemd128, "6", "q", "Bu$:7Tgra8V\\Za?zL>6G<azy]qV$>S"),
+    (HashAlgorithm::Tiger, "a", "b", "/[w-z(%{mf)deXXgY~|\\J:)CE2,_BB"),
+    (HashAlgorithm::Tiger, "7", "d", "BZ,JviC{V<$8l1nYOUL@{$n[cjSmLD"),
+    (HashAlgorithm::HmacTiger, "a", "b", "C-I'{ao\\f>i]o|a8!axwzZ`}s@hmp%"),
+    (HashAlgorithm::HmacTiger, "8", "n", "a~mRse!:hM;<}~Nx`>6?A96e3?28yV"),
+    (HashAlgorithm::Md5Version06, "a", "b", "07159c47ee1b19ae4fb9c40d480856"),
+    (HashAlgorithm::Md5Version06, "e", "y", "00c66f1a036bd8f9cb709cb8d925d3"),
+    (HashAlgorithm::HmacMd5Version06, "a", "b", "5e69fae25f4b4f3e8cc5de09a81635"),
+    (HashAlgorithm::HmacMd5Version06, "4", "5", "00a1d13c7b3ce76fa8a83aaa52bec9"),
+];
+
+/// The V0.6 algorithms of PasswordMaker Pro: Each hash is written as zero-padded lowercase hex, and the parts are separated
+/// the same way as in `reference_password`.
+fn reference_v06_password<F : Fn(&[u8], &[u8]) -> Vec<u8>>(hash : F, data : &str, key : &str, length : usize) -> String {
+    let mut password = String::new();
+    for part in 0.. {
+        if password.len() >= length {
+            break;
+        }
+        let key = if part == 0 { key.to_owned() } else { format!("{}\n{}", key, part) };
+        password.extend(hash(key.as_bytes(), data.as_bytes()).iter().map(|byte| format!("{:02x}", byte)));
+    }
+    password.chars().take(length).collect()
+}
+
+fn reference_password_for(algorithm : HashAlgorithm, data : &str, key : &str, characters : &str, length : usize) -> String {
+    match algorithm {
+        HashAlgorithm::Md4 => reference_password(reference_hash::<md4::Md4>, data, key, characters, length),
+        HashAlgorithm::HmacMd4 => reference_password(reference_hmac::<md4::Md4>, data, key, characters, length),
+        HashAlgorithm::Md5 => reference_password(reference_hash::<md5::Md5>, data, key, characters, length),
+        HashAlgorithm::Md5Version06 => reference_v06_password(reference_hash::<md5::Md5>, data, key, length),
+        HashAlgorithm::HmacMd5 => reference_password(reference_hmac::<md5::Md5>, data, key, characters, length),
+        HashAlgorithm::HmacMd5Version06 => reference_v06_password(reference_hmac::<md5::Md5>, data, key, length),
+        HashAlgorithm::Sha1 => reference_password(reference_hash::<sha1::Sha1>, data, key, characters, length),
+        HashAlgorithm::HmacSha1 => reference_password(reference_hmac::<sha1::Sha1>, data, key, characters, length),
+        HashAlgorithm::Sha256 => reference_password(reference_hash::<sha2::Sha256>, data, key, characters, length),
+        HashAlgorithm::HmacSha256 => reference_password(reference_hmac::<sha2::Sha256>, data, key, characters, length),
+        HashAlgorithm::Ripemd160 => reference_password(reference_hash::<ripemd::Ripemd160>, data, key, characters, length),
+        HashAlgorithm::HmacRipemd160 => reference_password(reference_hmac::<ripemd::Ripemd160>, data, key, characters, length),
+        HashAlgorithm::Ripemd128 => reference_password(reference_hash::<ripemd::Ripemd128>, data, key, characters, length),
+        HashAlgorithm::HmacRipemd128 => reference_password(reference_hmac::<ripemd::Ripemd128>, data, key, characters, length),
+        HashAlgorithm::Tiger => reference_password(reference_hash::<tiger::Tiger>, data, key, characters, length),
+        HashAlgorithm::HmacTiger => reference_password(reference_hmac::<tiger::Tiger>, data, key, characters, length),
+        _ => unimplemented!("No reference for {}", algorithm),
+    }
+}
+
+#[test]
+fn minimal_inputs(){
+    let characters = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+    for (algorithm, data, key, expected) in MINIMAL_INPUT_VECTORS {
+        let pwm = Pwm::new(
+            *algorithm,
+            passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+            characters,
+            "",
+            "",
+            30,
+            "",
+            ""
+        ).unwrap();
+        assert_eq!(reference_password_for(*algorithm, data, key, characters, 30), *expected, "reference for {} with data {:?} and key {:?}", algorithm, data, key);
+        assert_eq!(pwm.generate(*data, *key).unwrap(), *expected, "{} with data {:?} and key {:?}", algorithm, data, key);
+    }
+    let covered : std::collections::HashSet<_> = MINIMAL_INPUT_VECTORS.iter().map(|vector| vector.0).collect();
+    assert_eq!(covered.len(), HashAlgorithm::all().len());
+}
+
+#[test]
+fn part_counter_placement_long_password(){
+    use passwordmaker_rs::PartCounterPlacement;
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacSha1, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "0123456789",
+        "user",
+        "mod",
+        100,
+        "",
+        ""
+    ).unwrap();
+    let default_password = pwm.generate("example.com", "password").unwrap();
+    let expected_inputs : &[(PartCounterPlacement, &[u8], &[u8])] = &[
+        (PartCounterPlacement::Key, b"password\n1", b"example.comusermod"),
+        (PartCounterPlacement::Data, b"password", b"example.com\n1usermod"),
+        (PartCounterPlacement::Modifier, b"password", b"example.comusermod\n1"),
+    ];
+    let mut passwords = Vec::new();
+    for (placement, key, data) in expected_inputs {
+        let pwm = pwm.clone().with_part_counter_placement(*placement);
+        let (password, trace) = pwm.generate_with_trace("example.com", "password").unwrap();
+        assert_eq!(password.len(), 100);
+        assert_eq!(trace.parts().len(), 3);
+        assert_eq!(trace.parts()[0].hmac_key(), Some(&b"password"[..]));
+        assert_eq!(trace.parts()[0].hasher_input(), b"example.comusermod");
+        assert_eq!(trace.parts()[1].hmac_key(), Some(*key));
+        assert_eq!(trace.parts()[1].hasher_input(), *data);
+        //the first part does not get a counter, so it's the same for all placements.
+        assert_eq!(&password[..40], &default_password[..40]);
+        passwords.push(password);
+    }
+    assert_eq!(passwords[0], default_password);
+    assert_ne!(passwords[1], passwords[0]);
+    assert_ne!(passwords[2], passwords[0]);
+    assert_ne!(passwords[2], passwords[1]);
+}
+
+#[test]
+fn part_counter_placement_short_password_unaffected(){
+    use passwordmaker_rs::PartCounterPlacement;
+    let pwm = Pwm::new(
+        HashAlgorithm::Md5, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "user",
+        "mod",
+        12,
+        "",
+        ""
+    ).unwrap();
+    let with_data_counter = pwm.clone().with_part_counter_placement(PartCounterPlacement::Data);
+    assert_eq!(with_data_counter.generate("example.com", "password").unwrap(), pwm.generate("example.com", "password").unwrap());
+    assert_ne!(with_data_counter.settings_fingerprint(), pwm.settings_fingerprint());
+}
+
+#[test]
+fn part_counter_separator(){
+    use passwordmaker_rs::PartCounterPlacement;
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacSha1, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "0123456789",
+        "user",
+        "mod",
+        100,
+        "",
+        ""
+    ).unwrap();
+    let default_password = pwm.generate("example.com", "password").unwrap();
+    assert_eq!(pwm.clone().with_part_counter_separator("\n").generate("example.com", "password").unwrap(), default_password);
+    let no_separator = pwm.clone().with_part_counter_separator("");
+    let (password, trace) = no_separator.generate_with_trace("example.com", "password").unwrap();
+    assert_eq!(trace.parts()[1].hmac_key(), Some(&b"password1"[..]));
+    assert_eq!(trace.parts()[2].hmac_key(), Some(&b"password2"[..]));
+    assert_eq!(&password[..40], &default_password[..40]);
+    assert_ne!(password, default_password);
+    assert_ne!(no_separator.settings_fingerprint(), pwm.settings_fingerprint());
+    let dash = pwm.clone().with_part_counter_separator(String::from("-")).with_part_counter_placement(PartCounterPlacement::Data);
+    let (_, trace) = dash.generate_with_trace("example.com", "password").unwrap();
+    assert_eq!(trace.parts()[1].hasher_input(), b"example.com-1usermod");
+    //Single part passwords don't have a counter.
+    let short = Pwm::new(HashAlgorithm::HmacSha1, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "0123456789", "user", "mod", 20, "", "").unwrap();
+    assert_eq!(
+        short.generate("example.com", "password").unwrap(),
+        short.clone().with_part_counter_separator("").generate("example.com", "password").unwrap()
+    );
+}
+
+#[test]
+fn part_counter_separator_leeted_if_username_excluded(){
+    use passwordmaker_rs::{PartCounterPlacement, UseLeetWhenGenerating};
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::Before { level : LeetLevel::One }, "0123456789abcdef", "", "mod", 60, "", "").unwrap()
+        .with_part_counter_separator("-part");
+    for placement in &[PartCounterPlacement::Key, PartCounterPlacement::Data, PartCounterPlacement::Modifier] {
+        let pwm = pwm.clone().with_part_counter_placement(*placement);
+        let (password, trace) = pwm.generate_with_trace("example.com", "password").unwrap();
+        assert!(trace.parts().len() > 1);
+        //The username is empty, so excluding it from leet must not make a difference.
+        let excluded = pwm.clone().with_pre_leet_username(false);
+        assert_eq!(excluded.generate("example.com", "password").unwrap(), password, "{:?}", placement);
+    }
+}
+
+#[test]
+fn emoji_charset_long_password(){
+    let pwm = Pwm::new(
+        HashAlgorithm::Sha256, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "😀😁😂🤣😃😄😅😆😉😊😋😎😍😘🥰😗",
+        "",
+        "",
+        100,
+        "",
+        ""
+    ).unwrap();
+    let result = pwm.generate("example.com", "password").unwrap();
+    assert_eq!(result.chars().count(), 100);
+    assert_eq!(result.len(), 400);
+}
+
+#[test]
+fn self_test_passes_with_correct_hashers(){
+    assert!(Pwm::self_test().is_ok());
+}
+
+//RIPEMD-160 and SHA-1 have the same output size, so this mistake compiles just fine.
+struct SwappedHashes{}
+impl HasherList for SwappedHashes {
+    type MD4 = Md4;
+    type MD5 = Md5;
+    type SHA1 = RipeMD160;
+    type SHA256 = Sha256;
+    type RIPEMD160 = Sha1;
+}
+
+#[test]
+fn self_test_reports_swapped_hashers(){
+    let error = PasswordMaker::<SwappedHashes>::self_test().unwrap_err();
+    assert_eq!(error.failed_algorithms(), &[HashAlgorithm::Sha1, HashAlgorithm::HmacSha1, HashAlgorithm::Ripemd160, HashAlgorithm::HmacRipemd160]);
+    assert_eq!(error.to_string(), "Known-answer test failed for: Sha1 HmacSha1 Ripemd160 HmacRipemd160");
+}
+
+#[test]
+fn known_answer_vectors_cover_all_passwordmaker_pro_algorithms(){
+    use passwordmaker_rs::{BaseAlgorithm, KNOWN_ANSWER_VECTORS};
+    let covered : Vec<_> = KNOWN_ANSWER_VECTORS.iter().map(|v| v.hash_algorithm).collect();
+    let expected : Vec<_> = HashAlgorithm::all().iter().copied()
+        .filter(|a| !matches!(a.base_algorithm(), BaseAlgorithm::Ripemd128 | BaseAlgorithm::Tiger))
+        .collect();
+    assert_eq!(covered, expected);
+}
+
+#[test]
+fn max_output_bytes_limits_emoji_passwords(){
+    use passwordmaker_rs::GenerationError;
+    let pwm = Pwm::new(
+        HashAlgorithm::Sha256, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "😀😁😂🤣😃😄😅😆😉😊😋😎😍😘🥰😗",
+        "",
+        "",
+        100,
+        "",
+        ""
+    ).unwrap();
+    let unlimited = pwm.generate("example.com", "password").unwrap();
+    let limited = pwm.clone().with_max_output_bytes(Some(400));
+    assert_eq!(limited.generate("example.com", "password").unwrap(), unlimited);
+    //100 graphemes are well below any grapheme limit, but 400 bytes are above this one.
+    let limited = pwm.with_max_output_bytes(Some(399));
+    assert!(matches!(limited.generate("example.com", "password"), Err(GenerationError::OutputTooLarge)));
+    assert!(matches!(limited.generate_with_trace("example.com", "password"), Err(GenerationError::OutputTooLarge)));
+}
+
+#[test]
+fn estimated_parts_single_part(){
+    let pwm = Pwm::new(
+        HashAlgorithm::Md5, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./",
+        "",
+        "",
+        8,
+        "",
+        ""
+    ).unwrap();
+    assert_eq!(pwm.estimated_parts(), 1);
+    assert!(!pwm.requires_multiple_parts());
+    let (_, trace) = pwm.generate_with_trace("example.com", "password").unwrap();
+    assert_eq!(trace.parts().len(), 1);
+}
+
+#[test]
+fn estimated_parts_multiple_parts(){
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacSha1, 
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "0123456789",
+        "",
+        "",
+        102,
+        "p",
+        "s"
+    ).unwrap();
+    //SHA-1 yields at most 49 decimal digits. 100 are needed.
+    assert_eq!(pwm.estimated_parts(), 3);
+    assert!(pwm.requires_multiple_parts());
+    let (_, trace) = pwm.generate_with_trace("example.com", "password").unwrap();
+    assert_eq!(trace.parts().len(), 3);
+    //V0.6 always yields 32 hex digits per part.
+    let pwm = Pwm::new(HashAlgorithm::Md5Version06, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "ab", "", "", 64, "", "").unwrap();
+    assert_eq!(pwm.estimated_parts(), 2);
+    let pwm = Pwm::new(HashAlgorithm::Md5Version06, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "ab", "", "", 65, "", "").unwrap();
+    assert_eq!(pwm.estimated_parts(), 3);
+}
+
+#[test]
+fn search_space(){
+    let pwm = Pwm::new(HashAlgorithm::Md5, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "0123456789", "", "", 8, "p", "s").unwrap();
+    //Prefix and suffix take up 2 of the 8 characters.
+    assert_eq!(pwm.search_space(), Some(1_000_000));
+    //The V0.6 algorithms ignore the charset, and always use hexadecimal digits.
+    let pwm = Pwm::new(HashAlgorithm::Md5Version06, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "ab", "", "", 4, "", "").unwrap();
+    assert_eq!(pwm.search_space(), Some(65536));
+    let pwm = Pwm::new(HashAlgorithm::Md5, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "ab", "", "", 127, "", "").unwrap();
+    assert_eq!(pwm.search_space(), Some(1 << 127));
+    let pwm = Pwm::new(HashAlgorithm::Md5, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "ab", "", "", 128, "", "").unwrap();
+    assert_eq!(pwm.search_space(), None);
+    let pwm = Pwm::new(
+        HashAlgorithm::Md5,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./",
+        "",
+        "",
+        20,
+        "",
+        ""
+    ).unwrap();
+    assert_eq!(pwm.search_space(), None);
+    //Prefix and suffix as long as the password leave a single possibility.
+    let pwm = Pwm::new(HashAlgorithm::Md5, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "ab", "", "", 4, "pre", "suffix").unwrap();
+    assert_eq!(pwm.search_space(), Some(1));
+}
+
+fn to_lower_hex(bytes : &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn hex_output_sha256(){
+    let pwm = Pwm::new(
+        HashAlgorithm::Sha256,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+        "user",
+        "mod",
+        70,
+        "",
+        ""
+    ).unwrap().with_output_encoding(passwordmaker_rs::OutputEncoding::Hex);
+    assert_eq!(pwm.estimated_parts(), 2);
+    let result = pwm.generate("example.com", "password").unwrap();
+    //Non-HMAC hashes key + data + username + modifier. The second part has the counter appended to the key.
+    let first = to_lower_hex(&sha2::Sha256::digest(b"passwordexample.comusermod"));
+    let second = to_lower_hex(&sha2::Sha256::digest(b"password\n1example.comusermod"));
+    assert_eq!(result, first + &second[..6]);
+}
+
+#[test]
+fn hex_output_md4_keeps_leading_zeros(){
+    let pwm = Pwm::new(
+        HashAlgorithm::Md4,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+        "",
+        "",
+        32,
+        "",
+        ""
+    ).unwrap();
+    let hex_pwm = pwm.clone().with_output_encoding(passwordmaker_rs::OutputEncoding::Hex);
+    //Find an input whose hash starts with a zero digit, so the padding is actually tested.
+    let data = (0..).map(|i| format!("example{}.com", i))
+        .find(|data| md4::Md4::digest(("password".to_owned() + data).as_bytes())[0] < 0x10)
+        .unwrap();
+    let expected = to_lower_hex(&md4::Md4::digest(("password".to_owned() + &data).as_bytes()));
+    assert!(expected.starts_with('0'));
+    assert_eq!(hex_pwm.generate(&data, "password").unwrap(), expected);
+    assert_ne!(hex_pwm.settings_fingerprint(), pwm.settings_fingerprint());
+    //Hex output ignores the characters.
+    let other_characters = Pwm::new(HashAlgorithm::Md4, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "xyz", "", "", 32, "", "").unwrap()
+        .with_output_encoding(passwordmaker_rs::OutputEncoding::Hex);
+    assert_eq!(other_characters.generate(&data, "password").unwrap(), expected);
+}
+
+#[test]
+fn hex_output_is_v06_output(){
+    let pwm = Pwm::new(HashAlgorithm::HmacMd5Version06, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "abc", "u", "m", 40, "", "").unwrap();
+    let hex_pwm = pwm.clone().with_output_encoding(passwordmaker_rs::OutputEncoding::Hex);
+    assert_eq!(pwm.generate("example.com", "password").unwrap(), hex_pwm.generate("example.com", "password").unwrap());
+}
+
+#[test]
+fn base64_output_sha1(){
+    let pwm = Pwm::new(
+        HashAlgorithm::Sha1,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "abc",
+        "user",
+        "",
+        40,
+        "",
+        ""
+    ).unwrap().with_output_encoding(passwordmaker_rs::OutputEncoding::Base64);
+    //27 Base64 digits per SHA-1 hash.
+    assert_eq!(pwm.estimated_parts(), 2);
+    let result = pwm.generate("example.com", "password").unwrap();
+    let first = base64::encode_config(sha1::Sha1::digest(b"passwordexample.comuser"), base64::URL_SAFE_NO_PAD);
+    let second = base64::encode_config(sha1::Sha1::digest(b"password\n1example.comuser"), base64::URL_SAFE_NO_PAD);
+    assert_eq!(first.len(), 27);
+    assert_eq!(result, first + &second[..13]);
+}
+
+#[test]
+fn base64_output_all_hash_sizes(){
+    let algorithms = [
+        (HashAlgorithm::Md4, md4::Md4::digest(b"passwordexample.com").to_vec()),
+        (HashAlgorithm::Ripemd160, ripemd::Ripemd160::digest(b"passwordexample.com").to_vec()),
+        (HashAlgorithm::Sha256, sha2::Sha256::digest(b"passwordexample.com").to_vec()),
+    ];
+    for (algorithm, digest) in &algorithms {
+        let expected = base64::encode_config(digest, base64::URL_SAFE_NO_PAD);
+        let pwm = Pwm::new(*algorithm, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "abc", "", "", expected.len(), "", "").unwrap()
+            .with_output_encoding(passwordmaker_rs::OutputEncoding::Base64);
+        assert_eq!(pwm.generate("example.com", "password").unwrap(), expected);
+    }
+}
+
+#[test]
+fn base58_output_md5_keeps_leading_zero_bytes(){
+    //Find an input whose hash starts with a zero byte, as Base58 encodes those specially.
+    let data = (0..).map(|i| format!("example{}.com", i))
+        .find(|data| md5::Md5::digest(("password".to_owned() + data).as_bytes())[0] == 0)
+        .unwrap();
+    let expected = bs58::encode(md5::Md5::digest(("password".to_owned() + &data).as_bytes())).into_string();
+    assert!(expected.starts_with('1'));
+    let pwm = Pwm::new(
+        HashAlgorithm::Md5,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "abc",
+        "",
+        "",
+        expected.len(),
+        "",
+        ""
+    ).unwrap().with_output_encoding(passwordmaker_rs::OutputEncoding::Base58);
+    assert_eq!(pwm.generate(&data, "password").unwrap(), expected);
+    let (_, trace) = pwm.generate_with_trace(&data, "password").unwrap();
+    assert_eq!(trace.parts()[0].digits()[0], 0);
+}
+
+#[test]
+fn base58_output_sha256_multiple_parts(){
+    let pwm = Pwm::new(
+        HashAlgorithm::Sha256,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "abc",
+        "",
+        "",
+        60,
+        "",
+        ""
+    ).unwrap().with_output_encoding(passwordmaker_rs::OutputEncoding::Base58);
+    assert_eq!(pwm.estimated_parts(), 2);
+    let first = bs58::encode(sha2::Sha256::digest(b"passwordexample.com")).into_string();
+    let second = bs58::encode(sha2::Sha256::digest(b"password\n1example.com")).into_string();
+    assert_eq!(pwm.generate("example.com", "password").unwrap(), (first + &second).chars().take(60).collect::<String>());
+}
+
+#[test]
+fn max_single_hash_length(){
+    use passwordmaker_rs::{OutputEncoding, UseLeetWhenGenerating};
+    let characters = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+    //94^19 < 2^128 <= 94^20
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, characters, "", "", 8, "", "").unwrap();
+    assert_eq!(pwm.max_single_hash_length(), 20);
+    let pwm = Pwm::new(HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, "0123456789abcdef", "", "", 8, "", "").unwrap();
+    assert_eq!(pwm.max_single_hash_length(), 64);
+    assert_eq!(pwm.with_output_encoding(OutputEncoding::Hex).max_single_hash_length(), 64);
+    let pwm = Pwm::new(HashAlgorithm::HmacSha1, UseLeetWhenGenerating::NotAtAll, "01", "", "", 8, "", "").unwrap();
+    assert_eq!(pwm.max_single_hash_length(), 160);
+    assert_eq!(pwm.with_output_encoding(OutputEncoding::Base64).max_single_hash_length(), 27);
+    //V0.6 always outputs hex.
+    let pwm = Pwm::new(HashAlgorithm::Md5Version06, UseLeetWhenGenerating::NotAtAll, characters, "", "", 8, "", "").unwrap();
+    assert_eq!(pwm.max_single_hash_length(), 32);
+    //A password of exactly that length needs a single part, one character more needs two.
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, characters, "", "", 20, "", "").unwrap();
+    assert_eq!(pwm.estimated_parts(), 1);
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, characters, "", "", 21, "", "").unwrap();
+    assert_eq!(pwm.estimated_parts(), 2);
+}
+
+#[test]
+fn revision_label(){
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacSha256,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "user",
+        "",
+        60,
+        "",
+        ""
+    ).unwrap();
+    let unlabeled = pwm.generate("example.com", "password").unwrap();
+    let v2 = pwm.clone().with_revision("v2");
+    let labeled = v2.generate("example.com", "password").unwrap();
+    assert_ne!(labeled, unlabeled);
+    assert_eq!(labeled, v2.generate("example.com", "password").unwrap());
+    assert_eq!(labeled, pwm.clone().with_revision(String::from("v2")).generate("example.com", "password").unwrap());
+    assert_ne!(labeled, pwm.clone().with_revision("v3").generate("example.com", "password").unwrap());
+    //The label is appended to the key, for all password parts.
+    assert_eq!(labeled, pwm.generate("example.com", "password\n@v2").unwrap());
+    //Empty label is the same as no label.
+    assert_eq!(unlabeled, pwm.clone().with_revision("").generate("example.com", "password").unwrap());
+    assert_eq!(pwm.settings_fingerprint(), pwm.clone().with_revision("").settings_fingerprint());
+    assert_ne!(pwm.settings_fingerprint(), v2.settings_fingerprint());
+    //Revision "1" must not be the second password part of the unlabeled password.
+    let (_, trace) = pwm.generate_with_trace("example.com", "password").unwrap();
+    let (_, labeled_trace) = pwm.clone().with_revision("1").generate_with_trace("example.com", "password").unwrap();
+    assert_ne!(trace.parts()[1].hash(), labeled_trace.parts()[0].hash());
+}
+
+#[test]
+fn context_tag(){
+    use passwordmaker_rs::{PartCounterPlacement, UseLeetWhenGenerating};
+    let pwm = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789", "user", "", 60, "", "").unwrap();
+    let untagged = pwm.generate("example.com", "password").unwrap();
+    let email = pwm.clone().with_context_tag("email").generate("example.com", "password").unwrap();
+    let banking = pwm.clone().with_context_tag(String::from("banking")).generate("example.com", "password").unwrap();
+    assert_ne!(email, banking);
+    assert_ne!(email, untagged);
+    assert_ne!(banking, untagged);
+    //The tag is appended to the data, after username and modifier, for all password parts.
+    let tag_in_username = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789", "user\n#email", "", 60, "", "").unwrap();
+    assert_eq!(email, tag_in_username.generate("example.com", "password").unwrap());
+    //Empty tag is the same as no tag.
+    assert_eq!(untagged, pwm.clone().with_context_tag("").generate("example.com", "password").unwrap());
+    assert_eq!(pwm.settings_fingerprint(), pwm.clone().with_context_tag("").settings_fingerprint());
+    assert_ne!(pwm.settings_fingerprint(), pwm.clone().with_context_tag("email").settings_fingerprint());
+    //Tag "1" must not be the second password part of the untagged password, if the counter is placed in the modifier.
+    let in_modifier = pwm.clone().with_part_counter_placement(PartCounterPlacement::Modifier);
+    let (_, trace) = in_modifier.generate_with_trace("example.com", "password").unwrap();
+    let (_, tagged_trace) = in_modifier.with_context_tag("1").generate_with_trace("example.com", "password").unwrap();
+    assert_ne!(trace.parts()[1].hash(), tagged_trace.parts()[0].hash());
+}
+
+#[test]
+fn charset_is_effective(){
+    use passwordmaker_rs::{OutputEncoding, UseLeetWhenGenerating};
+    let pwm = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 8, "", "").unwrap();
+    assert!(pwm.charset_is_effective());
+    assert!(!pwm.clone().with_output_encoding(OutputEncoding::Hex).charset_is_effective());
+    assert!(!pwm.with_output_encoding(OutputEncoding::Base58).charset_is_effective());
+    let pwm = Pwm::new(HashAlgorithm::Md5Version06, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 8, "", "").unwrap();
+    assert!(!pwm.charset_is_effective());
+    assert!(!pwm.with_output_encoding(OutputEncoding::Characters).charset_is_effective());
+}
+
+#[test]
+fn charset_len(){
+    use passwordmaker_rs::{OutputEncoding, UseLeetWhenGenerating};
+    use unicode_segmentation::UnicodeSegmentation;
+    let ascii = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+    let pwm = Pwm::new(HashAlgorithm::Sha1, UseLeetWhenGenerating::NotAtAll, ascii, "", "", 8, "", "").unwrap();
+    assert_eq!(pwm.charset_len(), ascii.graphemes(true).count());
+    assert_eq!(pwm.charset_len(), 94);
+    let emoji = "😀👍🏽🇩🇪e\u{301}👨‍👩‍👧";
+    let pwm = Pwm::new(HashAlgorithm::Sha1, UseLeetWhenGenerating::NotAtAll, emoji, "", "", 8, "", "").unwrap();
+    assert_eq!(pwm.charset_len(), emoji.graphemes(true).count());
+    assert_eq!(pwm.charset_len(), 5);
+    assert_eq!(pwm.clone().with_output_encoding(OutputEncoding::Base64).charset_len(), 64);
+    assert_eq!(pwm.with_output_encoding(OutputEncoding::Base58).charset_len(), 58);
+    let pwm = Pwm::new(HashAlgorithm::HmacMd5Version06, UseLeetWhenGenerating::NotAtAll, emoji, "", "", 8, "", "").unwrap();
+    assert_eq!(pwm.charset_len(), 16);
+}
+
+#[test]
+fn pre_leet_data_only(){
+    use passwordmaker_rs::{LeetLevel, UseLeetWhenGenerating};
+    let leet = UseLeetWhenGenerating::Before { level : LeetLevel::Four };
+    let pwm = Pwm::new(HashAlgorithm::Sha256, leet, "abcdefghijklmnopqrstuvwxyz", "User@Example.COM", "Mod", 20, "", "").unwrap();
+    let data_only = pwm.clone().with_pre_leet_username(false).with_pre_leet_modifier(false);
+    let (whole, whole_trace) = pwm.generate_with_trace("example.com", "password").unwrap();
+    let (partial, partial_trace) = data_only.generate_with_trace("example.com", "password").unwrap();
+    assert_ne!(whole, partial);
+    assert!(partial_trace.parts()[0].hasher_input().ends_with(b"User@Example.COMMod"));
+    assert!(!whole_trace.parts()[0].hasher_input().ends_with(b"User@Example.COMMod"));
+    //Only the username is excluded from leet.
+    let (username_only, username_only_trace) = pwm.clone().with_pre_leet_username(false).generate_with_trace("example.com", "password").unwrap();
+    assert_ne!(username_only, whole);
+    assert_ne!(username_only, partial);
+    assert!(!username_only_trace.parts()[0].hasher_input().ends_with(b"User@Example.COMMod"));
+    assert_ne!(pwm.settings_fingerprint(), data_only.settings_fingerprint());
+    //Without leet before generation the flags have no effect.
+    let no_leet = Pwm::new(HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, "abcdefghijklmnopqrstuvwxyz", "User@Example.COM", "Mod", 20, "", "").unwrap();
+    assert_eq!(no_leet.generate("example.com", "password").unwrap(), no_leet.clone().with_pre_leet_username(false).generate("example.com", "password").unwrap());
+}
+
+#[test]
+fn pre_leet_separately_is_context_sensitive(){
+    use passwordmaker_rs::{LeetLevel, UseLeetWhenGenerating};
+    let leet = UseLeetWhenGenerating::Before { level : LeetLevel::One };
+    let pwm = Pwm::new(HashAlgorithm::HmacSha256, leet, "abcdefghijklmnopqrstuvwxyz", "Α", "", 20, "", "").unwrap();
+    //Excluding the (empty) modifier leets data and username separately. For plain ASCII that gives the same result...
+    let separate = pwm.clone().with_pre_leet_modifier(false);
+    assert_eq!(pwm.generate("example.com", "password").unwrap(), separate.generate("example.com", "password").unwrap());
+    //...but a sigma at the end of data is lower-cased to a final sigma if data is leeted on its own.
+    let (whole, whole_trace) = pwm.generate_with_trace("ΑΣ", "password").unwrap();
+    let (split, split_trace) = separate.generate_with_trace("ΑΣ", "password").unwrap();
+    assert_ne!(whole, split);
+    assert_eq!(whole_trace.parts()[0].hasher_input(), "ασα".as_bytes());
+    assert_eq!(split_trace.parts()[0].hasher_input(), "αςα".as_bytes());
+}
+
+#[test]
+fn min_key_length(){
+    use passwordmaker_rs::{GenerationError, UseLeetWhenGenerating};
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdefghijklmnopqrstuvwxyz", "", "", 8, "", "").unwrap();
+    //3 grapheme clusters, but 13 bytes.
+    let key = "äe\u{301}👍🏽";
+    assert_eq!(key.len(), 13);
+    assert!(pwm.generate("example.com", key).is_ok());
+    let strict = pwm.clone().with_min_key_length(4);
+    assert!(matches!(strict.generate("example.com", key), Err(GenerationError::MasterPasswordTooShort { min : 4, got : 3 })));
+    assert_eq!(
+        strict.generate("example.com", key).unwrap_err().to_string(),
+        "The master password needs at least 4 characters, but has only 3."
+    );
+    let exact = pwm.clone().with_min_key_length(3);
+    assert_eq!(exact.generate("example.com", key).unwrap(), pwm.generate("example.com", key).unwrap());
+    //Empty keys are always rejected, even with a minimum of 0.
+    assert!(matches!(pwm.clone().with_min_key_length(0).generate("example.com", ""), Err(GenerationError::MissingMasterPassword)));
+    assert!(matches!(strict.generate("example.com", ""), Err(GenerationError::MissingMasterPassword)));
+}
+
+#[test]
+fn debug_redacts_secrets(){
+    use passwordmaker_rs::{LeetLevel, UseLeetWhenGenerating};
+    let pwm = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::After { level : LeetLevel::Three }, "abcdef", "", "", 12, "", "").unwrap();
+    let key = "correct horse battery staple";
+    let password = pwm.generate("example.com", key).unwrap();
+    let debug = format!("{:?}", pwm);
+    assert_eq!(
+        debug,
+        "PasswordMaker { hash_algorithm: HmacSha256, pre_leet: None, post_leet: Some(Three), charset_len: 6, \
+        output_encoding: Characters, password_length: 12, key: \"<redacted>\", data: \"<redacted>\" }"
+    );
+    assert!(!debug.contains(key));
+    assert!(!debug.contains(&password));
+    assert!(format!("{:#?}", pwm).contains("<redacted>"));
+}
+
+#[test]
+fn generate_bytes(){
+    use passwordmaker_rs::{GenerationError, LeetLevel, OutputEncoding, UseLeetWhenGenerating};
+    let ascii = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+    let pwm = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, ascii, "", "", 50, "", "").unwrap();
+    let bytes = pwm.generate_bytes("example.com", "password").unwrap();
+    assert_eq!(bytes.len(), 50);
+    assert_eq!(bytes, pwm.generate("example.com", "password").unwrap().into_bytes());
+    let hex = pwm.clone().with_output_encoding(OutputEncoding::Hex);
+    assert_eq!(hex.generate_bytes("example.com", "password").unwrap(), hex.generate("example.com", "password").unwrap().into_bytes());
+    assert!(matches!(pwm.clone().with_max_output_bytes(Some(49)).generate_bytes("example.com", "password"), Err(GenerationError::OutputTooLarge)));
+    //Affixes and leet take the string path, but the result is the same.
+    let affixes = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::After { level : LeetLevel::Nine }, ascii, "", "", 50, "pre", "suf").unwrap();
+    assert_eq!(affixes.generate_bytes("example.com", "password").unwrap(), affixes.generate("example.com", "password").unwrap().into_bytes());
+    //For multi-byte characters, the bytes are UTF-8.
+    let emoji = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, "😀👍🏽e\u{301}ab", "", "", 20, "", "").unwrap();
+    let bytes = emoji.generate_bytes("example.com", "password").unwrap();
+    assert_eq!(String::from_utf8(bytes).unwrap(), emoji.generate("example.com", "password").unwrap());
+    assert!(matches!(emoji.generate_bytes("example.com", ""), Err(GenerationError::MissingMasterPassword)));
+}
+
+#[test]
+fn output_padding(){
+    use passwordmaker_rs::{OutputEncoding, OutputPadding, UseLeetWhenGenerating};
+    //The MD5 hash of "ye" starts with a zero byte.
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "0123456789abcdef", "", "", 32, "", "").unwrap();
+    let padded = pwm.clone().with_output_padding(OutputPadding::LeadingZeros);
+    let (unpadded_password, unpadded_trace) = pwm.generate_with_trace("e", "y").unwrap();
+    let (padded_password, padded_trace) = padded.generate_with_trace("e", "y").unwrap();
+    assert_eq!(padded_password, "00c66f1a036bd8f9cb709cb8d925d3d9");
+    assert_eq!(&unpadded_password[..30], &padded_password[2..]);
+    assert_eq!(unpadded_trace.parts().len(), 2);
+    assert_eq!(padded_trace.parts().len(), 1);
+    assert_eq!(padded_trace.parts()[0].digits().len(), padded.max_single_hash_length());
+    //Same as V0.6, and as hex output.
+    let v06 = Pwm::new(HashAlgorithm::Md5Version06, UseLeetWhenGenerating::NotAtAll, "0123456789abcdef", "", "", 32, "", "").unwrap();
+    assert_eq!(padded_password, v06.generate("e", "y").unwrap());
+    assert_eq!(padded_password, pwm.clone().with_output_encoding(OutputEncoding::Hex).generate("e", "y").unwrap());
+    //Padding maps to the first character.
+    let letters = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "ABCDEFGHIJKLMNOPQRSTUVWXYZ", "", "", 10, "", "").unwrap();
+    let padded_letters = letters.clone().with_output_padding(OutputPadding::LeadingZeros).generate("e", "y").unwrap();
+    assert!(padded_letters.starts_with('A'));
+    assert_ne!(padded_letters, letters.generate("e", "y").unwrap());
+    //No effect on V0.6, or if the hash has no leading zeros.
+    assert_eq!(v06.clone().with_output_padding(OutputPadding::LeadingZeros).generate("e", "y").unwrap(), v06.generate("e", "y").unwrap());
+    assert_eq!(padded.generate("example.com", "password").unwrap(), pwm.generate("example.com", "password").unwrap());
+    assert_ne!(padded.settings_fingerprint(), pwm.settings_fingerprint());
+}
+
+#[test]
+fn generate_borrows_settings(){
+    let pwm = Pwm::new(
+        HashAlgorithm::HmacSha256,
+        passwordmaker_rs::UseLeetWhenGenerating::NotAtAll,
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        "user",
+        "",
+        40,
+        "",
+        ""
+    ).unwrap();
+    let first = pwm.generate("example.com", "password").unwrap();
+    let second = pwm.generate("example.com", "password").unwrap();
+    assert_eq!(first, second);
+    assert_eq!(first, pwm.generate_nth("example.com", "password", 0).unwrap());
+    assert_ne!(first, pwm.generate_nth("example.com", "password", 1).unwrap());
+    assert_eq!(pwm.generate("example.org", "password").unwrap(), pwm.generate("example.org", "password").unwrap());
+}
+
+#[test]
+fn strict_charset(){
+    use passwordmaker_rs::{GenerationError, UseLeetWhenGenerating};
+    let with_tab = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abc\tdef", "", "", 8, "", "").unwrap();
+    assert!(with_tab.generate("example.com", "password").is_ok());
+    let strict = with_tab.with_strict_charset(true);
+    assert!(matches!(strict.generate("example.com", "password"), Err(GenerationError::NonPrintableCharsetEntry { index : 3 })));
+    assert_eq!(
+        strict.generate("example.com", "password").unwrap_err().to_string(),
+        "Character 3 of the charset contains a control character."
+    );
+    //"\r\n" is a single grapheme cluster.
+    let with_newline = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "ab\r\ncd", "", "", 8, "", "").unwrap()
+        .with_strict_charset(true);
+    assert!(matches!(with_newline.generate("example.com", "password"), Err(GenerationError::NonPrintableCharsetEntry { index : 2 })));
+
+    let normal = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef ghé👍🏽", "", "", 8, "", "").unwrap();
+    assert_eq!(
+        normal.clone().with_strict_charset(true).generate("example.com", "password").unwrap(),
+        normal.generate("example.com", "password").unwrap()
+    );
+}
+
+#[test]
+fn strict_charset_batch_checks_spec_characters(){
+    use passwordmaker_rs::{GenerationError, OutputSpec, UseLeetWhenGenerating};
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 8, "", "").unwrap()
+        .with_strict_charset(true);
+    let with_tab = [OutputSpec::new("0123456789", 4, "", "").unwrap(), OutputSpec::new("ab\tcd", 8, "", "").unwrap()];
+    assert!(matches!(pwm.generate_batch("example.com", "password", &with_tab), Err(GenerationError::NonPrintableCharsetEntry { index : 2 })));
+    //The characters of the PasswordMaker itself are not used by the batch, so they are not checked.
+    let unused_tab = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abc\tdef", "", "", 8, "", "").unwrap()
+        .with_strict_charset(true);
+    let printable = [OutputSpec::new("0123456789", 4, "", "").unwrap()];
+    assert_eq!(
+        unused_tab.generate_batch("example.com", "password", &printable).unwrap(),
+        pwm.generate_batch("example.com", "password", &printable).unwrap()
+    );
+}
+
+#[test]
+fn generate_with_context(){
+    use passwordmaker_rs::{LeetLevel, UseLeetWhenGenerating};
+    let pwm = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::Before { level : LeetLevel::Three }, "abcdef", "user", "mod", 40, "pre", "").unwrap();
+    let key = "correct horse battery staple";
+    let (password, context) = pwm.generate_with_context("example.com", key).unwrap();
+    assert_eq!(password, pwm.generate("example.com", key).unwrap());
+    assert_eq!(context.data(), "example.comusermod");
+    assert_eq!((context.text_to_use(), context.username(), context.modifier()), ("example.com", "user", "mod"));
+    assert_eq!(context.hash_algorithm(), HashAlgorithm::HmacSha256);
+    assert_eq!(context.password_length(), 40);
+    let debug = format!("{:?}", context);
+    assert!(!debug.contains(key));
+    assert!(!debug.contains(&password));
+    //Empty fields add nothing.
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 8, "", "").unwrap();
+    let (_, context) = pwm.generate_with_context("example.com", key).unwrap();
+    assert_eq!(context.data(), "example.com");
+    assert_eq!((context.text_to_use(), context.username(), context.modifier()), ("example.com", "", ""));
+    let (_, trace) = pwm.generate_with_trace("example.com", key).unwrap();
+    assert_eq!(trace.parts()[0].hasher_input(), (key.to_owned() + "example.com").as_bytes());
+}
+
+#[test]
+fn strict_whitespace(){
+    use passwordmaker_rs::{GenerationError, UseLeetWhenGenerating};
+    let untrimmed = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "user ", "", 8, "", "").unwrap();
+    assert!(untrimmed.generate("example.com", "password").is_ok());
+    let strict = untrimmed.with_strict_whitespace(true);
+    assert!(matches!(strict.generate("example.com", "password"), Err(GenerationError::UntrimmedField { field : "username" })));
+    assert_eq!(strict.generate("example.com", "password").unwrap_err().to_string(), "The username starts or ends with whitespace.");
+    let untrimmed = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "user", "\tmod", 8, "", "").unwrap()
+        .with_strict_whitespace(true);
+    assert!(matches!(untrimmed.generate("example.com", "password"), Err(GenerationError::UntrimmedField { field : "modifier" })));
+    //Whitespace within a field, and empty fields, are fine.
+    let trimmed = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "john doe", "", 8, "", "").unwrap();
+    assert_eq!(
+        trimmed.clone().with_strict_whitespace(true).generate("example.com", "password").unwrap(),
+        trimmed.generate("example.com", "password").unwrap()
+    );
+}
+
+#[test]
+fn grapheme_iter(){
+    use passwordmaker_rs::{LeetLevel, UseLeetWhenGenerating};
+    let pwm = Pwm::new(HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, "abcdefghé👍🏽", "", "", 70, "pre", "suffix").unwrap();
+    let graphemes : Vec<_> = pwm.grapheme_iter("example.com", "password").unwrap().collect();
+    assert_eq!(graphemes.len(), 70);
+    assert_eq!(graphemes.concat(), pwm.generate("example.com", "password").unwrap());
+    //Affixes longer than the password are cut, same as in generate.
+    let short = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 4, "pre", "suffix").unwrap();
+    assert_eq!(short.grapheme_iter("example.com", "password").unwrap().collect::<String>(), short.generate("example.com", "password").unwrap());
+    //Leet after generation is applied to the whole password.
+    let leet = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::After { level : LeetLevel::Nine }, "abcdefghij", "", "", 30, "", "").unwrap();
+    assert_eq!(leet.grapheme_iter("example.com", "password").unwrap().collect::<String>(), leet.generate("example.com", "password").unwrap());
+    assert!(pwm.grapheme_iter("", "password").is_err());
+}
+
+#[test]
+fn length_unit(){
+    use passwordmaker_rs::{LengthUnit, UseLeetWhenGenerating};
+    //The suffix is 2 grapheme clusters, 3 code points, and 5 UTF-16 code units long.
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 6, "", "x👍🏽").unwrap();
+    let graphemes = pwm.generate("example.com", "password").unwrap();
+    let code_points = pwm.clone().with_length_unit(LengthUnit::CodePoints).generate("example.com", "password").unwrap();
+    let utf16 = pwm.clone().with_length_unit(LengthUnit::Utf16CodeUnits).generate("example.com", "password").unwrap();
+    assert_eq!(graphemes.chars().count(), 7);
+    assert!(graphemes.ends_with("x👍🏽"));
+    assert_eq!(code_points.chars().count(), 6);
+    assert_eq!(&code_points[..3], &graphemes[..3]);
+    assert!(code_points.ends_with("x👍🏽"));
+    assert_eq!(utf16.encode_utf16().count(), 6);
+    assert_eq!(&utf16[..1], &graphemes[..1]);
+    assert!(utf16.ends_with("x👍🏽"));
+    //Javascript would cut the skin tone modifier in half. Half a code point is dropped instead.
+    let short = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 4, "", "x👍🏽").unwrap().with_length_unit(LengthUnit::Utf16CodeUnits);
+    assert_eq!(short.generate("example.com", "password").unwrap(), "x👍");
+    assert_eq!(short.grapheme_iter("example.com", "password").unwrap().collect::<String>(), "x👍");
+    assert_ne!(pwm.settings_fingerprint(), pwm.clone().with_length_unit(LengthUnit::CodePoints).settings_fingerprint());
+    assert_eq!(pwm.settings_fingerprint(), pwm.clone().with_length_unit(LengthUnit::GraphemeClusters).settings_fingerprint());
+}
+
+#[test]
+fn write_to(){
+    use passwordmaker_rs::{GenerationError, LeetLevel, UseLeetWhenGenerating};
+    use std::fmt::Write;
+    struct ByteCounter(usize);
+    impl Write for ByteCounter {
+        fn write_str(&mut self, s : &str) -> std::fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+    struct Failing;
+    impl Write for Failing {
+        fn write_str(&mut self, _ : &str) -> std::fmt::Result {
+            Err(std::fmt::Error)
+        }
+    }
+    let pwm = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, "abc😀👍🏽", "user", "", 40, "pre", "suf").unwrap();
+    let expected = pwm.generate("example.com", "password").unwrap();
+    //Appends to what is already there.
+    let mut target = String::from("password: ");
+    pwm.write_to("example.com", "password", &mut target).unwrap();
+    assert_eq!(target, format!("password: {}", expected));
+    let mut counter = ByteCounter(0);
+    pwm.write_to("example.com", "password", &mut counter).unwrap();
+    assert_eq!(counter.0, expected.len());
+    let leet = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::After { level : LeetLevel::Nine }, "abcdefghij", "", "", 30, "", "").unwrap();
+    let mut target = String::new();
+    leet.write_to("example.com", "password", &mut target).unwrap();
+    assert_eq!(target, leet.generate("example.com", "password").unwrap());
+    assert!(matches!(pwm.write_to("example.com", "password", &mut Failing), Err(GenerationError::WriteFailed)));
+    assert!(matches!(pwm.write_to("example.com", "", &mut target), Err(GenerationError::MissingMasterPassword)));
+}
+
+#[test]
+fn compare_algorithms(){
+    use passwordmaker_rs::{GenerationError, PreparedCharset};
+    let characters = PreparedCharset::new("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789").unwrap();
+    let groups = Pwm::compare_algorithms("example.com", "password", &characters, 12).unwrap();
+    assert_eq!(groups.values().map(Vec::len).sum::<usize>(), HashAlgorithm::all().len());
+    let group_of = |algorithm| groups.values().find(|group| group.contains(&algorithm)).unwrap();
+    assert!(!group_of(HashAlgorithm::Md5).contains(&HashAlgorithm::HmacMd5));
+    //Each group's key is the password its algorithms generate.
+    for (password, group) in &groups {
+        for algorithm in group {
+            let pwm = Pwm::new_with_prepared_charset(*algorithm, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, &characters, "", "", 12, "", "");
+            assert_eq!(&pwm.generate("example.com", "password").unwrap(), password);
+        }
+    }
+    //With a single character password of a binary charset, collisions are guaranteed.
+    let binary = PreparedCharset::new("01").unwrap();
+    let groups = Pwm::compare_algorithms("example.com", "password", &binary, 1).unwrap();
+    assert!(groups.values().any(|group| group.len() > 1));
+    assert!(matches!(Pwm::compare_algorithms("example.com", "", &binary, 1), Err(GenerationError::MissingMasterPassword)));
+    //Without the extended hash functions, RIPEMD-128 and Tiger are left out.
+    let groups = PasswordMaker::<Hashes>::compare_algorithms("example.com", "password", &characters, 12).unwrap();
+    assert_eq!(groups.values().map(Vec::len).sum::<usize>(), HashAlgorithm::all().len() - 4);
+}
+
+/// Passwords that fit into a single hash take a faster path in `generate`. `generate_with_trace` always takes the general one.
+#[test]
+fn single_part_fast_path_matches_general_path(){
+    use passwordmaker_rs::{OutputEncoding, OutputPadding, UseLeetWhenGenerating};
+    let algorithms = [HashAlgorithm::Md5, HashAlgorithm::HmacMd4, HashAlgorithm::Ripemd128, HashAlgorithm::Md5Version06, HashAlgorithm::HmacMd5Version06, HashAlgorithm::Sha1];
+    let charsets = ["01", "abcdefghij", "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./", "aé👍🏽"];
+    for algorithm in algorithms.iter().copied() {
+        for characters in charsets.iter().copied() {
+            let pwm = Pwm::new(algorithm, UseLeetWhenGenerating::NotAtAll, characters, "", "", 1, "", "").unwrap();
+            //Up to the full length of a single part. At full length, leading zeros make some hashes fall back to the general path.
+            for password_length in [1, 8, pwm.max_single_hash_length()].iter().copied() {
+                let pwm = Pwm::new(algorithm, UseLeetWhenGenerating::NotAtAll, characters, "", "", password_length, "", "").unwrap();
+                let variants = [
+                    pwm.clone(),
+                    pwm.clone().with_output_padding(OutputPadding::LeadingZeros),
+                    pwm.clone().with_output_encoding(OutputEncoding::Hex),
+                    pwm.clone().with_output_encoding(OutputEncoding::Base58),
+                ];
+                for variant in &variants {
+                    for i in 0..40 {
+                        let data = format!("example{}.com", i);
+                        let password = variant.generate(data.as_str(), "password").unwrap();
+                        assert_eq!(password, variant.generate_with_trace(data.as_str(), "password").unwrap().0, "{:?} {:?} {}", algorithm, characters, data);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn hasher_output_can_be_generic_array(){
+    use passwordmaker_rs::UseLeetWhenGenerating;
+    struct GenericMd5;
+    impl Hasher for GenericMd5 {
+        type Output = digest::Output<md5::Md5>;
+        fn hash(data : &[u8]) -> Self::Output {
+            md5::Md5::digest(data)
+        }
+    }
+    struct GenericHashes;
+    impl HasherList for GenericHashes {
+        type MD4 = Md4;
+        type MD5 = GenericMd5;
+        type SHA1 = Sha1;
+        type SHA256 = Sha256;
+        type RIPEMD160 = RipeMD160;
+    }
+    for algorithm in &[HashAlgorithm::Md5, HashAlgorithm::HmacMd5, HashAlgorithm::Md5Version06, HashAlgorithm::HmacMd5Version06] {
+        let generic = PasswordMaker::<GenericHashes>::new(*algorithm, UseLeetWhenGenerating::NotAtAll, "abcdefghij", "user", "", 40, "", "").unwrap();
+        let array = Pwm::new(*algorithm, UseLeetWhenGenerating::NotAtAll, "abcdefghij", "user", "", 40, "", "").unwrap();
+        assert_eq!(generic.generate("example.com", "password").unwrap(), array.generate("example.com", "password").unwrap());
+    }
+}
+
+#[test]
+#[should_panic(expected = "The hash function returned 20 bytes, but the algorithm needs 16 bytes.")]
+fn hasher_output_of_wrong_length_panics(){
+    struct MixedUpHashes;
+    impl HasherList for MixedUpHashes {
+        type MD4 = Md4;
+        type MD5 = Sha1;
+        type SHA1 = Sha1;
+        type SHA256 = Sha256;
+        type RIPEMD160 = RipeMD160;
+    }
+    let pwm = PasswordMaker::<MixedUpHashes>::new(HashAlgorithm::Md5, passwordmaker_rs::UseLeetWhenGenerating::NotAtAll, "abcdefghij", "", "", 8, "", "").unwrap();
+    let _ = pwm.generate("example.com", "password");
+}
+
+/// Implementations of the deprecated per-algorithm traits must keep compiling.
+#[allow(deprecated)]
+mod legacy_marker_traits {
+    impl passwordmaker_rs::Md4 for super::Md4{}
+    impl passwordmaker_rs::Md5 for super::Md5{}
+    impl passwordmaker_rs::Sha1 for super::Sha1{}
+    impl passwordmaker_rs::Sha256 for super::Sha256{}
+    impl passwordmaker_rs::Ripemd160 for super::RipeMD160{}
+}
+
+#[test]
+fn exclude_shell_metacharacters(){
+    use passwordmaker_rs::{exclude_from_charset, UseLeetWhenGenerating};
+    let all = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+    let shell_metacharacters = "`!$&*()|;<>?\\\"' ";
+    let safe = exclude_from_charset(all, shell_metacharacters);
+    assert_eq!(safe, "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789~@#%^_-+={}[]:,./");
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, safe.as_str(), "", "", 30, "", "").unwrap();
+    assert_eq!(pwm.charset_len(), 94 - 15);
+    let password = pwm.generate("example.com", "password").unwrap();
+    assert!(!password.chars().any(|c| shell_metacharacters.contains(c)));
+    //Multi-byte grapheme clusters are excluded as a whole, and nothing else.
+    assert_eq!(exclude_from_charset("a😀b👨‍👩‍👧c👨", "👨‍👩‍👧😀"), "abc👨");
+}
+
+#[test]
+fn leet_level_zero_only_converts_to_lower_case(){
+    use passwordmaker_rs::UseLeetWhenGenerating;
+    let characters = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    let make = |use_leet| Pwm::new(HashAlgorithm::Sha256, use_leet, characters, "User", "", 40, "", "").unwrap();
+    let not_at_all = make(UseLeetWhenGenerating::NotAtAll);
+    let before = make(UseLeetWhenGenerating::Before { level : LeetLevel::Zero });
+    let after = make(UseLeetWhenGenerating::After { level : LeetLevel::Zero });
+    let lower_case_username = Pwm::new(HashAlgorithm::Sha256, UseLeetWhenGenerating::NotAtAll, characters, "user", "", 40, "", "").unwrap();
+    assert_eq!(before.generate("Example.COM", "Password").unwrap(), lower_case_username.generate("example.com", "password").unwrap());
+    assert_ne!(before.generate("Example.COM", "Password").unwrap(), not_at_all.generate("Example.COM", "Password").unwrap());
+    assert_eq!(after.generate("Example.COM", "Password").unwrap(), not_at_all.generate("Example.COM", "Password").unwrap().to_lowercase());
+    assert_eq!(LeetLevel::Zero.replacement_table().concat(), "abcdefghijklmnopqrstuvwxyz");
+}
+
+#[test]
+fn input_encoding_utf16_discard_high_byte(){
+    use passwordmaker_rs::{InputEncoding, UseLeetWhenGenerating};
+    let characters = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    let utf8 = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, characters, "jürgen", "", 20, "", "").unwrap();
+    let utf16 = utf8.clone().with_input_encoding(InputEncoding::Utf16DiscardHighByte);
+    let (utf8_password, utf8_trace) = utf8.generate_with_trace("exämple.com", "pässword€").unwrap();
+    let (utf16_password, utf16_trace) = utf16.generate_with_trace("exämple.com", "pässword€").unwrap();
+    assert_ne!(utf8_password, utf16_password);
+    assert_eq!(utf16_password, utf16.generate("exämple.com", "pässword€").unwrap());
+    assert_eq!(utf8_trace.parts()[0].hasher_input(), "pässword€exämple.comjürgen".as_bytes());
+    //ä, ü and € each become a single byte, the lower byte of their UTF-16 code unit.
+    let truncated : Vec<u8> = "pässword€exämple.comjürgen".encode_utf16().map(|unit| unit.to_le_bytes()[0]).collect();
+    assert_eq!(utf16_trace.parts()[0].hasher_input(), truncated.as_slice());
+    assert_eq!(utf16_trace.parts()[0].hash(), md5::Md5::digest(&truncated).as_slice());
+    //ASCII input is the same in both encodings.
+    let ascii = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, characters, "user", "", 20, "", "").unwrap();
+    assert_eq!(ascii.generate("example.com", "password").unwrap(), ascii.clone().with_input_encoding(InputEncoding::Utf16DiscardHighByte).generate("example.com", "password").unwrap());
+    assert_ne!(utf8.settings_fingerprint(), utf16.settings_fingerprint());
+    //HMAC algorithms are not affected.
+    let hmac = Pwm::new(HashAlgorithm::HmacMd5, UseLeetWhenGenerating::NotAtAll, characters, "jürgen", "", 20, "", "").unwrap();
+    assert_eq!(hmac.generate("exämple.com", "pässword€").unwrap(), hmac.clone().with_input_encoding(InputEncoding::Utf16DiscardHighByte).generate("exämple.com", "pässword€").unwrap());
+}
+
+#[test]
+fn debug_hash_input_modern_part(){
+    use passwordmaker_rs::UseLeetWhenGenerating;
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "0123456789", "user", "mod", 100, "", "").unwrap();
+    let part = pwm.debug_hash_input("example.com", "password", 1).unwrap();
+    assert_eq!(part.hmac_key(), None);
+    assert_eq!(part.hasher_input(), b"password\n1example.comusermod");
+    assert_eq!(part.hash(), md5::Md5::digest(b"password\n1example.comusermod").as_slice());
+    let (_, trace) = pwm.generate_with_trace("example.com", "password").unwrap();
+    for (i, traced) in trace.parts().iter().enumerate() {
+        let part = pwm.debug_hash_input("example.com", "password", i).unwrap();
+        assert_eq!(part.hasher_input(), traced.hasher_input());
+        assert_eq!(part.hash(), traced.hash());
+        assert_eq!(part.digits(), traced.digits());
+    }
+    //Beyond the parts the password needs.
+    assert_eq!(pwm.debug_hash_input("example.com", "password", 1000).unwrap().hasher_input(), b"password\n1000example.comusermod");
+    assert!(pwm.debug_hash_input("", "password", 0).is_err());
+}
+
+#[test]
+fn debug_hash_input_v06_part(){
+    use passwordmaker_rs::UseLeetWhenGenerating;
+    let pwm = Pwm::new(HashAlgorithm::HmacMd5Version06, UseLeetWhenGenerating::Before { level : LeetLevel::One }, "ignored", "", "", 100, "", "").unwrap();
+    let part = pwm.debug_hash_input("exämple.com", "Pässwörd", 2).unwrap();
+    //Leet lower-cases and replaces some letters, then each character is truncated to the lower byte of its UTF-16 code unit.
+    assert_eq!(part.hmac_key(), Some(&b"p\xe4ssw\xf6rd\n2"[..]));
+    assert_eq!(part.hasher_input(), b"3x\xe4mp13.c0m");
+    let (_, trace) = pwm.generate_with_trace("exämple.com", "Pässwörd").unwrap();
+    assert_eq!(trace.parts()[2].hmac_key(), part.hmac_key());
+    assert_eq!(trace.parts()[2].hash(), part.hash());
+}
+
+#[test]
+fn max_affix_length(){
+    use passwordmaker_rs::{GenerationError, OutputSpec, UseLeetWhenGenerating};
+    let huge_prefix = "👍🏽".repeat(1_000_000);
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 20, huge_prefix.as_str(), "suf").unwrap();
+    let capped = pwm.clone().with_max_affix_length(Some(16));
+    assert!(matches!(capped.generate("example.com", "password"), Err(GenerationError::AffixTooLong { field : "prefix", max : 16 })));
+    assert_eq!(capped.generate("example.com", "password").unwrap_err().to_string(), "The prefix is longer than 16 characters.");
+    //Without a cap, the prefix takes up the whole password, except for the suffix.
+    assert_eq!(pwm.generate("example.com", "password").unwrap(), "👍🏽".repeat(17) + "suf");
+    let short_prefix = "👍🏽".repeat(16);
+    let short = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 20, short_prefix.as_str(), "suf").unwrap();
+    assert_eq!(short.clone().with_max_affix_length(Some(16)).generate("example.com", "password").unwrap(), short.generate("example.com", "password").unwrap());
+    assert!(matches!(short.clone().with_max_affix_length(Some(2)).generate("example.com", "password"), Err(GenerationError::AffixTooLong { field : "prefix", max : 2 })));
+    let no_prefix = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 20, "", "suffix").unwrap().with_max_affix_length(Some(5));
+    assert!(matches!(no_prefix.generate("example.com", "password"), Err(GenerationError::AffixTooLong { field : "suffix", max : 5 })));
+    //The affixes of batch specs are checked as well.
+    let spec = OutputSpec::new("abcdef", 20, huge_prefix.as_str(), "").unwrap();
+    let batch = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 20, "", "").unwrap().with_max_affix_length(Some(16));
+    assert!(matches!(batch.generate_batch("example.com", "password", &[spec]), Err(GenerationError::AffixTooLong { field : "prefix", max : 16 })));
+    //The affixes of the PasswordMaker itself are replaced by those of the specs, so they are not checked.
+    let short_spec = OutputSpec::new("abcdef", 20, "", "").unwrap();
+    assert_eq!(
+        capped.generate_batch("example.com", "password", &[short_spec]).unwrap(),
+        batch.generate_batch("example.com", "password", &[short_spec]).unwrap()
+    );
+}
+
+#[test]
+fn validate_all_reports_every_problem(){
+    use passwordmaker_rs::{GenerationError, UseLeetWhenGenerating};
+    let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "ab\u{7}c", " user", "modifier\n", 8, "long prefix", "suffix").unwrap()
+        .with_strict_whitespace(true)
+        .with_strict_charset(true)
+        .with_max_affix_length(Some(6))
+        .with_min_key_length(8);
+    let errors = pwm.validate_all("", "").unwrap_err();
+    assert_eq!(errors.len(), 6);
+    assert!(matches!(errors[0], GenerationError::MissingTextToUse));
+    assert!(matches!(errors[1], GenerationError::MissingMasterPassword));
+    assert!(matches!(errors[2], GenerationError::UntrimmedField { field : "username" }));
+    assert!(matches!(errors[3], GenerationError::UntrimmedField { field : "modifier" }));
+    assert!(matches!(errors[4], GenerationError::AffixTooLong { field : "prefix", max : 6 }));
+    assert!(matches!(errors[5], GenerationError::NonPrintableCharsetEntry { index : 2 }));
+    //The first error is the one generate() reports.
+    assert!(matches!(pwm.generate("", ""), Err(GenerationError::MissingTextToUse)));
+    let errors = pwm.validate_all("example.com", "short").unwrap_err();
+    assert!(matches!(errors[0], GenerationError::MasterPasswordTooShort { min : 8, got : 5 }));
+    assert_eq!(errors.len(), 5);
+    let fine = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abc", "", "", 8, "", "").unwrap();
+    assert!(fine.validate_all("example.com", "password").is_ok());
+}
+
+#[test]
+fn affix_budget(){
+    use passwordmaker_rs::{LengthUnit, UseLeetWhenGenerating};
+    let budget = |length, prefix, suffix| Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", length, prefix, suffix).unwrap().affix_budget();
+    let parts = |budget : passwordmaker_rs::AffixBudget| (budget.prefix_kept(), budget.prefix_length(), budget.generated_length(), budget.suffix_kept(), budget.suffix_length());
+    //Everything fits.
+    assert_eq!(parts(budget(10, "pre", "suffix")), (3, 3, 1, 6, 6));
+    //The suffix alone exceeds the length.
+    assert_eq!(parts(budget(3, "pre", "suffix")), (0, 3, 0, 3, 6));
+    //Prefix and suffix together exceed the length.
+    assert_eq!(parts(budget(8, "pre", "suffix")), (2, 3, 0, 6, 6));
+    assert_eq!(parts(budget(8, "👍🏽👍🏽", "")), (2, 2, 6, 0, 0));
+    //The budget matches what generate() puts out.
+    for length in 0..12 {
+        let pwm = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "0123456789", "", "", length, "pre", "suffix").unwrap();
+        let budget = pwm.affix_budget();
+        let password = pwm.generate("example.com", "password").unwrap();
+        assert_eq!(password.len(), budget.prefix_kept() + budget.generated_length() + budget.suffix_kept());
+        assert!(password.starts_with(&"pre"[..budget.prefix_kept()]));
+        assert!(password.ends_with(&"suffix"[..budget.suffix_kept()]));
+    }
+    let code_points = Pwm::new(HashAlgorithm::Md5, UseLeetWhenGenerating::NotAtAll, "abcdef", "", "", 3, "", "👍🏽👍🏽").unwrap().with_length_unit(LengthUnit::CodePoints);
+    assert_eq!(parts(code_points.affix_budget()), (0, 0, 0, 3, 4));
+}
+
+#[test]
+fn require_fast_base(){
+    use passwordmaker_rs::{GenerationError, OutputEncoding, OutputSpec, UseLeetWhenGenerating};
+    let large_charset : String = ('\u{100}'..).take(150).collect();
+    assert_eq!(passwordmaker_rs::graphemes(&large_charset).count(), 150);
+    let pwm = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, &large_charset, "", "", 20, "", "").unwrap();
+    let fast = pwm.clone().with_require_fast_base(true);
+    assert!(pwm.generate("example.com", "password").is_ok());
+    assert!(matches!(fast.generate("example.com", "password"), Err(GenerationError::SlowCharset)));
+    assert!(matches!(fast.validate_all("example.com", "password").unwrap_err().as_slice(), [GenerationError::SlowCharset]));
+    //16 byte hashes and encodings that don't convert bases are never rejected.
+    let md5 = Pwm::new(HashAlgorithm::HmacMd5, UseLeetWhenGenerating::NotAtAll, &large_charset, "", "", 20, "", "").unwrap().with_require_fast_base(true);
+    assert!(md5.generate("example.com", "password").is_ok());
+    assert!(fast.clone().with_output_encoding(OutputEncoding::Base64).generate("example.com", "password").is_ok());
+    //Batch specs are checked as well.
+    let default_charset = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789`~!@#$%^&*()_-+={}|[]\\:\";'<>?,./";
+    let fast_default = Pwm::new(HashAlgorithm::HmacSha256, UseLeetWhenGenerating::NotAtAll, default_charset, "", "", 20, "", "").unwrap().with_require_fast_base(true);
+    let spec = OutputSpec::new(&large_charset, 20, "", "").unwrap();
+    assert!(matches!(fast_default.generate_batch("example.com", "password", &[spec]), Err(GenerationError::SlowCharset)));
+    if passwordmaker_rs::is_base_precomputed(94, 32) {
+        assert!(fast_default.generate("example.com", "password").is_ok());
+        //The slow charset of the PasswordMaker itself is not used by the batch, so it is not checked.
+        let default_spec = OutputSpec::new(default_charset, 20, "", "").unwrap();
+        assert_eq!(
+            fast.generate_batch("example.com", "password", &[default_spec]).unwrap(),
+            vec![fast_default.generate("example.com", "password").unwrap()]
+        );
+    }
+}
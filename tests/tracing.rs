@@ -0,0 +1,88 @@
+//! Tests for the spans emitted with the `tracing` feature.
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+use passwordmaker_rs::{HashAlgorithm, Hasher, HasherList, PasswordMaker, UrlParsing, UseLeetWhenGenerating};
+use digest::Digest;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+struct Md5;
+struct Unused16;
+struct Unused20;
+struct Unused32;
+impl Hasher for Md5 {
+    type Output = [u8;16];
+    fn hash(input : &[u8]) -> Self::Output { md5::Md5::digest(input).into() }
+}
+impl Hasher for Unused16 {
+    type Output = [u8;16];
+    fn hash(_ : &[u8]) -> Self::Output { unimplemented!() }
+}
+impl Hasher for Unused20 {
+    type Output = [u8;20];
+    fn hash(_ : &[u8]) -> Self::Output { unimplemented!() }
+}
+impl Hasher for Unused32 {
+    type Output = [u8;32];
+    fn hash(_ : &[u8]) -> Self::Output { unimplemented!() }
+}
+struct Hashers;
+impl HasherList for Hashers {
+    type MD4 = Unused16;
+    type MD5 = Md5;
+    type SHA1 = Unused20;
+    type SHA256 = Unused32;
+    type RIPEMD160 = Unused20;
+}
+
+/// Records each span as its name, followed by its fields.
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    spans : Arc<Mutex<Vec<String>>>,
+}
+
+struct FieldWriter<'a>(&'a mut String);
+
+impl Visit for FieldWriter<'_> {
+    fn record_debug(&mut self, field : &Field, value : &dyn std::fmt::Debug) {
+        self.0.push_str(&format!(" {}={:?}", field.name(), value));
+    }
+}
+
+impl Subscriber for SpanRecorder {
+    fn enabled(&self, _ : &Metadata<'_>) -> bool { true }
+    fn new_span(&self, span : &Attributes<'_>) -> Id {
+        let mut spans = self.spans.lock().unwrap();
+        let mut description = span.metadata().name().to_owned();
+        span.record(&mut FieldWriter(&mut description));
+        spans.push(description);
+        Id::from_u64(spans.len() as u64)
+    }
+    fn record(&self, _ : &Id, _ : &Record<'_>) {}
+    fn record_follows_from(&self, _ : &Id, _ : &Id) {}
+    fn event(&self, _ : &Event<'_>) {}
+    fn enter(&self, _ : &Id) {}
+    fn exit(&self, _ : &Id) {}
+}
+
+#[test]
+fn spans_do_not_contain_secrets(){
+    let recorder = SpanRecorder::default();
+    let pwm = PasswordMaker::<Hashers>::new(HashAlgorithm::HmacMd5, UseLeetWhenGenerating::NotAtAll, "abcdefghij", "", "", 40, "", "").unwrap();
+    let password = tracing::subscriber::with_default(recorder.clone(), || {
+        pwm.generate_from_url(&UrlParsing::default(), "https://www.secretsite.com/login", "hunter2").unwrap()
+    });
+    let spans = recorder.spans.lock().unwrap();
+    assert_eq!(spans[0], "make_used_text_from_url protocol=Ignored userinfo=false subdomains=false domain=true port_path=false");
+    assert_eq!(spans[1], "validate_input algorithm=HmacMd5 length=40 min_key_length=1");
+    assert_eq!(spans[2], "hash_password_part part=0 algorithm=HmacMd5 base=10");
+    assert_eq!(spans[3], "hash_password_part part=1 algorithm=HmacMd5 base=10");
+    assert_eq!(spans.len(), 1 + 1 + pwm.estimated_parts());
+    for span in spans.iter() {
+        assert!(!span.contains("secretsite"), "{}", span);
+        assert!(!span.contains("hunter2"), "{}", span);
+        assert!(!span.contains(&password[..10]), "{}", span);
+    }
+}